@@ -0,0 +1,156 @@
+//! TARGET2 business days, on which SEPA direct debits are due and settled.
+//!
+//! TARGET2 is closed on Saturdays and Sundays, New Year's Day, Good Friday, Easter Monday,
+//! 1 May, Christmas Day and 26 December.
+
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+
+pub fn is_business_day(date: NaiveDate) -> bool {
+    !is_closing_day(date)
+}
+
+pub fn is_closing_day(date: NaiveDate) -> bool {
+    let easter = easter_sunday(date.year());
+    matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+        || matches!(
+            (date.month(), date.day()),
+            (1, 1) | (5, 1) | (12, 25) | (12, 26)
+        )
+        || date == easter - Duration::days(2)
+        || date == easter + Duration::days(1)
+}
+
+/// `date` itself on business days, otherwise the first business day after it.
+pub fn next_business_day(date: NaiveDate) -> NaiveDate {
+    let mut date = date;
+    while is_closing_day(date) {
+        date += Duration::days(1);
+    }
+    date
+}
+
+/// `date` itself on business days, otherwise the last business day before it.
+pub fn previous_business_day(date: NaiveDate) -> NaiveDate {
+    let mut date = date;
+    while is_closing_day(date) {
+        date -= Duration::days(1);
+    }
+    date
+}
+
+/// The business day `days` business days after `date`, or before it for negative `days`.
+/// `date` itself does not count, so with `days = 0` it is `date` even on a closing day.
+pub fn add_business_days(date: NaiveDate, days: i64) -> NaiveDate {
+    let step = Duration::days(days.signum());
+    let mut date = date;
+    for _ in 0..days.abs() {
+        date += step;
+        while is_closing_day(date) {
+            date += step;
+        }
+    }
+    date
+}
+
+/// `date` moved by whole months, the day is capped at the end of shorter months.
+pub fn add_months(date: NaiveDate, months: i32) -> NaiveDate {
+    let month0 = date.year() * 12 + date.month0() as i32 + months;
+    let (year, month) = (month0.div_euclid(12), month0.rem_euclid(12) as u32 + 1);
+    (1..=date.day())
+        .rev()
+        .find_map(|day| NaiveDate::from_ymd_opt(year, month, day))
+        .unwrap_or(date)
+}
+
+/// Easter Sunday of the Gregorian calendar (anonymous Gregorian algorithm).
+pub fn easter_sunday(year: i32) -> NaiveDate {
+    let a = year.rem_euclid(19);
+    let b = year.div_euclid(100);
+    let c = year.rem_euclid(100);
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32).expect("Easter is a valid date")
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use chrono::NaiveDate;
+
+    use super::{
+        add_business_days, add_months, easter_sunday, is_business_day, next_business_day,
+        previous_business_day,
+    };
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::from_str(value).unwrap()
+    }
+
+    #[test]
+    fn test_easter_sunday() {
+        assert_eq!(date("2000-04-23"), easter_sunday(2000));
+        assert_eq!(date("2008-03-23"), easter_sunday(2008));
+        assert_eq!(date("2019-04-21"), easter_sunday(2019));
+        assert_eq!(date("2024-03-31"), easter_sunday(2024));
+        assert_eq!(date("2038-04-25"), easter_sunday(2038));
+    }
+
+    #[test]
+    fn test_closing_days() {
+        for closed in [
+            "2024-01-01",
+            "2024-03-29",
+            "2024-04-01",
+            "2024-05-01",
+            "2024-12-25",
+            "2024-12-26",
+            "2024-06-29",
+            "2024-06-30",
+        ] {
+            assert!(!is_business_day(date(closed)), "{} is closed", closed);
+        }
+        // national holidays do not close TARGET2
+        assert!(is_business_day(date("2024-10-03")));
+        assert!(is_business_day(date("2024-12-24")));
+        assert!(is_business_day(date("2024-05-09")));
+    }
+
+    #[test]
+    fn test_shift_to_business_days() {
+        assert_eq!(date("2024-04-02"), next_business_day(date("2024-03-29")));
+        assert_eq!(
+            date("2024-03-28"),
+            previous_business_day(date("2024-04-01"))
+        );
+        assert_eq!(date("2024-06-28"), next_business_day(date("2024-06-28")));
+    }
+
+    #[test]
+    fn test_add_business_days() {
+        assert_eq!(date("2024-04-02"), add_business_days(date("2024-03-28"), 1));
+        assert_eq!(date("2024-04-03"), add_business_days(date("2024-03-30"), 2));
+        assert_eq!(
+            date("2024-03-28"),
+            add_business_days(date("2024-04-02"), -1)
+        );
+        assert_eq!(date("2024-12-30"), add_business_days(date("2024-12-23"), 3));
+        assert_eq!(date("2024-03-30"), add_business_days(date("2024-03-30"), 0));
+    }
+
+    #[test]
+    fn test_add_months_caps_the_day() {
+        assert_eq!(date("2024-02-29"), add_months(date("2024-01-31"), 1));
+        assert_eq!(date("2023-02-28"), add_months(date("2023-03-31"), -1));
+        assert_eq!(date("2025-01-15"), add_months(date("2023-12-15"), 13));
+    }
+}
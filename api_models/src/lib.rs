@@ -3,5 +3,6 @@ extern crate serde_derive;
 extern crate serde;
 extern crate serde_json;
 
+pub mod calendar;
 pub mod models;
 pub use validator;
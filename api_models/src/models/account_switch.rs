@@ -0,0 +1,36 @@
+use validator::Validate;
+
+use super::{BankAccount, Creditor};
+
+/// Moves the ACTIVE mandates of the account `from` to the account `to`.
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize, Validate)]
+pub struct AccountSwitch {
+    /// `api_id` of the old account.
+    pub from: uuid::Uuid,
+
+    /// A stored account (with `api_id`) or a new one, which is created.
+    #[validate]
+    pub to: BankAccount,
+}
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct AccountSwitchResult {
+    pub from: BankAccount,
+
+    pub to: BankAccount,
+
+    /// The moved mandates, their creditors need to be told about the new account.
+    pub mandates: Vec<SwitchedMandate>,
+}
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct SwitchedMandate {
+    pub api_id: uuid::Uuid,
+
+    pub display_name: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unique_reference: Option<String>,
+
+    pub creditor: Creditor,
+}
@@ -2,24 +2,73 @@ use lazy_static::lazy_static;
 use regex::Regex;
 use validator::Validate;
 
+use super::iban::{validate_iban, Iban};
+
 lazy_static! {
-     static ref RE_IBAN: Regex = Regex::new(r"^[A-Z]{2}[0-9]{2}(?:[ ]?[0-9]{4}){4}(?:[ ]?[0-9]{1,2})?$").unwrap();
      static ref RE_BIC: Regex = Regex::new(r"[A-Z]{6,6}[A-Z2-9][A-NP-Z0-9]([A-Z0-9]{3,3}){0,1}").unwrap();
 }
 
 
+/// A debtor account of the user, or the creditor account of an export.
 #[derive(Clone, Debug, PartialEq, PartialOrd, Ord, Eq, Default, Serialize, Deserialize, Validate)]
 pub struct BankAccount {
 
+    /// Set for stored accounts, mandates reference their account by it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_id: Option<uuid::Uuid>,
+
     #[validate(length(min = 2))]
     pub institution: String,
     
-    #[validate(regex(path = "RE_IBAN"))]
+    #[validate(custom = "validate_iban")]
     pub iban: String,
     
     #[serde(skip_serializing_if = "Option::is_none")]
     #[validate(regex(path = "RE_BIC"))]
     pub bic: Option<String>,
 
+    /// Defaults to the name of the user.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(length(min = 2, max = 70))]
+    pub holder_name: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(length(min = 1, max = 50))]
+    pub nickname: Option<String>,
+
+    /// The account new mandates are collected from unless another one is chosen.
+    #[serde(default)]
+    pub is_primary: bool,
+
 }
 
+impl BankAccount {
+    /// Brings IBAN and BIC into their canonical form (no spaces, upper case), drops blank names.
+    pub fn normalize(&mut self) {
+        if let Ok(iban) = Iban::parse(&self.iban) {
+            self.iban = iban.to_string();
+        }
+        self.bic = self
+            .bic
+            .as_ref()
+            .map(|bic| bic.trim().to_uppercase())
+            .filter(|bic| !bic.is_empty());
+        self.holder_name = trimmed(&self.holder_name);
+        self.nickname = trimmed(&self.nickname);
+    }
+
+    /// Nickname if there is one, otherwise institution and IBAN.
+    pub fn label(&self) -> String {
+        match &self.nickname {
+            Some(nickname) => format!("{} ({})", nickname, self.iban),
+            None => format!("{} - {}", self.institution, self.iban),
+        }
+    }
+}
+
+fn trimmed(value: &Option<String>) -> Option<String> {
+    value
+        .as_ref()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
@@ -0,0 +1,19 @@
+/// Settings the web UI needs to sign in at the OpenID Connect provider, served by `GET /api/config`.
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct ClientConfig {
+    pub issuer: String,
+
+    pub client_id: String,
+
+    /// Requested as `audience` parameter, providers like Auth0 only issue JWT access tokens with it.
+    pub audience: String,
+
+    pub scope: String,
+
+    pub authorization_endpoint: String,
+
+    pub token_endpoint: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_session_endpoint: Option<String>,
+}
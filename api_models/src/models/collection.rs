@@ -0,0 +1,83 @@
+use std::borrow::Cow;
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use strum_macros::{EnumString, IntoStaticStr};
+use validator::{Validate, ValidationError};
+
+use super::BankAccount;
+
+/// SEPA direct debit sequence type of a single collection.
+#[derive(Clone, IntoStaticStr, EnumString, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum SequenceType {
+    FRST,
+    #[default]
+    RCUR,
+    OOFF,
+    FNAL,
+}
+
+/// Supported ISO 20022 pain.008 message versions.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Pain008Version {
+    #[default]
+    #[serde(rename = "pain.008.001.02")]
+    V02,
+    #[serde(rename = "pain.008.001.08")]
+    V08,
+}
+
+impl Pain008Version {
+    pub fn message_name(&self) -> &'static str {
+        match self {
+            Pain008Version::V02 => "pain.008.001.02",
+            Pain008Version::V08 => "pain.008.001.08",
+        }
+    }
+}
+
+/// One amount to be collected from the debtor of a stored mandate.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Validate)]
+pub struct Collection {
+    pub mandate_api_id: uuid::Uuid,
+
+    #[validate(custom = "validate_amount")]
+    pub amount: Decimal,
+
+    pub collection_date: NaiveDate,
+
+    #[serde(default)]
+    pub sequence_type: SequenceType,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(length(min = 1, max = 140))]
+    pub remittance_information: Option<String>,
+}
+
+/// Request for a pain.008 direct debit initiation file.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Validate)]
+pub struct DirectDebitExport {
+    #[serde(default)]
+    pub version: Pain008Version,
+
+    /// Account the collected amounts are credited to.
+    #[validate]
+    pub creditor_account: BankAccount,
+
+    #[validate(length(min = 1))]
+    #[validate]
+    pub collections: Vec<Collection>,
+}
+
+/// Direct debit amounts are positive and booked in cents.
+pub fn validate_amount(amount: &Decimal) -> Result<(), ValidationError> {
+    if amount.is_sign_positive() && !amount.is_zero() && amount.normalize().scale() <= 2 {
+        Ok(())
+    } else {
+        let mut error = ValidationError::new("amount");
+        error.message = Some(Cow::from(
+            "amount must be positive with at most two decimal places",
+        ));
+        Err(error)
+    }
+}
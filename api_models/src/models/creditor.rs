@@ -1,5 +1,6 @@
 use validator::Validate;
 
+use super::creditor_identifier::{validate_creditor_identifier, CreditorIdentifier};
 use super::Address;
 
 #[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize, Validate)]
@@ -8,9 +9,37 @@ pub struct Creditor {
     #[validate(length(min = 2))]
     pub name: String,
     
-    #[validate(length(min = 2))]
+    #[validate(custom = "validate_creditor_identifier")]
     pub sepa_identifier: Option<String>,
     
     #[validate]
     pub address: Address,
 }
+
+impl Creditor {
+    pub fn creditor_identifier(&self) -> Option<CreditorIdentifier> {
+        self.sepa_identifier
+            .as_ref()
+            .and_then(|ci| CreditorIdentifier::parse(ci).ok())
+    }
+
+    /// Compares creditors by the national identifier of their CI, ignoring the business code.
+    pub fn is_same_creditor(&self, other: &Creditor) -> bool {
+        match (self.creditor_identifier(), other.creditor_identifier()) {
+            (Some(ci), Some(other_ci)) => ci.is_same_creditor(&other_ci),
+            _ => false,
+        }
+    }
+
+    /// Brings the creditor identifier into its canonical form, an empty one is dropped.
+    pub fn normalize(&mut self) {
+        self.sepa_identifier = self
+            .sepa_identifier
+            .as_ref()
+            .map(|ci| match CreditorIdentifier::parse(ci) {
+                Ok(ci) => ci.to_string(),
+                Err(_) => ci.trim().to_string(),
+            })
+            .filter(|ci| !ci.is_empty());
+    }
+}
@@ -0,0 +1,178 @@
+use std::{borrow::Cow, fmt, str::FromStr};
+
+use validator::ValidationError;
+
+use super::iban::{is_sepa_country, mod97};
+
+const MAX_LENGTH: usize = 35;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CreditorIdentifierError {
+    TooShort,
+    TooLong,
+    InvalidCharacter(char),
+    UnsupportedCountry(String),
+    InvalidCheckDigits,
+    InvalidChecksum,
+}
+
+impl fmt::Display for CreditorIdentifierError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CreditorIdentifierError::TooShort => write!(f, "creditor identifier is too short"),
+            CreditorIdentifierError::TooLong => write!(
+                f,
+                "creditor identifier must not be longer than {} characters",
+                MAX_LENGTH
+            ),
+            CreditorIdentifierError::InvalidCharacter(c) => {
+                write!(f, "creditor identifier contains invalid character '{}'", c)
+            }
+            CreditorIdentifierError::UnsupportedCountry(country) => {
+                write!(f, "{} is not a SEPA country", country)
+            }
+            CreditorIdentifierError::InvalidCheckDigits => {
+                write!(f, "creditor identifier check digits must be numeric")
+            }
+            CreditorIdentifierError::InvalidChecksum => {
+                write!(f, "creditor identifier checksum wrong")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CreditorIdentifierError {}
+
+/// SEPA Creditor Identifier (CI), e.g. `DE98ZZZ09999999999`:
+/// country code, two check digits, three character creditor business code and the national identifier.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CreditorIdentifier(String);
+
+impl CreditorIdentifier {
+    pub fn parse(input: &str) -> Result<CreditorIdentifier, CreditorIdentifierError> {
+        let ci: String = input
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| c.to_ascii_uppercase())
+            .collect();
+        if ci.len() < 8 {
+            return Err(CreditorIdentifierError::TooShort);
+        }
+        if let Some(c) = ci.chars().find(|c| !c.is_ascii_alphanumeric()) {
+            return Err(CreditorIdentifierError::InvalidCharacter(c));
+        }
+        if ci.len() > MAX_LENGTH {
+            return Err(CreditorIdentifierError::TooLong);
+        }
+        let country = &ci[0..2];
+        if !is_sepa_country(country) {
+            return Err(CreditorIdentifierError::UnsupportedCountry(
+                country.to_string(),
+            ));
+        }
+        if !ci[2..4].chars().all(|c| c.is_ascii_digit()) {
+            return Err(CreditorIdentifierError::InvalidCheckDigits);
+        }
+        // the creditor business code (positions 5-7) is not part of the checksum
+        let rearranged = format!("{}{}", &ci[7..], &ci[0..4]);
+        if mod97(&rearranged) != 1 {
+            return Err(CreditorIdentifierError::InvalidChecksum);
+        }
+        Ok(CreditorIdentifier(ci))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn country_code(&self) -> &str {
+        &self.0[0..2]
+    }
+
+    pub fn check_digits(&self) -> &str {
+        &self.0[2..4]
+    }
+
+    pub fn business_code(&self) -> &str {
+        &self.0[4..7]
+    }
+
+    pub fn national_id(&self) -> &str {
+        &self.0[7..]
+    }
+
+    /// Two identifiers belong to the same creditor when country and national identifier match,
+    /// the business code only distinguishes business lines of one creditor.
+    pub fn is_same_creditor(&self, other: &CreditorIdentifier) -> bool {
+        self.country_code() == other.country_code() && self.national_id() == other.national_id()
+    }
+}
+
+impl FromStr for CreditorIdentifier {
+    type Err = CreditorIdentifierError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        CreditorIdentifier::parse(s)
+    }
+}
+
+impl fmt::Display for CreditorIdentifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Custom `validator` function for creditor identifier fields.
+pub fn validate_creditor_identifier(ci: &str) -> Result<(), ValidationError> {
+    CreditorIdentifier::parse(ci).map(|_| ()).map_err(|e| {
+        let mut error = ValidationError::new("creditor_identifier");
+        error.message = Some(Cow::from(e.to_string()));
+        error
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{CreditorIdentifier, CreditorIdentifierError};
+
+    #[test]
+    fn test_parse_valid_identifiers() {
+        let ci = CreditorIdentifier::parse("de98 zzz0 9999 9999 99").unwrap();
+        assert_eq!("DE98ZZZ09999999999", ci.as_str());
+        assert_eq!("DE", ci.country_code());
+        assert_eq!("98", ci.check_digits());
+        assert_eq!("ZZZ", ci.business_code());
+        assert_eq!("09999999999", ci.national_id());
+
+        assert!(CreditorIdentifier::parse("NL36ZZZ0123456789").is_ok());
+        assert!(CreditorIdentifier::parse("AT34ZZZ12345678").is_ok());
+    }
+
+    #[test]
+    fn test_business_code_is_not_part_of_checksum() {
+        let zzz = CreditorIdentifier::parse("DE98ZZZ09999999999").unwrap();
+        let abc = CreditorIdentifier::parse("DE98ABC09999999999").unwrap();
+        assert_ne!(zzz, abc);
+        assert!(zzz.is_same_creditor(&abc));
+    }
+
+    #[test]
+    fn test_parse_invalid_identifiers() {
+        assert_eq!(
+            Err(CreditorIdentifierError::InvalidChecksum),
+            CreditorIdentifier::parse("DE97ZZZ09999999999")
+        );
+        assert_eq!(
+            Err(CreditorIdentifierError::UnsupportedCountry("US".to_string())),
+            CreditorIdentifier::parse("US98ZZZ09999999999")
+        );
+        assert_eq!(
+            Err(CreditorIdentifierError::InvalidCheckDigits),
+            CreditorIdentifier::parse("DEXXZZZ09999999999")
+        );
+        assert_eq!(
+            Err(CreditorIdentifierError::TooShort),
+            CreditorIdentifier::parse("DE98ZZZ")
+        );
+    }
+}
@@ -0,0 +1,215 @@
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+
+use chrono::{Duration, NaiveDate};
+use strum_macros::{EnumString, IntoStaticStr};
+
+use super::{Mandate, Scheme, Status, Transaction};
+use crate::calendar::add_months;
+
+/// Weeks in which the debtor of a CORE mandate gets a refund without giving a reason.
+pub const REFUND_WEEKS: i64 = 8;
+
+/// Months in which a debit without a valid mandate can be disputed.
+pub const UNAUTHORISED_MONTHS: i32 = 13;
+
+/// Banks use this end to end id when the creditor did not give one.
+const NOT_PROVIDED: &str = "NOTPROVIDED";
+
+#[derive(Clone, IntoStaticStr, EnumString, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum DebitAlert {
+    /// Collected after the mandate was canceled.
+    CollectedAfterCancellation,
+    /// Collected after the mandate was deleted.
+    CollectedAfterDeletion,
+}
+
+/// How long a recorded debit can still be refunded or disputed.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DebitDeadline {
+    pub transaction: Transaction,
+    pub display_name: String,
+    pub mandate_status: Status,
+    /// The day the account was debited, the deadlines count from it.
+    pub debit_date: NaiveDate,
+    /// Last day of the refund without a reason, `None` for B2B mandates.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refund_until: Option<NaiveDate>,
+    /// Last day to dispute the debit as unauthorised.
+    pub unauthorised_until: NaiveDate,
+    pub refundable: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alert: Option<DebitAlert>,
+}
+
+/// Deadlines of the debits collected under `mandates` that can still be disputed on `today`,
+/// alerts first, then the latest debits first. Debits already returned are left out.
+/// `ended_on` holds the day a mandate was canceled or deleted, a mandate missing from it
+/// raises an alert for all its debits.
+pub fn debit_deadlines(
+    transactions: &[Transaction],
+    mandates: &[Mandate],
+    ended_on: &HashMap<uuid::Uuid, NaiveDate>,
+    today: NaiveDate,
+) -> Vec<DebitDeadline> {
+    let mandates: HashMap<uuid::Uuid, &Mandate> = mandates.iter().map(|m| (m.api_id, m)).collect();
+    let returned: HashSet<&str> = transactions
+        .iter()
+        .filter(|t| t.is_reversal)
+        .filter_map(|t| t.end_to_end_id.as_deref())
+        .filter(|id| *id != NOT_PROVIDED)
+        .collect();
+    let mut deadlines = Vec::new();
+    for transaction in transactions.iter().filter(|t| !t.is_reversal) {
+        let mandate = match transaction.mandate_api_id.and_then(|id| mandates.get(&id)) {
+            Some(mandate) => *mandate,
+            None => continue,
+        };
+        if matches!(transaction.end_to_end_id.as_deref(), Some(id) if returned.contains(id)) {
+            continue;
+        }
+        let debit_date = transaction.value_date.unwrap_or(transaction.booking_date);
+        let unauthorised_until = add_months(debit_date, UNAUTHORISED_MONTHS);
+        if unauthorised_until < today {
+            continue;
+        }
+        let refund_until = Some(debit_date + Duration::weeks(REFUND_WEEKS))
+            .filter(|_| mandate.scheme == Scheme::CORE);
+        let alert = match mandate.status {
+            Status::CANCELED => Some(DebitAlert::CollectedAfterCancellation),
+            Status::DELETED => Some(DebitAlert::CollectedAfterDeletion),
+            _ => None,
+        }
+        .filter(|_| match ended_on.get(&mandate.api_id) {
+            Some(ended) => debit_date > *ended,
+            None => true,
+        });
+        deadlines.push(DebitDeadline {
+            transaction: transaction.clone(),
+            display_name: mandate.display_name.clone(),
+            mandate_status: mandate.status,
+            debit_date,
+            refund_until,
+            unauthorised_until,
+            refundable: matches!(refund_until, Some(until) if today <= until),
+            alert,
+        });
+    }
+    deadlines.sort_by_key(|d| (d.alert.is_none(), Reverse(d.debit_date)));
+    deadlines
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+    use std::str::FromStr;
+
+    use chrono::NaiveDate;
+    use rust_decimal::Decimal;
+
+    use super::{debit_deadlines, DebitAlert};
+    use crate::models::{Mandate, Scheme, Status, Transaction};
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::from_str(value).unwrap()
+    }
+
+    fn debit(mandate: &Mandate, booking_date: &str, end_to_end_id: &str) -> Transaction {
+        Transaction {
+            api_id: uuid::Uuid::new_v4(),
+            mandate_api_id: Some(mandate.api_id),
+            booking_date: date(booking_date),
+            value_date: None,
+            amount: Decimal::new(-4250, 2),
+            currency: "EUR".to_string(),
+            is_reversal: false,
+            mandate_reference: None,
+            creditor_identifier: None,
+            creditor_name: None,
+            debtor_iban: None,
+            end_to_end_id: Some(end_to_end_id.to_string()),
+            remittance_information: None,
+        }
+    }
+
+    fn mandate(status: Status) -> Mandate {
+        Mandate {
+            api_id: uuid::Uuid::new_v4(),
+            display_name: "Gym".to_string(),
+            status,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_refund_and_unauthorised_deadlines() {
+        let core = mandate(Status::ACTIVE);
+        let b2b = Mandate {
+            api_id: uuid::Uuid::new_v4(),
+            scheme: Scheme::B2B,
+            ..core.clone()
+        };
+        let transactions = vec![
+            debit(&core, "2023-01-31", "E2E-1"),
+            debit(&core, "2022-12-01", "E2E-2"),
+            debit(&b2b, "2023-02-15", "E2E-3"),
+            debit(&core, "2021-11-30", "E2E-4"),
+        ];
+        let deadlines = debit_deadlines(
+            &transactions,
+            &[core, b2b],
+            &HashMap::new(),
+            date("2023-03-01"),
+        );
+        // the debit of 2021 can no longer be disputed
+        assert_eq!(3, deadlines.len());
+        assert_eq!(date("2023-02-15"), deadlines[0].debit_date);
+        assert_eq!(None, deadlines[0].refund_until);
+        assert!(!deadlines[0].refundable);
+        assert_eq!(Some(date("2023-03-28")), deadlines[1].refund_until);
+        assert_eq!(date("2024-02-29"), deadlines[1].unauthorised_until);
+        assert!(deadlines[1].refundable);
+        assert_eq!(Some(date("2023-01-26")), deadlines[2].refund_until);
+        assert!(!deadlines[2].refundable);
+        assert!(deadlines.iter().all(|d| d.alert.is_none()));
+    }
+
+    #[test]
+    fn test_alerts_for_debits_after_the_end_of_the_mandate() {
+        let canceled = mandate(Status::CANCELED);
+        let deleted = mandate(Status::DELETED);
+        let mut returned = debit(&canceled, "2023-02-10", "E2E-3");
+        returned.is_reversal = true;
+        let transactions = vec![
+            debit(&canceled, "2023-01-02", "E2E-1"),
+            debit(&canceled, "2023-02-01", "E2E-2"),
+            debit(&canceled, "2023-02-03", "E2E-3"),
+            returned,
+            debit(&deleted, "2022-06-01", "E2E-4"),
+        ];
+        let ended_on = vec![(canceled.api_id, date("2023-01-15"))]
+            .into_iter()
+            .collect();
+        let deadlines = debit_deadlines(
+            &transactions,
+            &[canceled, deleted],
+            &ended_on,
+            date("2023-03-01"),
+        );
+        let alerts: Vec<(NaiveDate, Option<DebitAlert>)> =
+            deadlines.iter().map(|d| (d.debit_date, d.alert)).collect();
+        assert_eq!(
+            vec![
+                (
+                    date("2023-02-01"),
+                    Some(DebitAlert::CollectedAfterCancellation)
+                ),
+                (date("2022-06-01"), Some(DebitAlert::CollectedAfterDeletion)),
+                (date("2023-01-02"), None),
+            ],
+            alerts
+        );
+    }
+}
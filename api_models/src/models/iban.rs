@@ -0,0 +1,283 @@
+use std::{borrow::Cow, fmt, str::FromStr};
+
+use validator::ValidationError;
+
+/// IBAN registry entries (ISO 13616) for the SEPA countries:
+/// country code, total IBAN length and BBAN structure.
+/// The structure is a sequence of `<length><type>` segments where the type is
+/// `n` (digits), `a` (upper case letters) or `c` (alphanumeric).
+const REGISTRY: &[(&str, usize, &str)] = &[
+    ("AD", 24, "4n4n12c"),
+    ("AT", 20, "5n11n"),
+    ("BE", 16, "3n7n2n"),
+    ("BG", 22, "4a4n2n8c"),
+    ("CH", 21, "5n12c"),
+    ("CY", 28, "3n5n16c"),
+    ("CZ", 24, "4n6n10n"),
+    ("DE", 22, "8n10n"),
+    ("DK", 18, "4n9n1n"),
+    ("EE", 20, "2n14n"),
+    ("ES", 24, "4n4n1n1n10n"),
+    ("FI", 18, "3n11n"),
+    ("FR", 27, "5n5n11c2n"),
+    ("GB", 22, "4a6n8n"),
+    ("GI", 23, "4a15c"),
+    ("GR", 27, "3n4n16c"),
+    ("HR", 21, "7n10n"),
+    ("HU", 28, "3n4n1n15n1n"),
+    ("IE", 22, "4a6n8n"),
+    ("IS", 26, "4n2n6n10n"),
+    ("IT", 27, "1a5n5n12c"),
+    ("LI", 21, "5n12c"),
+    ("LT", 20, "5n11n"),
+    ("LU", 20, "3n13c"),
+    ("LV", 21, "4a13c"),
+    ("MC", 27, "5n5n11c2n"),
+    ("MT", 31, "4a5n18c"),
+    ("NL", 18, "4a10n"),
+    ("NO", 15, "4n6n1n"),
+    ("PL", 28, "8n16n"),
+    ("PT", 25, "4n4n11n2n"),
+    ("RO", 24, "4a16c"),
+    ("SE", 24, "3n16n1n"),
+    ("SI", 19, "5n8n2n"),
+    ("SK", 24, "4n6n10n"),
+    ("SM", 27, "1a5n5n12c"),
+    ("VA", 22, "3n15n"),
+];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum IbanError {
+    TooShort,
+    InvalidCharacter(char),
+    UnsupportedCountry(String),
+    InvalidLength {
+        country: String,
+        expected: usize,
+        actual: usize,
+    },
+    InvalidStructure(String),
+    InvalidChecksum,
+}
+
+impl fmt::Display for IbanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IbanError::TooShort => write!(f, "IBAN is too short"),
+            IbanError::InvalidCharacter(c) => write!(f, "IBAN contains invalid character '{}'", c),
+            IbanError::UnsupportedCountry(country) => {
+                write!(f, "{} is not a SEPA country", country)
+            }
+            IbanError::InvalidLength {
+                country,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "{} IBANs must be {} characters, got {}",
+                country, expected, actual
+            ),
+            IbanError::InvalidStructure(country) => {
+                write!(f, "account number format is not valid for {}", country)
+            }
+            IbanError::InvalidChecksum => write!(f, "IBAN checksum wrong"),
+        }
+    }
+}
+
+impl std::error::Error for IbanError {}
+
+/// A validated IBAN, stored in electronic format (no spaces, upper case).
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Iban(String);
+
+impl Iban {
+    pub fn parse(input: &str) -> Result<Iban, IbanError> {
+        let iban: String = input
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| c.to_ascii_uppercase())
+            .collect();
+        if iban.len() < 5 {
+            return Err(IbanError::TooShort);
+        }
+        if let Some(c) = iban.chars().find(|c| !c.is_ascii_alphanumeric()) {
+            return Err(IbanError::InvalidCharacter(c));
+        }
+        let country = &iban[0..2];
+        let (_, length, structure) = registry_entry(country)
+            .ok_or_else(|| IbanError::UnsupportedCountry(country.to_string()))?;
+        if iban.len() != length {
+            return Err(IbanError::InvalidLength {
+                country: country.to_string(),
+                expected: length,
+                actual: iban.len(),
+            });
+        }
+        if !iban[2..4].chars().all(|c| c.is_ascii_digit())
+            || !matches_structure(&iban[4..], structure)
+        {
+            return Err(IbanError::InvalidStructure(country.to_string()));
+        }
+        let rearranged = format!("{}{}", &iban[4..], &iban[0..4]);
+        if mod97(&rearranged) != 1 {
+            return Err(IbanError::InvalidChecksum);
+        }
+        Ok(Iban(iban))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn country_code(&self) -> &str {
+        &self.0[0..2]
+    }
+
+    pub fn check_digits(&self) -> &str {
+        &self.0[2..4]
+    }
+
+    pub fn bban(&self) -> &str {
+        &self.0[4..]
+    }
+
+    /// Groups of four characters separated by a space, as printed on paper.
+    pub fn to_print_format(&self) -> String {
+        self.0
+            .as_bytes()
+            .chunks(4)
+            .map(|chunk| std::str::from_utf8(chunk).unwrap_or_default())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+impl FromStr for Iban {
+    type Err = IbanError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Iban::parse(s)
+    }
+}
+
+impl fmt::Display for Iban {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Custom `validator` function for IBAN fields, the message carries the exact reason.
+pub fn validate_iban(iban: &str) -> Result<(), ValidationError> {
+    Iban::parse(iban).map(|_| ()).map_err(|e| {
+        let mut error = ValidationError::new("iban");
+        error.message = Some(Cow::from(e.to_string()));
+        error
+    })
+}
+
+pub(crate) fn is_sepa_country(country: &str) -> bool {
+    registry_entry(country).is_some()
+}
+
+/// ISO 7064 MOD 97-10 remainder of an alphanumeric string, letters count as A = 10 ... Z = 35.
+pub(crate) fn mod97(value: &str) -> u32 {
+    value.chars().fold(0, |acc, c| {
+        let digit = c.to_digit(36).unwrap_or_default();
+        if digit > 9 {
+            (acc * 100 + digit) % 97
+        } else {
+            (acc * 10 + digit) % 97
+        }
+    })
+}
+
+fn registry_entry(country: &str) -> Option<(&'static str, usize, &'static str)> {
+    REGISTRY.iter().find(|(cc, _, _)| *cc == country).copied()
+}
+
+fn matches_structure(bban: &str, structure: &str) -> bool {
+    let mut rest = bban;
+    let mut count = 0;
+    for c in structure.chars() {
+        if let Some(d) = c.to_digit(10) {
+            count = count * 10 + d as usize;
+            continue;
+        }
+        if rest.len() < count {
+            return false;
+        }
+        let (segment, tail) = rest.split_at(count);
+        let valid = match c {
+            'n' => segment.chars().all(|c| c.is_ascii_digit()),
+            'a' => segment.chars().all(|c| c.is_ascii_uppercase()),
+            _ => segment.chars().all(|c| c.is_ascii_alphanumeric()),
+        };
+        if !valid {
+            return false;
+        }
+        rest = tail;
+        count = 0;
+    }
+    rest.is_empty()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Iban, IbanError};
+
+    #[test]
+    fn test_parse_valid_ibans() {
+        for iban in [
+            "DE89370400440532013000",
+            "GB82WEST12345698765432",
+            "NL91ABNA0417164300",
+            "FR1420041010050500013M02606",
+            "IT60X0542811101000000123456",
+            "BE68539007547034",
+            "AT611904300234573201",
+            "CH9300762011623852957",
+        ] {
+            assert_eq!(Ok(iban), Iban::parse(iban).as_ref().map(|i| i.as_str()));
+        }
+    }
+
+    #[test]
+    fn test_parse_normalises_spacing_and_case() {
+        let iban = Iban::parse(" de89 3704 0044 0532 0130 00 ").unwrap();
+        assert_eq!("DE89370400440532013000", iban.as_str());
+        assert_eq!("DE", iban.country_code());
+        assert_eq!("89", iban.check_digits());
+        assert_eq!("DE89 3704 0044 0532 0130 00", iban.to_print_format());
+    }
+
+    #[test]
+    fn test_parse_wrong_checksum() {
+        assert_eq!(
+            Err(IbanError::InvalidChecksum),
+            Iban::parse("DE88370400440532013000")
+        );
+    }
+
+    #[test]
+    fn test_parse_wrong_length() {
+        let err = Iban::parse("DE8937040044053201300").unwrap_err();
+        assert_eq!("DE IBANs must be 22 characters, got 21", err.to_string());
+    }
+
+    #[test]
+    fn test_parse_wrong_structure_and_country() {
+        assert_eq!(
+            Err(IbanError::InvalidStructure("NL".to_string())),
+            Iban::parse("NL91ABN00417164300")
+        );
+        assert_eq!(
+            Err(IbanError::UnsupportedCountry("US".to_string())),
+            Iban::parse("US12345678901234")
+        );
+        assert_eq!(
+            Err(IbanError::InvalidCharacter('-')),
+            Iban::parse("DE89-3704-0044-0532-0130-00")
+        );
+    }
+}
@@ -0,0 +1,68 @@
+use chrono::NaiveDateTime;
+use strum_macros::{EnumString, IntoStaticStr};
+use validator::Validate;
+
+/// Periodic tasks of the backend, each one can also be started by an admin.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, IntoStaticStr, EnumString, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum Job {
+    /// The emails users opted in to.
+    SendNotifications,
+    /// Removes deleted mandates once nothing can be disputed under them, and old webhook deliveries.
+    PurgeDeleted,
+    /// Expires active mandates without a collection in the last 36 months.
+    ExpireMandates,
+}
+
+pub const JOBS: [Job; 3] = [Job::SendNotifications, Job::PurgeDeleted, Job::ExpireMandates];
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, IntoStaticStr, EnumString, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum JobRunStatus {
+    Running,
+    Succeeded,
+    Failed,
+}
+
+/// A run of a job, times are UTC.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct JobRun {
+    pub id: i32,
+    pub job: Job,
+    /// The slot of the schedule, or the time a manual run was requested.
+    pub scheduled_for: NaiveDateTime,
+    /// Subject of the admin who started the run, `None` for scheduled runs.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub triggered_by: Option<String>,
+    pub status: JobRunStatus,
+    pub started: NaiveDateTime,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished: Option<NaiveDateTime>,
+    /// What the run did, or why it failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct JobInfo {
+    pub job: Job,
+    pub description: String,
+    /// Cron expression with seconds, in UTC.
+    pub schedule: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_run: Option<NaiveDateTime>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_run: Option<JobRun>,
+}
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize, Validate)]
+pub struct JobRunQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub job: Option<Job>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 1, max = 500))]
+    pub limit: Option<u64>,
+}
@@ -0,0 +1,126 @@
+use strum_macros::{EnumString, IntoStaticStr};
+
+use super::Status;
+
+/// Languages the letter templates are written in, the first one is the fallback.
+pub const LETTER_LANGUAGES: [&str; 2] = ["en", "de"];
+
+/// Built-in letters to the creditor of a mandate.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, IntoStaticStr, EnumString, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum LetterKind {
+    /// Revokes the mandate.
+    Revocation,
+    /// Asks the creditor to collect from the current account of the mandate.
+    AccountChange,
+    /// Asks for the refund of an unauthorised debit.
+    RefundRequest,
+}
+
+impl LetterKind {
+    pub const ALL: [LetterKind; 3] = [
+        LetterKind::Revocation,
+        LetterKind::AccountChange,
+        LetterKind::RefundRequest,
+    ];
+
+    /// The letter to send after a mandate changed to `status`.
+    pub fn for_status(status: Status) -> Option<LetterKind> {
+        match status {
+            Status::CANCELED => Some(LetterKind::Revocation),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LetterFormat {
+    #[default]
+    Txt,
+    Html,
+    Pdf,
+}
+
+impl LetterFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            LetterFormat::Txt => "text/plain; charset=utf-8",
+            LetterFormat::Html => "text/html; charset=utf-8",
+            LetterFormat::Pdf => "application/pdf",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            LetterFormat::Txt => "txt",
+            LetterFormat::Html => "html",
+            LetterFormat::Pdf => "pdf",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct LetterQuery {
+    #[serde(default)]
+    pub format: LetterFormat,
+
+    /// Defaults to the preferred language of the user.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lang: Option<String>,
+
+    /// Imported debit a refund request is about.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction: Option<uuid::Uuid>,
+}
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct MandateFormQuery {
+    /// Defaults to the preferred language of the user.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lang: Option<String>,
+}
+
+/// The template language for a requested one, English when there are no templates for it.
+pub fn letter_language(requested: Option<&str>) -> &'static str {
+    requested
+        .map(|lang| lang.trim().to_lowercase())
+        .and_then(|lang| LETTER_LANGUAGES.iter().find(|l| **l == lang))
+        .unwrap_or(&LETTER_LANGUAGES[0])
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::{letter_language, LetterKind, LetterQuery};
+    use crate::models::Status;
+
+    #[test]
+    fn test_letter_language() {
+        assert_eq!("de", letter_language(Some("DE")));
+        assert_eq!("en", letter_language(Some("fr")));
+        assert_eq!("en", letter_language(None));
+    }
+
+    #[test]
+    fn test_letter_kind_names() {
+        let name: &'static str = LetterKind::AccountChange.into();
+        assert_eq!("account_change", name);
+        assert_eq!(Ok(LetterKind::RefundRequest), LetterKind::from_str("refund_request"));
+        assert_eq!(
+            "\"refund_request\"",
+            serde_json::to_string(&LetterKind::RefundRequest).unwrap()
+        );
+        assert_eq!(Some(LetterKind::Revocation), LetterKind::for_status(Status::CANCELED));
+        assert_eq!(None, LetterKind::for_status(Status::ACTIVE));
+    }
+
+    #[test]
+    fn test_letter_query_defaults() {
+        let query: LetterQuery = serde_json::from_str("{}").unwrap();
+        assert_eq!("txt", query.format.extension());
+        assert_eq!(None, query.lang);
+    }
+}
@@ -1,12 +1,23 @@
-use validator::Validate;
+use std::borrow::Cow;
 
-use super::BankAccount;
+use chrono::NaiveDate;
+use strum_macros::{EnumString, IntoStaticStr};
+use validator::{Validate, ValidationError};
+
+use super::{tag::validate_tags, BankAccount, CollectionSchedule};
+use crate::calendar::add_months;
+
+/// Months without a collection after which a mandate lapses.
+pub const EXPIRY_MONTHS: i32 = 36;
 
 #[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize, Validate)]
+#[validate(schema(function = "validate_scheme", skip_on_field_errors = false))]
 pub struct Mandate {
 
     pub api_id: uuid::Uuid,
     
+    #[serde(default)]
+    #[validate(custom = "validate_tags")]
     pub tags: Vec<String>,
 
     pub status: crate::models::Status,
@@ -26,4 +37,128 @@ pub struct Mandate {
     #[validate]
     pub bank_account: BankAccount,
 
+    #[serde(default)]
+    pub scheme: Scheme,
+
+    #[serde(default)]
+    pub payment_type: PaymentType,
+
+    /// Date the debtor signed the mandate, the creation date when unknown.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature_date: Option<NaiveDate>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(length(min = 2, max = 70))]
+    pub signature_place: Option<String>,
+
+    /// Who signed, when it is not the holder of the bank account.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(length(min = 2, max = 70))]
+    pub account_holder: Option<String>,
+
+    /// Expected collections, for the forecast of upcoming debits.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate]
+    pub schedule: Option<CollectionSchedule>,
+
+    /// Day of the latest collection, taken from imported debits or entered by the user.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_collection_date: Option<NaiveDate>,
+
+}
+
+/// SEPA direct debit scheme the mandate was signed for.
+#[derive(Clone, IntoStaticStr, EnumString, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum Scheme {
+    #[default]
+    CORE,
+    /// Business to business, without a refund right of the debtor.
+    B2B,
+}
+
+#[derive(Clone, IntoStaticStr, EnumString, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum PaymentType {
+    #[default]
+    Recurrent,
+    /// Allows a single collection only.
+    OneOff,
+}
+
+impl Mandate {
+    /// Mandates of one creditor may use different creditor business codes.
+    pub fn has_same_creditor(&self, other: &Mandate) -> bool {
+        self.creditor.is_same_creditor(&other.creditor)
+    }
+}
+
+/// Last day a mandate last used on `last_used` can be collected under, it expires the day after.
+/// A mandate that was never used counts from its signature.
+pub fn usable_until(last_used: NaiveDate) -> NaiveDate {
+    add_months(last_used, EXPIRY_MONTHS)
+}
+
+/// B2B mandates are only offered by creditors with a creditor identifier.
+pub fn validate_scheme(mandate: &Mandate) -> Result<(), ValidationError> {
+    if mandate.scheme == Scheme::B2B && mandate.creditor.sepa_identifier.is_none() {
+        let mut error = ValidationError::new("scheme");
+        error.message = Some(Cow::from(
+            "B2B mandates need the creditor identifier of the creditor",
+        ));
+        return Err(error);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use validator::Validate;
+
+    use chrono::NaiveDate;
+
+    use super::{usable_until, Mandate, PaymentType, Scheme};
+
+    #[test]
+    fn test_b2b_needs_creditor_identifier() {
+        let mut mandate = Mandate {
+            scheme: Scheme::B2B,
+            ..Default::default()
+        };
+        let errors = mandate.validate().unwrap_err();
+        assert!(errors.errors().contains_key("__all__"));
+
+        mandate.creditor.sepa_identifier = Some("DE98ZZZ09999999999".to_string());
+        let errors = mandate.validate().unwrap_err();
+        assert!(!errors.errors().contains_key("__all__"));
+    }
+
+    #[test]
+    fn test_metadata_defaults() {
+        let mandate: Mandate = serde_json::from_str(
+            r#"{"api_id": "7e5b3f3e-3c1a-4c4b-9d1e-1f2a3b4c5d6e", "status": "ACTIVE",
+                "unique_reference": null, "display_name": "Gas",
+                "creditor": {"name": "Stadtwerke", "sepa_identifier": null,
+                    "address": {"street": "", "house_number": "", "zip": "", "place": ""}},
+                "bank_account": {"institution": "Bank", "iban": "DE89370400440532013000"}}"#,
+        )
+        .unwrap();
+        assert_eq!(Scheme::CORE, mandate.scheme);
+        assert_eq!(PaymentType::Recurrent, mandate.payment_type);
+        assert_eq!(None, mandate.signature_date);
+        assert_eq!(Ok(PaymentType::OneOff), PaymentType::from_str("one_off"));
+        assert_eq!(
+            "\"one_off\"",
+            serde_json::to_string(&PaymentType::OneOff).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_usable_for_36_months_after_the_last_collection() {
+        let date = |value: &str| NaiveDate::from_str(value).unwrap();
+        assert_eq!(date("2026-03-15"), usable_until(date("2023-03-15")));
+        assert_eq!(date("2023-02-28"), usable_until(date("2020-02-29")));
+    }
 }
@@ -0,0 +1,19 @@
+use chrono::NaiveDateTime;
+
+/// One changed field of a mandate with its value before and after the change.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MandateChange {
+    pub date_changed: NaiveDateTime,
+
+    /// Subject of the user who made the change.
+    pub changed_by: String,
+
+    pub field: String,
+
+    /// `None` for the initial values of a new mandate.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_value: Option<serde_json::Value>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_value: Option<serde_json::Value>,
+}
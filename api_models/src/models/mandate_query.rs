@@ -0,0 +1,85 @@
+use super::{Mandate, Status};
+
+pub const DEFAULT_PAGE_SIZE: u64 = 50;
+pub const MAX_PAGE_SIZE: u64 = 200;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MandateSort {
+    #[default]
+    DateCreated,
+    DisplayName,
+    CreditorName,
+}
+
+/// Filters of the mandate listing, all of them optional and combined with AND.
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct MandateQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<Status>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+
+    /// Part of the creditor name, case insensitive.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub creditor: Option<String>,
+
+    /// IBAN of the debtor account, spaces and case don't matter.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iban: Option<String>,
+
+    /// Free text searched in display name, mandate reference and creditor name.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub q: Option<String>,
+
+    #[serde(default)]
+    pub sort: MandateSort,
+
+    #[serde(default)]
+    pub desc: bool,
+
+    /// `next_cursor` of the previous page.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<uuid::Uuid>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u64>,
+}
+
+impl MandateQuery {
+    pub fn page_size(&self) -> u64 {
+        self.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE)
+    }
+
+    /// The same query continuing after `cursor`.
+    pub fn next_page(&self, cursor: uuid::Uuid) -> MandateQuery {
+        MandateQuery {
+            cursor: Some(cursor),
+            ..self.clone()
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct MandatePage {
+    pub items: Vec<Mandate>,
+
+    /// Set when there are more mandates, pass it as `cursor` to get them.
+    pub next_cursor: Option<uuid::Uuid>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::{MandateQuery, DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE};
+
+    #[test]
+    fn test_page_size() {
+        let mut query = MandateQuery::default();
+        assert_eq!(DEFAULT_PAGE_SIZE, query.page_size());
+        query.limit = Some(0);
+        assert_eq!(1, query.page_size());
+        query.limit = Some(10_000);
+        assert_eq!(MAX_PAGE_SIZE, query.page_size());
+    }
+}
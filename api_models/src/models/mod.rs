@@ -1,12 +1,46 @@
+pub mod account_switch;
+pub use self::account_switch::{AccountSwitch, AccountSwitchResult, SwitchedMandate};
 pub mod address;
 pub use self::address::Address;
 pub mod bank_account;
 pub use self::bank_account::BankAccount;
+pub mod client_config;
+pub use self::client_config::ClientConfig;
+pub mod collection;
+pub use self::collection::{Collection, DirectDebitExport, Pain008Version, SequenceType};
 pub mod creditor;
 pub use self::creditor::Creditor;
+pub mod creditor_identifier;
+pub use self::creditor_identifier::{CreditorIdentifier, CreditorIdentifierError};
+pub mod deadline;
+pub use self::deadline::{DebitAlert, DebitDeadline};
+pub mod iban;
+pub use self::iban::{Iban, IbanError};
+pub mod job;
+pub use self::job::{Job, JobInfo, JobRun, JobRunQuery, JobRunStatus};
+pub mod letter;
+pub use self::letter::{LetterFormat, LetterKind, LetterQuery, MandateFormQuery};
 pub mod mandate;
-pub use self::mandate::Mandate;
+pub use self::mandate::{Mandate, PaymentType, Scheme};
+pub mod mandate_history;
+pub use self::mandate_history::MandateChange;
+pub mod mandate_query;
+pub use self::mandate_query::{MandatePage, MandateQuery, MandateSort};
+pub mod problem;
+pub use self::problem::Problem;
+pub mod schedule;
+pub use self::schedule::{
+    CollectionSchedule, Forecast, ForecastQuery, Frequency, MonthlyTotal, UpcomingDebit,
+};
 pub mod status;
 pub use self::status::Status;
+pub mod tag;
+pub use self::tag::{TagCount, TagMerge, TagRename};
+pub mod transaction;
+pub use self::transaction::{StatementImport, Transaction, TransactionQuery};
 pub mod user_profile;
-pub use self::user_profile::UserProfile;
+pub use self::user_profile::{NotificationSettings, UserProfile};
+pub mod webhook;
+pub use self::webhook::{
+    DeliveryStatus, WebhookDelivery, WebhookEvent, WebhookPayload, WebhookSubscription,
+};
@@ -0,0 +1,168 @@
+use std::collections::BTreeMap;
+
+use validator::{ValidationError, ValidationErrors, ValidationErrorsKind};
+
+/// Problem details (RFC 7807), the `application/problem+json` body of every failed API call.
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct Problem {
+    #[serde(rename = "type", default = "about_blank")]
+    pub problem_type: String,
+
+    pub title: String,
+
+    pub status: u16,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+
+    /// Validation messages by field path, e.g. `creditor.address.zip` or `collections[0].amount`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub errors: BTreeMap<String, Vec<String>>,
+}
+
+fn about_blank() -> String {
+    "about:blank".to_string()
+}
+
+impl Problem {
+    pub fn new(status: u16, title: &str, detail: Option<String>) -> Problem {
+        Problem {
+            problem_type: about_blank(),
+            title: title.to_string(),
+            status,
+            detail,
+            errors: BTreeMap::new(),
+        }
+    }
+
+    pub fn validation(errors: &ValidationErrors) -> Problem {
+        Problem {
+            problem_type: "/problems/validation".to_string(),
+            errors: field_messages(errors),
+            ..Problem::new(400, "Validation failed", None)
+        }
+    }
+
+    /// Messages of one field, empty when the field is valid.
+    pub fn field_errors(&self, field: &str) -> &[String] {
+        self.errors.get(field).map_or(&[], |messages| messages.as_slice())
+    }
+}
+
+/// Flattens nested validation errors into messages keyed by their dotted field path.
+pub fn field_messages(errors: &ValidationErrors) -> BTreeMap<String, Vec<String>> {
+    let mut messages = BTreeMap::new();
+    collect_messages("", errors, &mut messages);
+    messages
+}
+
+fn collect_messages(
+    prefix: &str,
+    errors: &ValidationErrors,
+    messages: &mut BTreeMap<String, Vec<String>>,
+) {
+    for (field, kind) in errors.errors() {
+        let path = if prefix.is_empty() {
+            field.to_string()
+        } else {
+            format!("{}.{}", prefix, field)
+        };
+        match kind {
+            ValidationErrorsKind::Field(field_errors) => messages
+                .entry(path)
+                .or_default()
+                .extend(field_errors.iter().map(message)),
+            ValidationErrorsKind::Struct(nested) => collect_messages(&path, nested, messages),
+            ValidationErrorsKind::List(items) => {
+                for (index, nested) in items {
+                    collect_messages(&format!("{}[{}]", path, index), nested, messages);
+                }
+            }
+        }
+    }
+}
+
+/// The custom message of the error or a generic one derived from the validation code.
+fn message(error: &ValidationError) -> String {
+    if let Some(message) = &error.message {
+        return message.to_string();
+    }
+    let param = |name: &str| error.params.get(name).map(|value| value.to_string());
+    match error.code.as_ref() {
+        "length" => match (param("min"), param("max"), param("equal")) {
+            (_, _, Some(equal)) => format!("must be exactly {} characters", equal),
+            (Some(min), Some(max), _) => format!("must be {} to {} characters", min, max),
+            (Some(min), None, _) => format!("must be at least {} characters", min),
+            (None, Some(max), _) => format!("must be at most {} characters", max),
+            _ => "has an invalid length".to_string(),
+        },
+        "email" => "must be a valid email address".to_string(),
+        "regex" => "has an invalid format".to_string(),
+        "range" => "is out of range".to_string(),
+        "required" => "is required".to_string(),
+        code => format!("is invalid ({})", code),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use validator::Validate;
+
+    use super::Problem;
+    use crate::models::{Address, Mandate, UserProfile};
+
+    #[test]
+    fn test_validation_problem_has_dotted_field_paths() {
+        let mut mandate = Mandate {
+            display_name: "My mandate".to_string(),
+            ..Default::default()
+        };
+        mandate.bank_account.institution = "Bank".to_string();
+        mandate.bank_account.iban = "DE8937040044053201300".to_string();
+        mandate.creditor.name = "Stadtwerke".to_string();
+        mandate.creditor.address = Address {
+            street: "Hauptstr".to_string(),
+            house_number: "1".to_string(),
+            zip: "12345".to_string(),
+            place: "Berlin".to_string(),
+        };
+
+        let problem = Problem::validation(&mandate.validate().unwrap_err());
+        assert_eq!(400, problem.status);
+        assert_eq!(
+            vec!["must be at least 2 characters".to_string()],
+            problem.field_errors("creditor.address.house_number")
+        );
+        assert_eq!(
+            vec!["DE IBANs must be 22 characters, got 21".to_string()],
+            problem.field_errors("bank_account.iban")
+        );
+        assert_eq!(2, problem.errors.len());
+    }
+
+    #[test]
+    fn test_validation_problem_generic_messages() {
+        let mut up = UserProfile::new("Dragan".to_string(), "L".to_string());
+        up.preferred_language = Some("deu".to_string());
+
+        let problem = Problem::validation(&up.validate().unwrap_err());
+        assert_eq!(
+            vec!["must be at least 2 characters".to_string()],
+            problem.field_errors("last_name")
+        );
+        assert_eq!(
+            vec!["must be exactly 2 characters".to_string()],
+            problem.field_errors("preferred_language")
+        );
+        assert!(problem.field_errors("first_name").is_empty());
+    }
+
+    #[test]
+    fn test_problem_serialisation() {
+        let problem = Problem::new(409, "Conflict", Some("illegal status change".to_string()));
+        let json = serde_json::to_value(&problem).unwrap();
+        assert_eq!("about:blank", json["type"]);
+        assert_eq!(409, json["status"]);
+        assert!(json.get("errors").is_none());
+    }
+}
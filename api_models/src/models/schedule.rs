@@ -0,0 +1,294 @@
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, Duration, NaiveDate};
+use rust_decimal::Decimal;
+use strum_macros::{EnumString, IntoStaticStr};
+use validator::{Validate, ValidationError};
+
+use super::{collection::validate_amount, Mandate, PaymentType, Status};
+use crate::calendar::{add_months, next_business_day};
+
+/// Months forecast when the query does not say.
+pub const DEFAULT_FORECAST_MONTHS: u32 = 3;
+
+#[derive(Clone, IntoStaticStr, EnumString, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum Frequency {
+    #[default]
+    Monthly,
+    Quarterly,
+    Yearly,
+    /// Every `interval_months` months.
+    Custom,
+}
+
+/// When and how much the creditor is expected to collect, amounts are in EUR.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Validate)]
+#[validate(schema(function = "validate_interval", skip_on_field_errors = false))]
+pub struct CollectionSchedule {
+    #[validate(custom = "validate_amount")]
+    pub amount: Decimal,
+
+    #[serde(default)]
+    pub frequency: Frequency,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 1, max = 120))]
+    pub interval_months: Option<u32>,
+
+    /// One of the due dates, the others are whole intervals before and after it.
+    pub anchor_date: NaiveDate,
+}
+
+impl CollectionSchedule {
+    pub fn interval(&self) -> u32 {
+        match self.frequency {
+            Frequency::Monthly => 1,
+            Frequency::Quarterly => 3,
+            Frequency::Yearly => 12,
+            Frequency::Custom => self.interval_months.unwrap_or(1),
+        }
+    }
+
+    /// Due dates from `from` to `until`, both included, moved to the next TARGET2 business day.
+    pub fn due_dates(&self, from: NaiveDate, until: NaiveDate) -> Vec<NaiveDate> {
+        let interval = self.interval() as i32;
+        // start one interval early, a date before `from` may move into the range
+        let mut step = months_between(self.anchor_date, from).div_euclid(interval) - 1;
+        let mut dates = Vec::new();
+        loop {
+            let due = next_business_day(add_months(self.anchor_date, step * interval));
+            if due > until {
+                return dates;
+            }
+            if due >= from {
+                dates.push(due);
+            }
+            step += 1;
+        }
+    }
+}
+
+/// Custom schedules need their interval.
+pub fn validate_interval(schedule: &CollectionSchedule) -> Result<(), ValidationError> {
+    if schedule.frequency == Frequency::Custom && schedule.interval_months.is_none() {
+        let mut error = ValidationError::new("interval_months");
+        error.message = Some(Cow::from("custom schedules need an interval in months"));
+        return Err(error);
+    }
+    Ok(())
+}
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize, Validate)]
+pub struct ForecastQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 1, max = 24))]
+    pub months: Option<u32>,
+}
+
+/// An expected debit of a mandate.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct UpcomingDebit {
+    pub mandate_api_id: uuid::Uuid,
+    pub display_name: String,
+    pub creditor_name: String,
+    /// The debited account.
+    pub iban: String,
+    pub due_date: NaiveDate,
+    pub amount: Decimal,
+}
+
+/// Expected debits of one account in one month.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MonthlyTotal {
+    /// First day of the month.
+    pub month: NaiveDate,
+    pub iban: String,
+    pub debits: usize,
+    pub total: Decimal,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Forecast {
+    pub from: NaiveDate,
+    pub until: NaiveDate,
+    /// Ordered by due date.
+    pub debits: Vec<UpcomingDebit>,
+    /// Ordered by month and IBAN.
+    pub totals: Vec<MonthlyTotal>,
+}
+
+/// Upcoming debits of the active mandates with a schedule, for `months` months from `from`.
+/// One-off mandates are collected on their anchor date only.
+pub fn forecast(mandates: &[Mandate], from: NaiveDate, months: u32) -> Forecast {
+    let until = add_months(from, months as i32) - Duration::days(1);
+    let mut debits: Vec<UpcomingDebit> = Vec::new();
+    for mandate in mandates.iter().filter(|m| m.status == Status::ACTIVE) {
+        let schedule = match &mandate.schedule {
+            Some(schedule) => schedule,
+            None => continue,
+        };
+        let due_dates = match mandate.payment_type {
+            PaymentType::Recurrent => schedule.due_dates(from, until),
+            PaymentType::OneOff => Some(next_business_day(schedule.anchor_date))
+                .filter(|due| *due >= from && *due <= until)
+                .into_iter()
+                .collect(),
+        };
+        debits.extend(due_dates.into_iter().map(|due_date| UpcomingDebit {
+            mandate_api_id: mandate.api_id,
+            display_name: mandate.display_name.clone(),
+            creditor_name: mandate.creditor.name.clone(),
+            iban: mandate.bank_account.iban.clone(),
+            due_date,
+            amount: schedule.amount,
+        }));
+    }
+    debits.sort_by(|a, b| (a.due_date, &a.display_name).cmp(&(b.due_date, &b.display_name)));
+
+    let mut totals: BTreeMap<(NaiveDate, &str), (usize, Decimal)> = BTreeMap::new();
+    for debit in &debits {
+        let month = debit.due_date.with_day(1).unwrap_or(debit.due_date);
+        let total = totals.entry((month, &debit.iban)).or_default();
+        total.0 += 1;
+        total.1 += debit.amount;
+    }
+    let totals = totals
+        .into_iter()
+        .map(|((month, iban), (debits, total))| MonthlyTotal {
+            month,
+            iban: iban.to_string(),
+            debits,
+            total,
+        })
+        .collect();
+    Forecast {
+        from,
+        until,
+        debits,
+        totals,
+    }
+}
+
+fn months_between(from: NaiveDate, to: NaiveDate) -> i32 {
+    (to.year() - from.year()) * 12 + to.month() as i32 - from.month() as i32
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use chrono::NaiveDate;
+    use rust_decimal::Decimal;
+    use validator::Validate;
+
+    use super::{forecast, CollectionSchedule, Frequency};
+    use crate::models::{Mandate, PaymentType, Status};
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::from_str(value).unwrap()
+    }
+
+    fn schedule(frequency: Frequency, anchor_date: &str) -> CollectionSchedule {
+        CollectionSchedule {
+            amount: Decimal::new(4250, 2),
+            frequency,
+            interval_months: None,
+            anchor_date: date(anchor_date),
+        }
+    }
+
+    #[test]
+    fn test_due_dates_keep_the_anchor_day() {
+        // 31 January, capped to the end of shorter months, 2023-04-30 is a Sunday
+        let monthly = schedule(Frequency::Monthly, "2023-01-31");
+        assert_eq!(
+            vec![
+                date("2023-02-28"),
+                date("2023-03-31"),
+                date("2023-05-02"),
+                date("2023-05-31"),
+            ],
+            monthly.due_dates(date("2023-02-01"), date("2023-05-31"))
+        );
+    }
+
+    #[test]
+    fn test_due_dates_move_to_target2_business_days() {
+        // Good Friday 2024-03-29 and Easter Monday 2024-04-01, 2024-06-29 is a Saturday
+        let quarterly = schedule(Frequency::Quarterly, "2023-12-29");
+        assert_eq!(
+            vec![date("2024-04-02"), date("2024-07-01")],
+            quarterly.due_dates(date("2024-03-01"), date("2024-08-31"))
+        );
+        // 2022-12-25 is a Sunday, 26 December is closed as well
+        let yearly = schedule(Frequency::Yearly, "2020-12-25");
+        assert_eq!(
+            vec![date("2022-12-27")],
+            yearly.due_dates(date("2022-12-01"), date("2022-12-31"))
+        );
+        // a date before the range can move into it
+        let monthly = schedule(Frequency::Monthly, "2022-04-30");
+        assert_eq!(
+            date("2022-05-02"),
+            monthly.due_dates(date("2022-05-01"), date("2022-05-31"))[0]
+        );
+    }
+
+    #[test]
+    fn test_custom_schedule_needs_interval() {
+        let mut custom = schedule(Frequency::Custom, "2022-01-15");
+        assert!(custom.validate().is_err());
+        custom.interval_months = Some(2);
+        assert!(custom.validate().is_ok());
+        assert_eq!(
+            vec![date("2022-11-15"), date("2023-01-16")],
+            custom.due_dates(date("2022-10-01"), date("2023-01-31"))
+        );
+    }
+
+    #[test]
+    fn test_forecast_totals_per_month_and_account() {
+        let mut rent = Mandate {
+            display_name: "Rent".to_string(),
+            status: Status::ACTIVE,
+            schedule: Some(schedule(Frequency::Monthly, "2022-01-03")),
+            ..Default::default()
+        };
+        rent.bank_account.iban = "DE89370400440532013000".to_string();
+        let mut fee = Mandate {
+            display_name: "Fee".to_string(),
+            status: Status::ACTIVE,
+            payment_type: PaymentType::OneOff,
+            schedule: Some(schedule(Frequency::Monthly, "2022-11-10")),
+            ..rent.clone()
+        };
+        fee.schedule.as_mut().unwrap().amount = Decimal::new(10, 0);
+        let suspended = Mandate {
+            status: Status::SUSPENDED,
+            ..rent.clone()
+        };
+
+        let result = forecast(&[rent, fee, suspended], date("2022-10-15"), 2);
+        assert_eq!(date("2022-12-14"), result.until);
+        let due: Vec<(NaiveDate, &str)> = result
+            .debits
+            .iter()
+            .map(|d| (d.due_date, d.display_name.as_str()))
+            .collect();
+        assert_eq!(
+            vec![
+                (date("2022-11-03"), "Rent"),
+                (date("2022-11-10"), "Fee"),
+                (date("2022-12-05"), "Rent"),
+            ],
+            due
+        );
+        assert_eq!(2, result.totals.len());
+        assert_eq!(date("2022-11-01"), result.totals[0].month);
+        assert_eq!(2, result.totals[0].debits);
+        assert_eq!(Decimal::new(5250, 2), result.totals[0].total);
+    }
+}
@@ -1,16 +1,75 @@
 use strum_macros::{IntoStaticStr, EnumString};
 
-#[derive(Clone, IntoStaticStr, EnumString, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+/// Lifecycle of a mandate:
+/// `NEW` → `ACTIVE` ⇄ `SUSPENDED`, then `CANCELED` or `EXPIRED` and finally `DELETED`.
+#[derive(Clone, IntoStaticStr, EnumString, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
 pub enum Status {
     ACTIVE,
     DELETED,
     CANCELED,
-    NEW
+    #[default]
+    NEW,
+    SUSPENDED,
+    EXPIRED,
 }
 
+impl Status {
+    /// Statuses a mandate in this status can be moved to.
+    pub fn transitions(&self) -> &'static [Status] {
+        match self {
+            Status::NEW => &[Status::ACTIVE, Status::DELETED],
+            Status::ACTIVE => &[Status::SUSPENDED, Status::CANCELED, Status::EXPIRED],
+            Status::SUSPENDED => &[
+                Status::ACTIVE,
+                Status::CANCELED,
+                Status::EXPIRED,
+                Status::DELETED,
+            ],
+            Status::CANCELED | Status::EXPIRED => &[Status::DELETED],
+            Status::DELETED => &[],
+        }
+    }
+
+    /// Keeping the status is always allowed, e.g. when only other fields are changed.
+    pub fn can_transition_to(&self, next: Status) -> bool {
+        *self == next || self.transitions().contains(&next)
+    }
+
+    /// Whether a new mandate may be created in this status.
+    pub fn is_initial(&self) -> bool {
+        Status::NEW.can_transition_to(*self) && *self != Status::DELETED
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Status;
+
+    #[test]
+    fn test_allowed_transitions() {
+        assert!(Status::NEW.can_transition_to(Status::ACTIVE));
+        assert!(Status::ACTIVE.can_transition_to(Status::SUSPENDED));
+        assert!(Status::SUSPENDED.can_transition_to(Status::ACTIVE));
+        assert!(Status::ACTIVE.can_transition_to(Status::CANCELED));
+        assert!(Status::EXPIRED.can_transition_to(Status::DELETED));
+        assert!(Status::SUSPENDED.can_transition_to(Status::DELETED));
+        assert!(Status::CANCELED.can_transition_to(Status::CANCELED));
+    }
+
+    #[test]
+    fn test_illegal_transitions() {
+        assert!(!Status::DELETED.can_transition_to(Status::ACTIVE));
+        assert!(!Status::CANCELED.can_transition_to(Status::ACTIVE));
+        assert!(!Status::NEW.can_transition_to(Status::CANCELED));
+        assert!(!Status::ACTIVE.can_transition_to(Status::DELETED));
+        assert!(!Status::ACTIVE.can_transition_to(Status::NEW));
+    }
 
-impl Default for Status {
-    fn default() -> Status {
-        Self::NEW
+    #[test]
+    fn test_initial_statuses() {
+        assert!(Status::NEW.is_initial());
+        assert!(Status::ACTIVE.is_initial());
+        assert!(!Status::DELETED.is_initial());
+        assert!(!Status::CANCELED.is_initial());
     }
 }
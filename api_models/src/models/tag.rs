@@ -0,0 +1,125 @@
+use std::borrow::Cow;
+
+use validator::{Validate, ValidationError};
+
+pub const MAX_TAG_LENGTH: usize = 32;
+
+/// A tag and the number of mandates carrying it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TagCount {
+    pub tag: String,
+    pub count: u64,
+}
+
+/// New name of a tag; renaming onto an existing tag merges both.
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize, Validate)]
+pub struct TagRename {
+    #[validate(custom = "validate_tag")]
+    pub name: String,
+}
+
+/// Replaces all `tags` by `into` on every mandate of the user.
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize, Validate)]
+pub struct TagMerge {
+    #[validate(length(min = 1))]
+    pub tags: Vec<String>,
+
+    #[validate(custom = "validate_tag")]
+    pub into: String,
+}
+
+/// Tags are compared trimmed and lower case, "Household " and "household" are the same tag.
+pub fn normalize_tag(tag: &str) -> String {
+    tag.trim().to_lowercase()
+}
+
+/// Normalized tags without duplicates, in their original order.
+pub fn normalize_tags(tags: &[String]) -> Vec<String> {
+    let mut result: Vec<String> = Vec::with_capacity(tags.len());
+    for tag in tags.iter().map(|t| normalize_tag(t)) {
+        if !tag.is_empty() && !result.contains(&tag) {
+            result.push(tag);
+        }
+    }
+    result
+}
+
+/// `tags` with each of `from` replaced by `into`, `None` when none of them is present.
+pub fn merge_tags(tags: &[String], from: &[String], into: &str) -> Option<Vec<String>> {
+    let from: Vec<String> = from.iter().map(|t| normalize_tag(t)).collect();
+    if !tags.iter().any(|t| from.contains(&normalize_tag(t))) {
+        return None;
+    }
+    let replaced: Vec<String> = tags
+        .iter()
+        .map(|t| {
+            if from.contains(&normalize_tag(t)) {
+                into.to_string()
+            } else {
+                t.clone()
+            }
+        })
+        .collect();
+    Some(normalize_tags(&replaced))
+}
+
+pub fn validate_tag(tag: &str) -> Result<(), ValidationError> {
+    let tag = normalize_tag(tag);
+    let reason = if tag.is_empty() {
+        "must not be empty"
+    } else if tag.chars().count() > MAX_TAG_LENGTH {
+        "must be at most 32 characters"
+    } else if tag.chars().any(|c| c.is_control() || c == ',') {
+        "must not contain commas or control characters"
+    } else {
+        return Ok(());
+    };
+    let mut error = ValidationError::new("tag");
+    error.message = Some(Cow::from(reason));
+    Err(error)
+}
+
+pub fn validate_tags(tags: &[String]) -> Result<(), ValidationError> {
+    tags.iter().try_for_each(|tag| validate_tag(tag))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{merge_tags, normalize_tags, validate_tag};
+
+    fn tags(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_normalize_tags() {
+        assert_eq!(
+            tags(&["household", "insurance"]),
+            normalize_tags(&tags(&[" Household", "insurance", "household ", ""]))
+        );
+    }
+
+    #[test]
+    fn test_merge_tags() {
+        assert_eq!(
+            Some(tags(&["utilities", "insurance"])),
+            merge_tags(
+                &tags(&["power", "insurance", "Water"]),
+                &tags(&["power", "water"]),
+                "utilities"
+            )
+        );
+        assert_eq!(
+            None,
+            merge_tags(&tags(&["insurance"]), &tags(&["power"]), "utilities")
+        );
+    }
+
+    #[test]
+    fn test_validate_tag() {
+        assert!(validate_tag("utilities").is_ok());
+        assert!(validate_tag("  ").is_err());
+        assert!(validate_tag("a,b").is_err());
+        assert!(validate_tag(&"x".repeat(33)).is_err());
+    }
+}
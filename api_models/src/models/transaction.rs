@@ -0,0 +1,62 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+
+/// A direct debit taken from an imported bank statement.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Transaction {
+    pub api_id: uuid::Uuid,
+
+    /// Mandate the debit was matched to, `None` for debits without a known mandate.
+    pub mandate_api_id: Option<uuid::Uuid>,
+
+    pub booking_date: NaiveDate,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_date: Option<NaiveDate>,
+
+    pub amount: Decimal,
+
+    pub currency: String,
+
+    /// Set for returned or refunded debits.
+    pub is_reversal: bool,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mandate_reference: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub creditor_identifier: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub creditor_name: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub debtor_iban: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_to_end_id: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remittance_information: Option<String>,
+}
+
+/// Outcome of a statement upload.
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct StatementImport {
+    pub format: String,
+
+    /// Debits already imported from an earlier upload.
+    pub duplicates: usize,
+
+    pub matched: Vec<Transaction>,
+
+    /// Debits without a known mandate.
+    pub unmatched: Vec<Transaction>,
+}
+
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct TransactionQuery {
+    /// Only return debits without a known mandate.
+    #[serde(default)]
+    pub unmatched: bool,
+}
@@ -27,6 +27,33 @@ pub struct UserProfile {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[validate(length(equal = 2))]
     pub preferred_language: Option<String>,
+
+    /// Set by the backend from the token, notifications are sent to it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+
+    #[serde(default)]
+    pub notifications: NotificationSettings,
+}
+
+/// Emails the user opted in to, all off by default.
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct NotificationSettings {
+    /// Debits due in the next days.
+    #[serde(default)]
+    pub upcoming_debits: bool,
+
+    /// Debits collected after the end of their mandate.
+    #[serde(default)]
+    pub debit_alerts: bool,
+
+    /// Reminders to complete the profile.
+    #[serde(default)]
+    pub profile_reminders: bool,
+
+    /// Mandates that expired after 36 months without a collection.
+    #[serde(default)]
+    pub expired_mandates: bool,
 }
 
 impl UserProfile {
@@ -37,6 +64,8 @@ impl UserProfile {
             address: None,
             date_of_birth: None,
             preferred_language: None,
+            email: None,
+            notifications: NotificationSettings::default(),
         }
     }
 }
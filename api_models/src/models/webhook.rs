@@ -0,0 +1,171 @@
+use std::borrow::Cow;
+
+use chrono::{Duration, NaiveDateTime};
+use strum_macros::{EnumString, IntoStaticStr};
+use validator::{validate_url, Validate, ValidationError};
+
+/// Deliveries of an event are attempted this many times before they fail.
+pub const MAX_ATTEMPTS: u32 = 8;
+
+/// Subscriptions a user can have.
+pub const MAX_SUBSCRIPTIONS: u64 = 10;
+
+/// Changes a webhook can subscribe to, named like `mandate.created` in the API and the deliveries.
+#[derive(Clone, IntoStaticStr, EnumString, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum WebhookEvent {
+    #[serde(rename = "mandate.created")]
+    #[strum(serialize = "mandate.created")]
+    MandateCreated,
+    /// Any saved change of a mandate, including status changes.
+    #[serde(rename = "mandate.updated")]
+    #[strum(serialize = "mandate.updated")]
+    MandateUpdated,
+    #[serde(rename = "mandate.status_changed")]
+    #[strum(serialize = "mandate.status_changed")]
+    MandateStatusChanged,
+    #[serde(rename = "profile.updated")]
+    #[strum(serialize = "profile.updated")]
+    ProfileUpdated,
+}
+
+pub const WEBHOOK_EVENTS: [WebhookEvent; 4] = [
+    WebhookEvent::MandateCreated,
+    WebhookEvent::MandateUpdated,
+    WebhookEvent::MandateStatusChanged,
+    WebhookEvent::ProfileUpdated,
+];
+
+/// Where the events of the user are posted to.
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize, Validate)]
+pub struct WebhookSubscription {
+    pub api_id: uuid::Uuid,
+
+    #[validate(custom = "validate_webhook_url")]
+    pub url: String,
+
+    /// Key of the HMAC-SHA256 signature of the deliveries. Needed for a new subscription,
+    /// left out it is kept on updates. It is never returned.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(length(min = 16, max = 200))]
+    pub secret: Option<String>,
+
+    #[validate(length(min = 1))]
+    pub events: Vec<WebhookEvent>,
+
+    /// Paused subscriptions receive no events.
+    #[serde(default = "default_active")]
+    pub active: bool,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date_created: Option<NaiveDateTime>,
+}
+
+fn default_active() -> bool {
+    true
+}
+
+/// Body of a delivery, the same for every subscription of the event.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WebhookPayload {
+    /// Id of the event, a retried delivery sends it again.
+    pub id: uuid::Uuid,
+    pub event: WebhookEvent,
+    /// UTC.
+    pub created: NaiveDateTime,
+    /// The mandate or the profile after the change.
+    pub data: serde_json::Value,
+}
+
+#[derive(Clone, IntoStaticStr, EnumString, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum DeliveryStatus {
+    Pending,
+    Delivered,
+    /// Given up after `MAX_ATTEMPTS`.
+    Failed,
+}
+
+/// An event sent to a subscription, times are UTC.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WebhookDelivery {
+    pub api_id: uuid::Uuid,
+    pub event_id: uuid::Uuid,
+    pub event: WebhookEvent,
+    pub status: DeliveryStatus,
+    pub attempts: u32,
+    pub date_created: NaiveDateTime,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_attempt: Option<NaiveDateTime>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_attempt: Option<NaiveDateTime>,
+    /// HTTP status of the last attempt.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_status: Option<u16>,
+    /// Why the last attempt failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Wait after `attempts` failed attempts: a minute after the first, doubling with every further
+/// one. `None` once the delivery is given up.
+pub fn retry_delay(attempts: u32) -> Option<Duration> {
+    if attempts == 0 || attempts >= MAX_ATTEMPTS {
+        return None;
+    }
+    Some(Duration::minutes(1 << (attempts - 1)))
+}
+
+/// Events are posted to https URLs only, they carry personal data.
+pub fn validate_webhook_url(url: &str) -> Result<(), ValidationError> {
+    if validate_url(url) && url.starts_with("https://") {
+        return Ok(());
+    }
+    let mut error = ValidationError::new("url");
+    error.message = Some(Cow::from("must be an https URL"));
+    Err(error)
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use chrono::Duration;
+    use validator::Validate;
+
+    use super::{retry_delay, WebhookEvent, WebhookSubscription, MAX_ATTEMPTS};
+
+    #[test]
+    fn test_retry_with_exponential_backoff() {
+        assert_eq!(Some(Duration::minutes(1)), retry_delay(1));
+        assert_eq!(Some(Duration::minutes(2)), retry_delay(2));
+        assert_eq!(Some(Duration::minutes(64)), retry_delay(MAX_ATTEMPTS - 1));
+        assert_eq!(None, retry_delay(MAX_ATTEMPTS));
+    }
+
+    #[test]
+    fn test_subscription_validation() {
+        let mut subscription: WebhookSubscription = serde_json::from_str(
+            r#"{"api_id": "7e5b3f3e-3c1a-4c4b-9d1e-1f2a3b4c5d6e",
+                "url": "https://example.com/hook", "events": ["mandate.status_changed"]}"#,
+        )
+        .unwrap();
+        assert!(subscription.active);
+        assert_eq!(Ok(()), subscription.validate());
+        assert_eq!(
+            Ok(WebhookEvent::MandateStatusChanged),
+            WebhookEvent::from_str("mandate.status_changed")
+        );
+
+        subscription.url = "http://example.com/hook".to_string();
+        assert!(subscription.validate().unwrap_err().errors().contains_key("url"));
+
+        subscription.url = "ftp://example.com/hook".to_string();
+        subscription.events.clear();
+        subscription.secret = Some("short".to_string());
+        let errors = subscription.validate().unwrap_err();
+        assert!(errors.errors().contains_key("url"));
+        assert!(errors.errors().contains_key("events"));
+        assert!(errors.errors().contains_key("secret"));
+    }
+}
@@ -0,0 +1,117 @@
+use api_models::calendar::{
+    add_business_days, add_months, easter_sunday, is_business_day, is_closing_day,
+    next_business_day, previous_business_day,
+};
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+
+/// Every third day of 1990 to 2071, so each weekday and holiday shows up.
+fn dates() -> impl Iterator<Item = NaiveDate> {
+    let base = NaiveDate::from_ymd_opt(1990, 1, 1).unwrap();
+    (0..30_000i64)
+        .step_by(3)
+        .map(move |days| base + Duration::days(days))
+}
+
+fn business_days_between(from: NaiveDate, to: NaiveDate) -> i64 {
+    let mut count = 0;
+    let mut date = from;
+    while date < to {
+        date += Duration::days(1);
+        if is_business_day(date) {
+            count += 1;
+        }
+    }
+    count
+}
+
+#[test]
+fn next_business_day_is_the_first_one() {
+    for date in dates() {
+        let next = next_business_day(date);
+        assert!(is_business_day(next), "{}", date);
+        assert!(next >= date, "{}", date);
+        let day_before = date - Duration::days(1);
+        assert_eq!(
+            0,
+            business_days_between(day_before, next - Duration::days(1)),
+            "{}",
+            date
+        );
+    }
+}
+
+#[test]
+fn previous_business_day_is_the_last_one() {
+    for date in dates() {
+        let previous = previous_business_day(date);
+        assert!(is_business_day(previous), "{}", date);
+        assert!(previous <= date, "{}", date);
+        assert_eq!(0, business_days_between(previous, date), "{}", date);
+    }
+}
+
+#[test]
+fn add_business_days_counts_business_days() {
+    for (date, days) in dates().zip((1..60i64).cycle()) {
+        let result = add_business_days(date, days);
+        assert!(is_business_day(result), "{} + {}", date, days);
+        assert_eq!(days, business_days_between(date, result), "{} + {}", date, days);
+    }
+}
+
+#[test]
+fn add_business_days_goes_back_and_forth() {
+    for (date, days) in dates().zip((0..60i64).cycle()) {
+        let date = next_business_day(date);
+        assert_eq!(
+            date,
+            add_business_days(add_business_days(date, days), -days),
+            "{} + {}",
+            date,
+            days
+        );
+    }
+}
+
+#[test]
+fn easter_is_a_sunday_in_spring() {
+    for year in 1583..4000 {
+        let easter = easter_sunday(year);
+        assert_eq!(Weekday::Sun, easter.weekday(), "{}", year);
+        assert!(easter >= NaiveDate::from_ymd_opt(year, 3, 22).unwrap(), "{}", year);
+        assert!(easter <= NaiveDate::from_ymd_opt(year, 4, 25).unwrap(), "{}", year);
+    }
+    // the earliest and latest dates Easter falls on
+    assert_eq!(NaiveDate::from_ymd_opt(1818, 3, 22), Some(easter_sunday(1818)));
+    assert_eq!(NaiveDate::from_ymd_opt(2285, 3, 22), Some(easter_sunday(2285)));
+    assert_eq!(NaiveDate::from_ymd_opt(1943, 4, 25), Some(easter_sunday(1943)));
+    assert_eq!(NaiveDate::from_ymd_opt(2038, 4, 25), Some(easter_sunday(2038)));
+}
+
+#[test]
+fn good_friday_and_easter_monday_are_closing_days() {
+    for year in 1583..4000 {
+        let easter = easter_sunday(year);
+        let good_friday = easter - Duration::days(2);
+        let easter_monday = easter + Duration::days(1);
+        assert_eq!(Weekday::Fri, good_friday.weekday(), "{}", year);
+        assert!(good_friday >= NaiveDate::from_ymd_opt(year, 3, 20).unwrap(), "{}", year);
+        assert!(good_friday <= NaiveDate::from_ymd_opt(year, 4, 23).unwrap(), "{}", year);
+        assert!(is_closing_day(good_friday), "{}", year);
+        assert!(is_closing_day(easter_monday), "{}", year);
+        // the Thursday before and the Tuesday after are business days
+        assert!(is_business_day(good_friday - Duration::days(1)), "{}", year);
+        assert!(is_business_day(easter_monday + Duration::days(1)), "{}", year);
+    }
+}
+
+#[test]
+fn add_months_moves_whole_months() {
+    for (date, months) in dates().zip((-240..240i32).cycle()) {
+        let result = add_months(date, months);
+        let month0 = |d: NaiveDate| d.year() * 12 + d.month0() as i32;
+        assert_eq!(month0(date) + months, month0(result), "{} + {}", date, months);
+        assert!(result.day() <= date.day(), "{} + {}", date, months);
+        assert!(result.day() == date.day() || result.day() >= 28, "{} + {}", date, months);
+    }
+}
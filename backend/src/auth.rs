@@ -1,47 +1,274 @@
 use crate::errors::ServiceError;
-use jsonwebtoken::jwk::{AlgorithmParameters, JwkSet};
+use api_models::models::ClientConfig;
+use jsonwebtoken::jwk::{AlgorithmParameters, Jwk, JwkSet};
 use jsonwebtoken::{decode, decode_header, DecodingKey, TokenData, Validation};
+use log::{debug, error, warn};
+use serde::Deserialize;
 use serde_json;
 use std::collections::HashMap;
+use std::env;
 use std::error::Error;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
 
+/// An unknown `kid` triggers a refetch only when the keys are older than this,
+/// so tokens with made up key ids can't make us hammer the provider.
+const MIN_REFETCH_INTERVAL: Duration = Duration::from_secs(10);
+
+/// The part of the provider metadata (`.well-known/openid-configuration`) we rely on.
+#[derive(Clone, Debug, Deserialize)]
+pub struct ProviderMetadata {
+    pub issuer: String,
+    pub jwks_uri: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub end_session_endpoint: Option<String>,
+}
+
+/// OpenID Connect settings of the backend, any compliant provider (Auth0, Keycloak, a mock IdP) works.
+#[derive(Clone, Debug)]
+pub struct AuthConfig {
+    pub client_id: String,
+    pub audience: String,
+    pub scope: String,
+    pub provider: ProviderMetadata,
+    pub jwks: JwksCache,
+    /// Subjects (`sub` claims) allowed to use the admin endpoints.
+    pub admins: Vec<String>,
+}
+
+impl AuthConfig {
+    /// Reads `OIDC_ISSUER` (or the older `AUTHORITY`), `OIDC_CLIENT_ID`, `OIDC_AUDIENCE`
+    /// (defaults to the client id), `OIDC_SCOPE`, `JWKS_CACHE_TTL_SECONDS` (defaults to an hour,
+    /// at least a second) and `ADMIN_SUBJECTS` (comma separated, no admins by default),
+    /// then discovers the provider endpoints.
+    pub async fn from_env() -> Result<AuthConfig, Box<dyn Error>> {
+        let issuer = env::var("OIDC_ISSUER")
+            .or_else(|_| env::var("AUTHORITY"))
+            .map_err(|_| "OIDC_ISSUER must be set")?;
+        let client_id = env::var("OIDC_CLIENT_ID").map_err(|_| "OIDC_CLIENT_ID must be set")?;
+        let audience = env::var("OIDC_AUDIENCE").unwrap_or_else(|_| client_id.clone());
+        // `offline_access` for the refresh token the web UI renews the session with
+        let scope = env::var("OIDC_SCOPE")
+            .unwrap_or_else(|_| "openid profile email offline_access".to_string());
+        let ttl = cache_ttl(env::var("JWKS_CACHE_TTL_SECONDS").ok().as_deref());
+        let admins = env::var("ADMIN_SUBJECTS")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|subject| !subject.is_empty())
+            .map(str::to_string)
+            .collect();
+        let provider = discover(&issuer).await?;
+        let jwks = JwksCache::new(&provider.jwks_uri, ttl);
+        Ok(AuthConfig {
+            client_id,
+            audience,
+            scope,
+            provider,
+            jwks,
+            admins,
+        })
+    }
+
+    pub fn client_config(&self) -> ClientConfig {
+        ClientConfig {
+            issuer: self.provider.issuer.clone(),
+            client_id: self.client_id.clone(),
+            audience: self.audience.clone(),
+            scope: self.scope.clone(),
+            authorization_endpoint: self.provider.authorization_endpoint.clone(),
+            token_endpoint: self.provider.token_endpoint.clone(),
+            end_session_endpoint: self.provider.end_session_endpoint.clone(),
+        }
+    }
+}
+
+/// The TTL of the signing keys from `JWKS_CACHE_TTL_SECONDS`, an hour when unset or invalid.
+fn cache_ttl(seconds: Option<&str>) -> Duration {
+    let seconds = seconds
+        .and_then(|ttl| ttl.parse::<u64>().ok())
+        .unwrap_or(3600)
+        // the background refresh needs a non-zero interval
+        .max(1);
+    Duration::from_secs(seconds)
+}
+
+/// OpenID Connect discovery, the returned issuer must be the configured one.
+pub async fn discover(issuer: &str) -> Result<ProviderMetadata, Box<dyn Error>> {
+    let uri = format!(
+        "{}/.well-known/openid-configuration",
+        issuer.trim_end_matches('/')
+    );
+    let client = awc::Client::default();
+    let mut res = client.get(uri.as_str()).send().await?;
+    let metadata = res.json::<ProviderMetadata>().await?;
+    if metadata.issuer.trim_end_matches('/') != issuer.trim_end_matches('/') {
+        return Err(format!(
+            "Discovery document of {} is for issuer {}",
+            issuer, metadata.issuer
+        )
+        .into());
+    }
+    Ok(metadata)
+}
+
+struct CachedKeys {
+    keys: JwkSet,
+    fetched_at: Instant,
+}
+
+/// Signing keys of the provider shared by all workers, refetched after the TTL
+/// and when a token is signed with a key we don't know yet (key rotation).
+/// If the provider is unreachable the last good key set stays in use.
+#[derive(Clone)]
+pub struct JwksCache {
+    jwks_uri: String,
+    ttl: Duration,
+    cached: Arc<RwLock<Option<CachedKeys>>>,
+    /// Held while the key set is fetched for a lookup, so concurrent ones fetch it once.
+    fetching: Arc<tokio::sync::Mutex<()>>,
+}
+
+impl std::fmt::Debug for JwksCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("JwksCache")
+            .field("jwks_uri", &self.jwks_uri)
+            .field("ttl", &self.ttl)
+            .finish()
+    }
+}
+
+impl JwksCache {
+    pub fn new(jwks_uri: &str, ttl: Duration) -> JwksCache {
+        JwksCache {
+            jwks_uri: jwks_uri.to_string(),
+            ttl,
+            cached: Arc::new(RwLock::new(None)),
+            fetching: Arc::new(tokio::sync::Mutex::new(())),
+        }
+    }
+
+    /// Finds the key with the given id, fetching the key set when needed.
+    pub async fn find(&self, kid: &str) -> Result<Jwk, ServiceError> {
+        let (key, age) = self.lookup(kid);
+        if !needs_refetch(key.is_some(), age, self.ttl) {
+            return key.ok_or_else(|| unknown_key(kid));
+        }
+        let _fetching = self.fetching.lock().await;
+        // a lookup waited for meanwhile may have fetched the keys already
+        let (key, age) = self.lookup(kid);
+        if !needs_refetch(key.is_some(), age, self.ttl) {
+            return key.ok_or_else(|| unknown_key(kid));
+        }
+        match self.refresh().await {
+            Ok(()) => self.lookup(kid).0.ok_or_else(|| unknown_key(kid)),
+            // a stale key is better than rejecting every request while the provider is down
+            Err(e) => key.ok_or(e),
+        }
+    }
+
+    /// Fetches the key set, on failure the previous one is kept.
+    pub async fn refresh(&self) -> Result<(), ServiceError> {
+        match fetch_jwks(&self.jwks_uri).await {
+            Ok(keys) => {
+                debug!(
+                    "Fetched {} signing keys from {}",
+                    keys.keys.len(),
+                    self.jwks_uri
+                );
+                if let Ok(mut cached) = self.cached.write() {
+                    *cached = Some(CachedKeys {
+                        keys,
+                        fetched_at: Instant::now(),
+                    });
+                }
+                Ok(())
+            }
+            Err(e) => {
+                let has_cached_keys = matches!(self.cached.read().as_deref(), Ok(Some(_)));
+                if has_cached_keys {
+                    warn!(
+                        "Can't refresh JWKS from {}, using cached keys: {}",
+                        self.jwks_uri, e
+                    );
+                } else {
+                    error!("Can't get JWKS from {}: {}", self.jwks_uri, e);
+                }
+                Err(ServiceError::JWKSFetchError)
+            }
+        }
+    }
+
+    /// Refreshes the key set in the background, so requests rarely wait for the provider.
+    pub fn spawn_refresh(&self) {
+        let cache = self.clone();
+        actix_web::rt::spawn(async move {
+            let mut interval = actix_web::rt::time::interval(cache.ttl);
+            loop {
+                interval.tick().await;
+                // failures are logged, the cached keys stay valid
+                let _ = cache.refresh().await;
+            }
+        });
+    }
+
+    /// The key with the given id and the age of the cached key set, `None` when nothing is cached.
+    fn lookup(&self, kid: &str) -> (Option<Jwk>, Option<Duration>) {
+        match self.cached.read() {
+            Ok(cached) => match cached.as_ref() {
+                Some(c) => (c.keys.find(kid).cloned(), Some(c.fetched_at.elapsed())),
+                None => (None, None),
+            },
+            Err(_) => (None, None),
+        }
+    }
+}
+
+/// Whether a lookup fetches the key set: when nothing is cached, when the cached set is older
+/// than the TTL, and for an unknown key when the set is older than `MIN_REFETCH_INTERVAL`.
+fn needs_refetch(known: bool, age: Option<Duration>, ttl: Duration) -> bool {
+    match age {
+        None => true,
+        Some(age) if known => age > ttl,
+        Some(age) => age > MIN_REFETCH_INTERVAL,
+    }
+}
+
+fn unknown_key(kid: &str) -> ServiceError {
+    ServiceError::Unauthorized(format!("Token is signed with unknown key {}", kid))
+}
+
+/// Verifies signature, issuer, audience and expiry of the access token.
 pub async fn get_token_data(
     token: &str,
+    config: &AuthConfig,
 ) -> Result<TokenData<HashMap<String, serde_json::Value>>, ServiceError> {
-    let authority = std::env::var("AUTHORITY").expect("AUTHORITY must be set");
-    let jwks = fetch_jwks(&format!(
-        "{}{}",
-        authority.as_str(),
-        ".well-known/jwks.json"
-    ))
-    .await
-    .expect("Can't get JWKS!");
     let header = decode_header(token)
-        .map_err(|_| ServiceError::BadRequest("Invalid token header".to_string()))?;
+        .map_err(|_| ServiceError::Unauthorized("Invalid token header".to_string()))?;
 
     let kid = match header.kid {
         Some(k) => k,
         None => {
-            return Err(ServiceError::BadRequest(
+            return Err(ServiceError::Unauthorized(
                 "Token doesn't have a `kid` header field".into(),
             ))
         }
     };
-    if let Some(j) = jwks.find(&kid) {
-        match j.algorithm {
-            AlgorithmParameters::RSA(ref rsa) => {
-                let decoding_key = DecodingKey::from_rsa_components(&rsa.n, &rsa.e).unwrap();
-                let mut validation = Validation::new(j.common.algorithm.unwrap());
-                validation.validate_exp = false;
-                let decoded_token =
-                    decode::<HashMap<String, serde_json::Value>>(token, &decoding_key, &validation)
-                        .unwrap();
-                Ok(decoded_token)
-            }
-            _ => unreachable!("this should be a RSA"),
+    let j = config.jwks.find(&kid).await?;
+    match j.algorithm {
+        AlgorithmParameters::RSA(ref rsa) => {
+            let decoding_key = DecodingKey::from_rsa_components(&rsa.n, &rsa.e)
+                .map_err(|_| ServiceError::JWKSFetchError)?;
+            let mut validation = Validation::new(j.common.algorithm.unwrap_or(header.alg));
+            validation.set_issuer(&[&config.provider.issuer]);
+            validation.set_audience(&[&config.audience]);
+            decode::<HashMap<String, serde_json::Value>>(token, &decoding_key, &validation)
+                .map_err(|e| ServiceError::Unauthorized(format!("Invalid token: {}", e)))
         }
-    } else {
-        Err(ServiceError::JWKSFetchError)
+        _ => Err(ServiceError::Unauthorized(
+            "Only RSA signed tokens are supported".to_string(),
+        )),
     }
 }
 
@@ -51,3 +278,62 @@ async fn fetch_jwks(uri: &str) -> Result<JwkSet, Box<dyn Error>> {
     let val = res.json::<JwkSet>().await?;
     Ok(val)
 }
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, RwLock};
+    use std::time::{Duration, Instant};
+
+    use jsonwebtoken::jwk::JwkSet;
+
+    use super::{cache_ttl, needs_refetch, CachedKeys, JwksCache, MIN_REFETCH_INTERVAL};
+    use crate::errors::ServiceError;
+
+    const HOUR: Duration = Duration::from_secs(3600);
+
+    #[test]
+    fn test_cache_ttl() {
+        assert_eq!(HOUR, cache_ttl(None));
+        assert_eq!(Duration::from_secs(60), cache_ttl(Some("60")));
+        assert_eq!(Duration::from_secs(1), cache_ttl(Some("0")));
+        assert_eq!(HOUR, cache_ttl(Some("-5")));
+        assert_eq!(HOUR, cache_ttl(Some("an hour")));
+    }
+
+    #[test]
+    fn test_needs_refetch() {
+        let second = Duration::from_secs(1);
+        // nothing cached
+        assert!(needs_refetch(false, None, HOUR));
+        // known key, within and after the TTL
+        assert!(!needs_refetch(true, Some(HOUR), HOUR));
+        assert!(needs_refetch(true, Some(HOUR + second), HOUR));
+        // unknown key, throttled
+        assert!(!needs_refetch(false, Some(MIN_REFETCH_INTERVAL), HOUR));
+        assert!(needs_refetch(false, Some(MIN_REFETCH_INTERVAL + second), HOUR));
+    }
+
+    #[actix_web::test]
+    async fn test_stale_key_when_the_provider_is_down() {
+        let keys: JwkSet = serde_json::from_str(
+            r#"{"keys": [{"kty": "RSA", "kid": "k1", "use": "sig", "n": "sXchDaQebHnPiGvyDOAT4saGEUetSyo9MKLOoWFsueri23bOdgWp4Dy1WlUzewbgBHod5pcM9H95GQRV3JDXboIRROSBigeC5yjU1hGzHHyXss8UDprecbAYxknTcQkhslANGRUZmdTOQ5qTRsLAt6BTYuyvVRdhS8exSZEy_c4gs_7svlJJQ4H9_NxsiIoLwAEk7-Q3UXERGYw_75IDrGA84-lA_-Ct4eTlXHBIY2EaV7t7LjJaynVJCpkv4LKjTTAumiGUIuQhrNhZLuF_RJLqHpM2kgWFLU7-VTdL1VbC2tejvcI2BlMkEpk1BzBZI0KQB0GaDWFLN-aEAw3vRw", "e": "AQAB"}]}"#,
+        )
+        .unwrap();
+        // nothing listens on the discard port
+        let mut cache = JwksCache::new("http://127.0.0.1:9/jwks", Duration::ZERO);
+        cache.cached = Arc::new(RwLock::new(Some(CachedKeys {
+            keys,
+            fetched_at: Instant::now(),
+        })));
+        assert_eq!(Some("k1"), cache.find("k1").await.unwrap().common.key_id.as_deref());
+        // unknown keys are only refetched after the interval
+        assert!(matches!(
+            cache.find("k2").await,
+            Err(ServiceError::Unauthorized(_))
+        ));
+        assert!(matches!(
+            JwksCache::new("http://127.0.0.1:9/jwks", HOUR).find("k1").await,
+            Err(ServiceError::JWKSFetchError)
+        ));
+    }
+}
@@ -1,28 +1,79 @@
 use std::fmt::Display;
 
-use actix_web::{error::ResponseError, HttpResponse};
+use actix_web::{error::ResponseError, http::StatusCode, HttpResponse};
+use api_models::{models::Problem, validator::ValidationErrors};
 
 #[derive(Debug)]
 pub enum ServiceError {
     InternalServerError,
     BadRequest(String),
+    /// Invalid fields of the request body, reported per field.
+    Validation(ValidationErrors),
+    Unauthorized(String),
+    Forbidden(String),
+    NotFound(String),
+    Conflict(String),
+    /// The identity provider (its key set) can't be reached.
     JWKSFetchError,
 }
 
+impl ServiceError {
+    pub fn problem(&self) -> Problem {
+        let status = self.status_code();
+        let title = status.canonical_reason().unwrap_or("Error");
+        match self {
+            ServiceError::InternalServerError => Problem::new(
+                status.as_u16(),
+                title,
+                Some("Internal Server Error, Please try later".to_string()),
+            ),
+            ServiceError::Validation(errors) => Problem::validation(errors),
+            ServiceError::BadRequest(message)
+            | ServiceError::Unauthorized(message)
+            | ServiceError::Forbidden(message)
+            | ServiceError::NotFound(message)
+            | ServiceError::Conflict(message) => {
+                Problem::new(status.as_u16(), title, Some(message.clone()))
+            }
+            ServiceError::JWKSFetchError => Problem::new(
+                status.as_u16(),
+                title,
+                Some("Could not fetch JWKS from the identity provider".to_string()),
+            ),
+        }
+    }
+}
+
 // impl ResponseError trait allows to convert our errors into http responses with appropriate data
 impl ResponseError for ServiceError {
-    fn error_response(&self) -> HttpResponse {
+    fn status_code(&self) -> StatusCode {
         match self {
-            ServiceError::InternalServerError => {
-                HttpResponse::InternalServerError().json("Internal Server Error, Please try later")
-            }
-            ServiceError::BadRequest(ref message) => HttpResponse::BadRequest().json(message),
-            ServiceError::JWKSFetchError => {
-                HttpResponse::InternalServerError().json("Could not fetch JWKS")
-            }
+            ServiceError::InternalServerError => StatusCode::INTERNAL_SERVER_ERROR,
+            ServiceError::BadRequest(_) | ServiceError::Validation(_) => StatusCode::BAD_REQUEST,
+            ServiceError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            ServiceError::Forbidden(_) => StatusCode::FORBIDDEN,
+            ServiceError::NotFound(_) => StatusCode::NOT_FOUND,
+            ServiceError::Conflict(_) => StatusCode::CONFLICT,
+            ServiceError::JWKSFetchError => StatusCode::BAD_GATEWAY,
         }
     }
+
+    fn error_response(&self) -> HttpResponse {
+        let mut response = HttpResponse::build(self.status_code());
+        response.content_type("application/problem+json");
+        if let ServiceError::Unauthorized(_) = self {
+            response.insert_header(("WWW-Authenticate", "Bearer error=\"invalid_token\""));
+        }
+        response.body(serde_json::to_string(&self.problem()).unwrap_or_default())
+    }
 }
+
+impl From<ValidationErrors> for ServiceError {
+    fn from(errors: ValidationErrors) -> Self {
+        ServiceError::Validation(errors)
+    }
+}
+
 impl Display for ServiceError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.status_code())
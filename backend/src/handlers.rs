@@ -16,6 +16,11 @@ use entity::{
 };
 use user_profile::Column::AuthId;
 
+pub(crate) fn db_error(e: entity::sea_orm::DbErr) -> crate::errors::ServiceError {
+    error!("Db Error: {}", e);
+    crate::errors::ServiceError::InternalServerError
+}
+
 #[derive(Serialize)]
 pub struct DtoBankAccount {
     pub institution: String,
@@ -23,35 +28,66 @@ pub struct DtoBankAccount {
     pub bic: Option<String>,
 }
 
+pub mod config {
+    use super::*;
+
+    /// Unauthenticated, the web UI bootstraps its sign in from it.
+    pub async fn get_config(state: web::Data<AppState>) -> impl Responder {
+        HttpResponse::Ok().json(state.auth.client_config())
+    }
+}
+
 pub mod profile {
 
-    use api_models::validator::Validate;
+    use api_models::models::{NotificationSettings, WebhookEvent};
+    use api_models::validator::{Validate, ValidationError, ValidationErrors};
+    use entity::sea_orm::TransactionTrait;
 
     use super::*;
 
-    pub async fn profile_exists(state: web::Data<AppState>, auth: BearerAuth) -> impl Responder {
-        return match get_profile_by_auth(&auth, &state).await {
-            Ok((Some(_), _)) => HttpResponse::Ok(),
-            Ok((None, _)) => HttpResponse::NotFound(),
-            Err(_) => HttpResponse::InternalServerError(),
-        };
+    use crate::errors::ServiceError;
+    use crate::webhooks;
+
+    /// Format of `date_of_birth` in the API, as enforced by its validation.
+    const DATE_OF_BIRTH_FORMAT: &str = "%d-%m-%Y";
+
+    pub async fn profile_exists(
+        state: web::Data<AppState>,
+        auth: BearerAuth,
+    ) -> Result<HttpResponse, ServiceError> {
+        match get_profile_by_auth(&auth, &state).await? {
+            (Some(_), _) => Ok(HttpResponse::Ok().finish()),
+            (None, _) => Err(ServiceError::NotFound("No profile yet".to_string())),
+        }
     }
 
     pub async fn set_user_profile(
         state: web::Data<AppState>,
         dto: web::Json<api_models::models::UserProfile>,
         auth: BearerAuth,
-    ) -> impl Responder {
-        if dto.validate().is_err() {
-            return HttpResponse::BadRequest();
-        }
-        let (id, auth_id) = match get_profile_by_auth(&auth, &state).await {
-            Ok((Some(e), _)) => (Unchanged(e.id), Unchanged(e.auth_id)),
-            Ok((None, a)) => (NotSet, Set(a)),
-            Err(err) => {
-                log::error!("Db Error: {}", err);
-                return HttpResponse::InternalServerError();
-            }
+    ) -> Result<HttpResponse, ServiceError> {
+        dto.validate()?;
+        let date_of_birth = match &dto.date_of_birth {
+            Some(d) => Some(
+                Date::parse_from_str(d.as_str(), DATE_OF_BIRTH_FORMAT).map_err(|_| {
+                    let mut errors = ValidationErrors::new();
+                    let mut error = ValidationError::new("date");
+                    error.message = Some("is not a valid date".into());
+                    errors.add("date_of_birth", error);
+                    ServiceError::Validation(errors)
+                })?,
+            ),
+            None => None,
+        };
+        let (id, auth_id, exists) = match get_profile_by_auth(&auth, &state).await? {
+            (Some(e), _) => (Unchanged(e.id), Unchanged(e.auth_id), true),
+            (None, a) => (NotSet, Set(a), false),
+        };
+        let email = token_email(&auth, &state).await?;
+        // letters and forms need the address of the user
+        let status = match dto.address {
+            Some(_) => user_profile::ProfileStatus::ProfileComplete,
+            None => user_profile::ProfileStatus::ProfileIncomplete,
         };
 
         let new_profile = user_profile::ActiveModel {
@@ -59,176 +95,2106 @@ pub mod profile {
             auth_id,
             address: Set(serde_json::to_value(&dto.address).ok()),
             date_created: NotSet,
-            date_of_birth: Set(dto
-                .date_of_birth
-                .clone()
-                .map(|d| Date::parse_from_str(d.as_str(), "%Y/%m/%d").ok().unwrap())),
+            date_of_birth: Set(date_of_birth),
             firstname: Set(dto.first_name.clone()),
             lastname: Set(dto.last_name.clone()),
             preferred_language: Set(dto.preferred_language.clone()),
-            status: Set(user_profile::ProfileStatus::ProfileIncomplete),
+            status: Set(status),
+            email: Set(email),
+            notify_upcoming_debits: Set(dto.notifications.upcoming_debits),
+            notify_debit_alerts: Set(dto.notifications.debit_alerts),
+            notify_profile_reminders: Set(dto.notifications.profile_reminders),
+            notify_expired_mandates: Set(dto.notifications.expired_mandates),
         };
-        let np = new_profile.save(&state.connection).await;
+        let txn = state.connection.begin().await.map_err(db_error)?;
+        let np = if exists {
+            new_profile.update(&txn).await
+        } else {
+            new_profile.insert(&txn).await
+        }
+        .map_err(db_error)?;
+        webhooks::enqueue(&txn, np.id, WebhookEvent::ProfileUpdated, &to_dto(&np))
+            .await
+            .map_err(db_error)?;
+        txn.commit().await.map_err(db_error)?;
         debug!("Saved {:?}", np);
-        return HttpResponse::Ok();
-    }
-
-    pub async fn get_user_profile(auth: BearerAuth, state: web::Data<AppState>) -> impl Responder {
-        return match get_profile_by_auth(&auth, &state).await {
-            Ok((Some(user), _)) => {
-                let result = api_models::models::UserProfile {
-                    address: user
-                        .address
-                        .clone()
-                        .map(|ajson| serde_json::from_value(ajson).unwrap()),
-                    date_of_birth: user.date_of_birth.map(|db| db.to_string()),
-                    first_name: user.firstname.clone(),
-                    last_name: user.lastname.clone(),
-                    preferred_language: user.preferred_language.clone(),
-                };
-                return HttpResponse::Ok().json(web::Json(result).0);
-            }
-            Ok((None, _)) => HttpResponse::NotFound().finish(),
-            Err(_) => HttpResponse::InternalServerError().finish(),
-        };
+        Ok(HttpResponse::Ok().finish())
+    }
+
+    pub async fn get_user_profile(
+        auth: BearerAuth,
+        state: web::Data<AppState>,
+    ) -> Result<HttpResponse, ServiceError> {
+        let (user, _) = require_profile(&auth, &state).await?;
+        Ok(HttpResponse::Ok().json(to_dto(&user)))
+    }
+
+    fn to_dto(user: &Model) -> api_models::models::UserProfile {
+        api_models::models::UserProfile {
+            address: user
+                .address
+                .clone()
+                .and_then(|ajson| serde_json::from_value(ajson).ok()),
+            date_of_birth: user
+                .date_of_birth
+                .map(|db| db.format(DATE_OF_BIRTH_FORMAT).to_string()),
+            first_name: user.firstname.clone(),
+            last_name: user.lastname.clone(),
+            preferred_language: user.preferred_language.clone(),
+            email: user.email.clone(),
+            notifications: NotificationSettings {
+                upcoming_debits: user.notify_upcoming_debits,
+                debit_alerts: user.notify_debit_alerts,
+                profile_reminders: user.notify_profile_reminders,
+                expired_mandates: user.notify_expired_mandates,
+            },
+        }
+    }
+
+    /// The `email` claim of the token, unless the provider says it is not verified.
+    async fn token_email(
+        auth: &BearerAuth,
+        state: &web::Data<AppState>,
+    ) -> Result<Option<String>, ServiceError> {
+        let td = auth::get_token_data(auth.token(), &state.auth).await?;
+        if td.claims.get("email_verified") == Some(&serde_json::Value::Bool(false)) {
+            return Ok(None);
+        }
+        Ok(td
+            .claims
+            .get("email")
+            .and_then(|email| email.as_str())
+            .map(str::to_string))
     }
 
     pub async fn get_profile_by_auth(
         auth: &BearerAuth,
         state: &web::Data<AppState>,
-    ) -> Result<(Option<Model>, String), entity::sea_orm::DbErr> {
-        let td = auth::get_token_data(auth.token())
-            .await
-            .expect("Token data is not valid");
+    ) -> Result<(Option<Model>, String), ServiceError> {
+        let td = auth::get_token_data(auth.token(), &state.auth).await?;
         let auth_id = td
             .claims
             .get("sub")
-            .expect("Invalid token format!")
+            .ok_or_else(|| ServiceError::Unauthorized("Token has no subject".to_string()))?
             .to_string();
         let existing = UserProfile::find()
             .filter(AuthId.eq(auth_id.clone()))
             .one(&state.connection)
-            .await?;
-        return Ok((existing, auth_id.clone()));
+            .await
+            .map_err(db_error)?;
+        Ok((existing, auth_id))
+    }
+
+    /// Profile of the caller, every endpoint but the profile itself needs one.
+    pub async fn require_profile(
+        auth: &BearerAuth,
+        state: &web::Data<AppState>,
+    ) -> Result<(Model, String), ServiceError> {
+        match get_profile_by_auth(auth, state).await? {
+            (Some(up), auth_id) => Ok((up, auth_id)),
+            (None, _) => Err(ServiceError::Forbidden(
+                "Create your profile first".to_string(),
+            )),
+        }
     }
 }
 
 pub mod mandate {
-    use std::str::FromStr;
-
     use super::*;
 
+    use crate::errors::ServiceError;
+    use crate::history;
+    use crate::webhooks;
     use api_models::{
-        models::{Mandate as MandateDto, Status},
-        validator::Validate,
+        models::{
+            tag::{normalize_tag, normalize_tags},
+            Mandate as MandateDto, MandateChange, MandatePage, MandateQuery, MandateSort, Status,
+        },
+        validator::{Validate, ValidationError, ValidationErrors},
     };
     use entity::{
         mandate::ActiveModel as MandateActiveModel,
-        mandate::{Column, Entity as MandateEntity, MandateStatus},
-        sea_orm::{ModelTrait, QueryOrder},
+        mandate::{
+            Column, Entity as MandateEntity, MandateFrequency, MandatePaymentType, MandateScheme,
+            MandateStatus,
+        },
+        mandate_history::{self, Entity as MandateHistoryEntity},
+        sea_orm::{
+            sea_query::{Expr, IntoColumnRef, SimpleExpr},
+            Condition, ModelTrait, Order, QueryOrder, QuerySelect, TransactionTrait, Value,
+        },
     };
     use serde_json::json;
 
-    pub async fn get_mandates(_auth: BearerAuth, _state: web::Data<AppState>) -> impl Responder {
-        let up = match profile::get_profile_by_auth(&_auth, &_state).await {
-            Ok((Some(up), _)) => up,
-            Ok((None, _)) => return HttpResponse::Forbidden().finish(),
-            Err(_) => return HttpResponse::InternalServerError().finish(),
-        };
-        let result = up
-            .find_related(MandateEntity)
-            .order_by_asc(Column::DateCreated)
-            .all(&_state.connection)
-            .await;
-        return match result {
-            Ok(res) => {
-                let mandates: Vec<MandateDto> = res
-                    .iter()
-                    .map(|m| {
-                        MandateDto {
-                            api_id: m.api_id,
-                            status: Status::from_str(m.status.clone().into())
-                                .expect("Unknown mandate state"),
-                            unique_reference: m.unique_reference.clone(),
-                            display_name: m.display_name.clone(),
-                            date_created: Some(m.date_created.to_string()), // todo ?
-                            creditor: serde_json::from_value(m.creditor.clone())
-                                .unwrap_or_default(),
-                            bank_account: serde_json::from_value(m.bank_account.clone())
-                                .unwrap_or_default(),
-                            tags: m
-                                .tags
-                                .as_array()
-                                .unwrap()
-                                .iter()
-                                .map(|st| st.as_str().unwrap().to_owned())
-                                .collect(),
-                        }
-                    })
-                    .collect();
-                HttpResponse::Ok().json(mandates)
-            }
-            Err(e) => {
+    pub async fn get_mandates(
+        auth: BearerAuth,
+        state: web::Data<AppState>,
+        query: web::Query<MandateQuery>,
+    ) -> Result<HttpResponse, ServiceError> {
+        let (up, _) = profile::require_profile(&auth, &state).await?;
+        let query = query.into_inner();
+        let mut select = up.find_related(MandateEntity);
+        if let Some(status) = query.status {
+            select = select.filter(Column::Status.eq(MandateStatus::from(status)));
+        }
+        if let Some(tag) = &query.tag {
+            // tags are stored normalized
+            select = select.filter(Expr::cust_with_values(
+                "tags @> ?::jsonb",
+                vec![json!([normalize_tag(tag)]).to_string()],
+            ));
+        }
+        if let Some(creditor) = &query.creditor {
+            select = select.filter(Expr::cust_with_values(
+                "creditor->>'name' ILIKE ?",
+                vec![like_pattern(creditor)],
+            ));
+        }
+        if let Some(iban) = &query.iban {
+            let iban: String = iban
+                .chars()
+                .filter(|c| !c.is_whitespace())
+                .map(|c| c.to_ascii_uppercase())
+                .collect();
+            select = select.filter(Expr::cust_with_values(
+                "bank_account_id IN (SELECT id FROM bank_account WHERE iban = ?)",
+                vec![iban],
+            ));
+        }
+        if let Some(q) = query.q.as_deref().map(str::trim).filter(|q| !q.is_empty()) {
+            let pattern = like_pattern(q);
+            select = select.filter(Expr::cust_with_values(
+                "(display_name ILIKE ? OR unique_reference ILIKE ? OR creditor->>'name' ILIKE ?)",
+                vec![pattern.clone(), pattern.clone(), pattern],
+            ));
+        }
+        if let Some(cursor) = query.cursor {
+            let after = find_owned_mandate(&state, &up, cursor)
+                .await?
+                .ok_or_else(|| ServiceError::BadRequest(format!("Unknown cursor {}", cursor)))?;
+            select = select.filter(after_cursor(&query, &after));
+        }
+        let order = if query.desc { Order::Desc } else { Order::Asc };
+        let page_size = query.page_size();
+        let mut mandates = select
+            .order_by(sort_expr(query.sort), order.clone())
+            .order_by(Column::Id, order)
+            .limit(page_size + 1)
+            .all(&state.connection)
+            .await
+            .map_err(|e| {
                 error!("Error returning mandates for {} {:?}", up.id, e);
-                HttpResponse::InternalServerError().finish()
-            }
+                ServiceError::InternalServerError
+            })?;
+        let next_cursor = if mandates.len() as u64 > page_size {
+            mandates.truncate(page_size as usize);
+            mandates.last().map(|m| m.api_id)
+        } else {
+            None
+        };
+        let accounts =
+            account::load_accounts(&state, mandates.iter().map(|m| m.bank_account_id)).await?;
+        Ok(HttpResponse::Ok().json(MandatePage {
+            items: mandates
+                .iter()
+                .map(|m| to_dto(m, accounts.get(&m.bank_account_id)))
+                .collect(),
+            next_cursor,
+        }))
+    }
+
+    /// Substring pattern for ILIKE, wildcards typed by the user match literally.
+    fn like_pattern(value: &str) -> String {
+        let escaped = value
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_");
+        format!("%{}%", escaped)
+    }
+
+    fn sort_expr(sort: MandateSort) -> SimpleExpr {
+        match sort {
+            MandateSort::DateCreated => SimpleExpr::Column(Column::DateCreated.into_column_ref()),
+            MandateSort::DisplayName => SimpleExpr::Column(Column::DisplayName.into_column_ref()),
+            MandateSort::CreditorName => Expr::cust("creditor->>'name'"),
+        }
+    }
+
+    /// Keyset condition: everything sorted after the cursor mandate, ties broken by id.
+    fn after_cursor(query: &MandateQuery, cursor: &entity::mandate::Model) -> Condition {
+        let value: Value = match query.sort {
+            MandateSort::DateCreated => cursor.date_created.into(),
+            MandateSort::DisplayName => cursor.display_name.clone().into(),
+            MandateSort::CreditorName => cursor
+                .creditor
+                .get("name")
+                .and_then(|name| name.as_str())
+                .unwrap_or_default()
+                .to_string()
+                .into(),
+        };
+        let sort = sort_expr(query.sort);
+        let (beyond, tie_break) = if query.desc {
+            (
+                Expr::expr(sort.clone()).lt(value.clone()),
+                Column::Id.lt(cursor.id),
+            )
+        } else {
+            (
+                Expr::expr(sort.clone()).gt(value.clone()),
+                Column::Id.gt(cursor.id),
+            )
         };
+        Condition::any()
+            .add(beyond)
+            .add(Expr::expr(sort).eq(value).and(tie_break))
     }
 
+    pub(crate) fn to_dto(
+        m: &entity::mandate::Model,
+        bank_account: Option<&entity::bank_account::Model>,
+    ) -> MandateDto {
+        MandateDto {
+            api_id: m.api_id,
+            status: m.status.clone().into(),
+            unique_reference: m.unique_reference.clone(),
+            display_name: m.display_name.clone(),
+            date_created: Some(m.date_created.to_string()), // todo ?
+            creditor: serde_json::from_value(m.creditor.clone()).unwrap_or_default(),
+            bank_account: bank_account.map(account::to_dto).unwrap_or_default(),
+            tags: serde_json::from_value(m.tags.clone()).unwrap_or_default(),
+            scheme: m.scheme.clone().into(),
+            payment_type: m.payment_type.clone().into(),
+            signature_date: m.signature_date,
+            signature_place: m.signature_place.clone(),
+            account_holder: m.account_holder.clone(),
+            schedule: m.schedule(),
+            last_collection_date: m.last_collection_date,
+        }
+    }
+
+    async fn mandate_dto(
+        state: &AppState,
+        m: &entity::mandate::Model,
+    ) -> Result<MandateDto, ServiceError> {
+        let accounts = account::load_accounts(state, Some(m.bank_account_id)).await?;
+        Ok(to_dto(m, accounts.get(&m.bank_account_id)))
+    }
+
+    pub async fn get_mandate(
+        auth: BearerAuth,
+        state: web::Data<AppState>,
+        api_id: web::Path<uuid::Uuid>,
+    ) -> Result<HttpResponse, ServiceError> {
+        let (user_profile, _) = profile::require_profile(&auth, &state).await?;
+        let api_id = api_id.into_inner();
+        let mandate = find_owned_mandate(&state, &user_profile, api_id)
+            .await?
+            .ok_or_else(|| not_found(api_id))?;
+        Ok(HttpResponse::Ok().json(mandate_dto(&state, &mandate).await?))
+    }
+
+    /// Upsert by the `api_id` of the body.
     pub async fn save_mandate(
         auth: BearerAuth,
         state: web::Data<AppState>,
         dto: web::Json<MandateDto>,
-    ) -> impl Responder {
-        if let Err(_err) = dto.validate() {
-            // todo errors to body message
-            return HttpResponse::BadRequest();
+    ) -> Result<HttpResponse, ServiceError> {
+        let (user_profile, auth_id) = profile::require_profile(&auth, &state).await?;
+        upsert(&state, &user_profile, &auth_id, dto.into_inner()).await?;
+        Ok(HttpResponse::Ok().finish())
+    }
+
+    /// Creates (201) or replaces (200) the mandate at `api_id`.
+    pub async fn put_mandate(
+        auth: BearerAuth,
+        state: web::Data<AppState>,
+        api_id: web::Path<uuid::Uuid>,
+        dto: web::Json<MandateDto>,
+    ) -> Result<HttpResponse, ServiceError> {
+        let api_id = api_id.into_inner();
+        if dto.api_id != api_id {
+            return Err(ServiceError::BadRequest(format!(
+                "Body is mandate {} but the path is mandate {}",
+                dto.api_id, api_id
+            )));
         }
-        let user_profile = match profile::get_profile_by_auth(&auth, &state).await {
-            Ok((Some(up), _)) => up,
-            Ok((None, _)) => return HttpResponse::Forbidden(),
-            Err(_) => return HttpResponse::InternalServerError(),
-        };
-        let matched_mandate = user_profile
-            .find_related(MandateEntity)
-            .filter(entity::mandate::Column::ApiId.eq(dto.api_id))
+        let (user_profile, auth_id) = profile::require_profile(&auth, &state).await?;
+        let (saved, created) = upsert(&state, &user_profile, &auth_id, dto.into_inner()).await?;
+        let body = mandate_dto(&state, &saved).await?;
+        if created {
+            Ok(HttpResponse::Created()
+                .insert_header(("Location", format!("/api/mandates/{}", api_id)))
+                .json(body))
+        } else {
+            Ok(HttpResponse::Ok().json(body))
+        }
+    }
+
+    /// Soft delete, only mandates whose status may move to DELETED can be deleted.
+    pub async fn delete_mandate(
+        auth: BearerAuth,
+        state: web::Data<AppState>,
+        api_id: web::Path<uuid::Uuid>,
+    ) -> Result<HttpResponse, ServiceError> {
+        let (user_profile, auth_id) = profile::require_profile(&auth, &state).await?;
+        let api_id = api_id.into_inner();
+        let mandate = find_owned_mandate(&state, &user_profile, api_id)
+            .await?
+            .ok_or_else(|| not_found(api_id))?;
+        // only the status changes, so mandates failing today's validation can still be deleted
+        check_transition(&mandate, Status::DELETED)?;
+        let accounts = account::load_accounts(&state, Some(mandate.bank_account_id)).await?;
+        let txn = state.connection.begin().await.map_err(db_error)?;
+        let mut active_model: MandateActiveModel = mandate.clone().into();
+        active_model.status = Set(MandateStatus::DELETED);
+        let saved = active_model.update(&txn).await.map_err(db_error)?;
+        history::record_changes(&txn, &auth_id, Some(&mandate), &saved)
+            .await
+            .map_err(db_error)?;
+        let data = to_dto(&saved, accounts.get(&saved.bank_account_id));
+        webhooks::enqueue_mandate(&txn, Some(&mandate), &saved, &data)
+            .await
+            .map_err(db_error)?;
+        txn.commit().await.map_err(db_error)?;
+        Ok(HttpResponse::NoContent().finish())
+    }
+
+    pub async fn get_mandate_history(
+        auth: BearerAuth,
+        state: web::Data<AppState>,
+        api_id: web::Path<uuid::Uuid>,
+    ) -> Result<HttpResponse, ServiceError> {
+        let (user_profile, _) = profile::require_profile(&auth, &state).await?;
+        let api_id = api_id.into_inner();
+        let mandate = find_owned_mandate(&state, &user_profile, api_id)
+            .await?
+            .ok_or_else(|| not_found(api_id))?;
+        let changes: Vec<MandateChange> = mandate
+            .find_related(MandateHistoryEntity)
+            .order_by_asc(mandate_history::Column::DateChanged)
+            .order_by_asc(mandate_history::Column::Id)
+            .all(&state.connection)
+            .await
+            .map_err(db_error)?
+            .iter()
+            .map(history::to_dto)
+            .collect();
+        Ok(HttpResponse::Ok().json(changes))
+    }
+
+    pub(crate) fn not_found(api_id: uuid::Uuid) -> ServiceError {
+        ServiceError::NotFound(format!("Mandate {} not found", api_id))
+    }
+
+    /// The mandate with `api_id`. A mandate of another profile is reported as not found,
+    /// so it can neither be read nor taken over by reusing its id.
+    pub(crate) async fn find_owned_mandate(
+        state: &AppState,
+        user_profile: &Model,
+        api_id: uuid::Uuid,
+    ) -> Result<Option<entity::mandate::Model>, ServiceError> {
+        match MandateEntity::find()
+            .filter(Column::ApiId.eq(api_id))
             .one(&state.connection)
-            .await;
-        let (id, api_id, user_profile_id) = match matched_mandate {
-            Ok(Some(m)) => (
+            .await
+            .map_err(db_error)?
+        {
+            Some(m) if m.user_profile_id != user_profile.id => Err(not_found(api_id)),
+            found => Ok(found),
+        }
+    }
+
+    /// A Conflict naming the allowed statuses when the mandate can't move to `next`.
+    fn check_transition(mandate: &entity::mandate::Model, next: Status) -> Result<(), ServiceError> {
+        let current = Status::from(mandate.status.clone());
+        if current.can_transition_to(next) {
+            return Ok(());
+        }
+        Err(ServiceError::Conflict(format!(
+            "Mandate {} can not change from {:?} to {:?}, allowed are {:?}",
+            mandate.api_id,
+            current,
+            next,
+            current.transitions()
+        )))
+    }
+
+    /// Validates and stores the mandate with its history, returns it and whether it is new.
+    async fn upsert(
+        state: &AppState,
+        user_profile: &Model,
+        auth_id: &str,
+        mut dto: MandateDto,
+    ) -> Result<(entity::mandate::Model, bool), ServiceError> {
+        dto.validate()?;
+        let today = chrono::Local::now().naive_local().date();
+        for (field, date) in [
+            ("signature_date", dto.signature_date),
+            ("last_collection_date", dto.last_collection_date),
+        ] {
+            if matches!(date, Some(date) if date > today) {
+                let mut errors = ValidationErrors::new();
+                let mut error = ValidationError::new("date");
+                error.message = Some("must not be in the future".into());
+                errors.add(field, error);
+                return Err(ServiceError::Validation(errors));
+            }
+        }
+        dto.bank_account.normalize();
+        dto.creditor.normalize();
+        dto.tags = normalize_tags(&dto.tags);
+        let matched_mandate = find_owned_mandate(state, user_profile, dto.api_id).await?;
+        match &matched_mandate {
+            Some(current) => check_transition(current, dto.status)?,
+            None if !dto.status.is_initial() => {
+                return Err(ServiceError::Conflict(format!(
+                    "New mandate {} must be NEW or ACTIVE, got {:?}",
+                    dto.api_id, dto.status
+                )));
+            }
+            None => {}
+        }
+        let (id, api_id, user_profile_id) = match &matched_mandate {
+            Some(m) => (
                 Unchanged(m.id),
                 Unchanged(m.api_id),
                 Unchanged(m.user_profile_id),
             ),
-            Ok(None) => (NotSet, Set(dto.api_id), Set(user_profile.id)),
-            Err(e) => {
-                error!("Error fetching mandate {}, {:?}", dto.api_id, e);
-                return HttpResponse::InternalServerError();
-            }
+            None => (NotSet, Set(dto.api_id), Set(user_profile.id)),
         };
 
+        let txn = state.connection.begin().await.map_err(db_error)?;
+        let bank_account = account::resolve(&txn, user_profile, &dto.bank_account).await?;
         let active_model = mandate::MandateActiveModel {
             id,
             api_id,
             user_profile_id,
             tags: Set(json!(dto.tags)),
-            status: Set(
-                MandateStatus::from_str(dto.status.clone().into()).expect("Unknown mandate status")
-            ),
+            status: Set(MandateStatus::from(dto.status)),
             unique_reference: Set(dto.unique_reference.clone()),
             display_name: Set(dto.display_name.clone()),
             date_created: NotSet,
             creditor: Set(json!(dto.creditor)),
-            bank_account: Set(json!(dto.bank_account)),
+            bank_account_id: Set(bank_account.id),
+            scheme: Set(MandateScheme::from(dto.scheme)),
+            payment_type: Set(MandatePaymentType::from(dto.payment_type)),
+            signature_date: Set(dto.signature_date),
+            signature_place: Set(dto.signature_place.clone()),
+            account_holder: Set(dto.account_holder.clone()),
+            expected_amount: Set(dto.schedule.as_ref().map(|s| s.amount)),
+            frequency: Set(dto
+                .schedule
+                .as_ref()
+                .map(|s| MandateFrequency::from(s.frequency))),
+            interval_months: Set(dto
+                .schedule
+                .as_ref()
+                .and_then(|s| s.interval_months)
+                .map(|months| months as i32)),
+            anchor_date: Set(dto.schedule.as_ref().map(|s| s.anchor_date)),
+            last_collection_date: Set(dto.last_collection_date),
+        };
+        let saved = match &matched_mandate {
+            Some(_) => active_model.update(&txn).await,
+            None => active_model.insert(&txn).await,
+        }
+        .map_err(|e| {
+            error!("Error persisting dto {:?} {}", dto, e);
+            ServiceError::InternalServerError
+        })?;
+        history::record_changes(&txn, auth_id, matched_mandate.as_ref(), &saved)
+            .await
+            .map_err(db_error)?;
+        let data = to_dto(&saved, Some(&bank_account));
+        webhooks::enqueue_mandate(&txn, matched_mandate.as_ref(), &saved, &data)
+            .await
+            .map_err(db_error)?;
+        txn.commit().await.map_err(db_error)?;
+        Ok((saved, matched_mandate.is_none()))
+    }
+}
+
+pub mod account {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    use crate::errors::ServiceError;
+    use crate::handlers::mandate::to_dto as mandate_dto;
+    use crate::history;
+    use crate::webhooks;
+    use api_models::{
+        models::{AccountSwitch, AccountSwitchResult, BankAccount, SwitchedMandate, WebhookEvent},
+        validator::Validate,
+    };
+    use entity::{
+        bank_account::{self, Column, Entity as BankAccountEntity},
+        mandate::{self, Entity as MandateEntity, MandateStatus},
+        sea_orm::{
+            sea_query::Expr, ConnectionTrait, ModelTrait, PaginatorTrait, QueryOrder,
+            TransactionTrait,
+        },
+    };
+
+    /// Accounts of the user, the primary one first.
+    pub async fn get_accounts(
+        auth: BearerAuth,
+        state: web::Data<AppState>,
+    ) -> Result<HttpResponse, ServiceError> {
+        let (user_profile, _) = profile::require_profile(&auth, &state).await?;
+        let accounts: Vec<BankAccount> = user_profile
+            .find_related(BankAccountEntity)
+            .order_by_desc(Column::IsPrimary)
+            .order_by_asc(Column::DateCreated)
+            .order_by_asc(Column::Id)
+            .all(&state.connection)
+            .await
+            .map_err(db_error)?
+            .iter()
+            .map(to_dto)
+            .collect();
+        Ok(HttpResponse::Ok().json(accounts))
+    }
+
+    pub async fn get_account(
+        auth: BearerAuth,
+        state: web::Data<AppState>,
+        api_id: web::Path<uuid::Uuid>,
+    ) -> Result<HttpResponse, ServiceError> {
+        let (user_profile, _) = profile::require_profile(&auth, &state).await?;
+        let api_id = api_id.into_inner();
+        let account = find_owned_account(&state.connection, &user_profile, api_id)
+            .await?
+            .ok_or_else(|| not_found(api_id))?;
+        Ok(HttpResponse::Ok().json(to_dto(&account)))
+    }
+
+    pub async fn create_account(
+        auth: BearerAuth,
+        state: web::Data<AppState>,
+        dto: web::Json<BankAccount>,
+    ) -> Result<HttpResponse, ServiceError> {
+        dto.validate()?;
+        let mut dto = dto.into_inner();
+        dto.normalize();
+        let (user_profile, _) = profile::require_profile(&auth, &state).await?;
+        let txn = state.connection.begin().await.map_err(db_error)?;
+        let saved = create(&txn, &user_profile, &dto).await?;
+        txn.commit().await.map_err(db_error)?;
+        Ok(HttpResponse::Created()
+            .insert_header(("Location", format!("/api/accounts/{}", saved.api_id)))
+            .json(to_dto(&saved)))
+    }
+
+    /// Changes the account of every mandate collected from it.
+    pub async fn update_account(
+        auth: BearerAuth,
+        state: web::Data<AppState>,
+        api_id: web::Path<uuid::Uuid>,
+        dto: web::Json<BankAccount>,
+    ) -> Result<HttpResponse, ServiceError> {
+        dto.validate()?;
+        let mut dto = dto.into_inner();
+        dto.normalize();
+        let (user_profile, _) = profile::require_profile(&auth, &state).await?;
+        let api_id = api_id.into_inner();
+        let txn = state.connection.begin().await.map_err(db_error)?;
+        let saved = update(&txn, &user_profile, api_id, &dto).await?;
+        txn.commit().await.map_err(db_error)?;
+        Ok(HttpResponse::Ok().json(to_dto(&saved)))
+    }
+
+    /// Only accounts without mandates can be deleted.
+    pub async fn delete_account(
+        auth: BearerAuth,
+        state: web::Data<AppState>,
+        api_id: web::Path<uuid::Uuid>,
+    ) -> Result<HttpResponse, ServiceError> {
+        let (user_profile, _) = profile::require_profile(&auth, &state).await?;
+        let api_id = api_id.into_inner();
+        let txn = state.connection.begin().await.map_err(db_error)?;
+        delete(&txn, &user_profile, api_id).await?;
+        txn.commit().await.map_err(db_error)?;
+        Ok(HttpResponse::NoContent().finish())
+    }
+
+    /// Moves every ACTIVE mandate of the old account to the new one in one transaction
+    /// and returns the moved mandates, their creditors have to be told the new IBAN.
+    pub async fn switch_account(
+        auth: BearerAuth,
+        state: web::Data<AppState>,
+        dto: web::Json<AccountSwitch>,
+    ) -> Result<HttpResponse, ServiceError> {
+        dto.validate()?;
+        let mut dto = dto.into_inner();
+        dto.to.normalize();
+        let (user_profile, auth_id) = profile::require_profile(&auth, &state).await?;
+        let txn = state.connection.begin().await.map_err(db_error)?;
+        let from = find_owned_account(&txn, &user_profile, dto.from)
+            .await?
+            .ok_or_else(|| not_found(dto.from))?;
+        let to = resolve(&txn, &user_profile, &dto.to).await?;
+        if from.id == to.id {
+            return Err(ServiceError::BadRequest(
+                "Old and new bank account must differ".to_string(),
+            ));
+        }
+        let mandates = user_profile
+            .find_related(MandateEntity)
+            .filter(mandate::Column::BankAccountId.eq(from.id))
+            .filter(mandate::Column::Status.eq(MandateStatus::ACTIVE))
+            .order_by_asc(mandate::Column::DateCreated)
+            .order_by_asc(mandate::Column::Id)
+            .all(&txn)
+            .await
+            .map_err(db_error)?;
+        let mut switched = Vec::with_capacity(mandates.len());
+        for m in mandates {
+            let mut active_model: mandate::ActiveModel = m.clone().into();
+            active_model.bank_account_id = Set(to.id);
+            let saved = active_model.update(&txn).await.map_err(db_error)?;
+            history::record_changes(&txn, &auth_id, Some(&m), &saved)
+                .await
+                .map_err(db_error)?;
+            webhooks::enqueue_mandate(&txn, Some(&m), &saved, &mandate_dto(&saved, Some(&to)))
+                .await
+                .map_err(db_error)?;
+            switched.push(SwitchedMandate {
+                api_id: saved.api_id,
+                display_name: saved.display_name.clone(),
+                unique_reference: saved.unique_reference.clone(),
+                creditor: serde_json::from_value(saved.creditor.clone()).unwrap_or_default(),
+            });
+        }
+        txn.commit().await.map_err(db_error)?;
+        debug!(
+            "Moved {} mandate(s) from account {} to {}",
+            switched.len(),
+            from.api_id,
+            to.api_id
+        );
+        Ok(HttpResponse::Ok().json(AccountSwitchResult {
+            from: to_dto(&from),
+            to: to_dto(&to),
+            mandates: switched,
+        }))
+    }
+
+    pub(crate) fn to_dto(a: &bank_account::Model) -> BankAccount {
+        BankAccount {
+            api_id: Some(a.api_id),
+            institution: a.institution.clone(),
+            iban: a.iban.clone(),
+            bic: a.bic.clone(),
+            holder_name: Some(a.holder_name.clone()),
+            nickname: a.nickname.clone(),
+            is_primary: a.is_primary,
+        }
+    }
+
+    /// The accounts with the given ids, by id.
+    pub(crate) async fn load_accounts(
+        state: &AppState,
+        ids: impl IntoIterator<Item = i32>,
+    ) -> Result<HashMap<i32, bank_account::Model>, ServiceError> {
+        let mut ids: Vec<i32> = ids.into_iter().collect();
+        ids.sort_unstable();
+        ids.dedup();
+        if ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        Ok(BankAccountEntity::find()
+            .filter(Column::Id.is_in(ids))
+            .all(&state.connection)
+            .await
+            .map_err(db_error)?
+            .into_iter()
+            .map(|a| (a.id, a))
+            .collect())
+    }
+
+    /// The account a mandate refers to: the stored one with its `api_id`, or
+    /// the account with the same IBAN, which is created if the user has none yet.
+    pub(crate) async fn resolve<C: ConnectionTrait>(
+        db: &C,
+        user_profile: &Model,
+        dto: &BankAccount,
+    ) -> Result<bank_account::Model, ServiceError> {
+        if let Some(api_id) = dto.api_id {
+            return find_owned_account(db, user_profile, api_id)
+                .await?
+                .ok_or_else(|| not_found(api_id));
+        }
+        match find_by_iban(db, user_profile, &dto.iban).await? {
+            Some(account) => Ok(account),
+            None => insert_account(db, user_profile, dto).await,
+        }
+    }
+
+    async fn create<C: ConnectionTrait>(
+        db: &C,
+        user_profile: &Model,
+        dto: &BankAccount,
+    ) -> Result<bank_account::Model, ServiceError> {
+        if find_by_iban(db, user_profile, &dto.iban).await?.is_some() {
+            return Err(duplicate_iban(&dto.iban));
+        }
+        insert_account(db, user_profile, dto).await
+    }
+
+    async fn update<C: ConnectionTrait>(
+        db: &C,
+        user_profile: &Model,
+        api_id: uuid::Uuid,
+        dto: &BankAccount,
+    ) -> Result<bank_account::Model, ServiceError> {
+        let existing = find_owned_account(db, user_profile, api_id)
+            .await?
+            .ok_or_else(|| not_found(api_id))?;
+        match find_by_iban(db, user_profile, &dto.iban).await? {
+            Some(other) if other.id != existing.id => return Err(duplicate_iban(&dto.iban)),
+            _ => {}
+        }
+        if dto.is_primary && !existing.is_primary {
+            clear_primary(db, user_profile).await?;
+        }
+        let mut active_model: bank_account::ActiveModel = existing.clone().into();
+        active_model.holder_name = Set(dto
+            .holder_name
+            .clone()
+            .unwrap_or_else(|| existing.holder_name.clone()));
+        active_model.nickname = Set(dto.nickname.clone());
+        active_model.institution = Set(dto.institution.clone());
+        active_model.iban = Set(dto.iban.clone());
+        active_model.bic = Set(dto.bic.clone());
+        active_model.is_primary = Set(dto.is_primary);
+        let saved = active_model.update(db).await.map_err(db_error)?;
+        // the account is part of the mandates collected from it, the primary flag is not
+        let as_in_mandate = |a: &bank_account::Model| BankAccount {
+            is_primary: false,
+            ..to_dto(a)
         };
+        if as_in_mandate(&existing) != as_in_mandate(&saved) {
+            let mandates = MandateEntity::find()
+                .filter(mandate::Column::BankAccountId.eq(saved.id))
+                .filter(mandate::Column::Status.ne(MandateStatus::DELETED))
+                .all(db)
+                .await
+                .map_err(db_error)?;
+            for m in mandates {
+                let data = mandate_dto(&m, Some(&saved));
+                webhooks::enqueue(db, m.user_profile_id, WebhookEvent::MandateUpdated, &data)
+                    .await
+                    .map_err(db_error)?;
+            }
+        }
+        Ok(saved)
+    }
 
-        let result = active_model.save(&state.connection).await;
-        return match result {
-            Ok(_) => HttpResponse::Ok(),
-            Err(e) => {
-                error!("Error persisting dto {:?} {}", dto, e);
-                HttpResponse::InternalServerError()
+    async fn delete<C: ConnectionTrait>(
+        db: &C,
+        user_profile: &Model,
+        api_id: uuid::Uuid,
+    ) -> Result<(), ServiceError> {
+        let account = find_owned_account(db, user_profile, api_id)
+            .await?
+            .ok_or_else(|| not_found(api_id))?;
+        let mandates = MandateEntity::find()
+            .filter(mandate::Column::BankAccountId.eq(account.id))
+            .count(db)
+            .await
+            .map_err(db_error)?;
+        if mandates > 0 {
+            return Err(ServiceError::Conflict(format!(
+                "Bank account {} is used by {} mandate(s)",
+                api_id, mandates
+            )));
+        }
+        let was_primary = account.is_primary;
+        account.delete(db).await.map_err(db_error)?;
+        if was_primary {
+            // the oldest remaining account takes over
+            if let Some(next) = user_profile
+                .find_related(BankAccountEntity)
+                .order_by_asc(Column::DateCreated)
+                .order_by_asc(Column::Id)
+                .one(db)
+                .await
+                .map_err(db_error)?
+            {
+                let mut active_model: bank_account::ActiveModel = next.into();
+                active_model.is_primary = Set(true);
+                active_model.update(db).await.map_err(db_error)?;
             }
+        }
+        Ok(())
+    }
+
+    fn not_found(api_id: uuid::Uuid) -> ServiceError {
+        ServiceError::NotFound(format!("Bank account {} not found", api_id))
+    }
+
+    fn duplicate_iban(iban: &str) -> ServiceError {
+        ServiceError::Conflict(format!("There is already a bank account with IBAN {}", iban))
+    }
+
+    /// Like mandates, accounts of other profiles are reported as not found.
+    async fn find_owned_account<C: ConnectionTrait>(
+        db: &C,
+        user_profile: &Model,
+        api_id: uuid::Uuid,
+    ) -> Result<Option<bank_account::Model>, ServiceError> {
+        match BankAccountEntity::find()
+            .filter(Column::ApiId.eq(api_id))
+            .one(db)
+            .await
+            .map_err(db_error)?
+        {
+            Some(a) if a.user_profile_id != user_profile.id => Err(not_found(api_id)),
+            found => Ok(found),
+        }
+    }
+
+    async fn find_by_iban<C: ConnectionTrait>(
+        db: &C,
+        user_profile: &Model,
+        iban: &str,
+    ) -> Result<Option<bank_account::Model>, ServiceError> {
+        user_profile
+            .find_related(BankAccountEntity)
+            .filter(Column::Iban.eq(iban))
+            .one(db)
+            .await
+            .map_err(db_error)
+    }
+
+    async fn clear_primary<C: ConnectionTrait>(
+        db: &C,
+        user_profile: &Model,
+    ) -> Result<(), ServiceError> {
+        BankAccountEntity::update_many()
+            .col_expr(Column::IsPrimary, Expr::value(false))
+            .filter(Column::UserProfileId.eq(user_profile.id))
+            .filter(Column::IsPrimary.eq(true))
+            .exec(db)
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    /// The first account of the user always becomes the primary one.
+    async fn insert_account<C: ConnectionTrait>(
+        db: &C,
+        user_profile: &Model,
+        dto: &BankAccount,
+    ) -> Result<bank_account::Model, ServiceError> {
+        let has_accounts = user_profile
+            .find_related(BankAccountEntity)
+            .count(db)
+            .await
+            .map_err(db_error)?
+            > 0;
+        let is_primary = dto.is_primary || !has_accounts;
+        if is_primary && has_accounts {
+            clear_primary(db, user_profile).await?;
+        }
+        bank_account::ActiveModel {
+            id: NotSet,
+            api_id: Set(uuid::Uuid::new_v4()),
+            user_profile_id: Set(user_profile.id),
+            holder_name: Set(dto.holder_name.clone().unwrap_or_else(|| {
+                format!("{} {}", user_profile.firstname, user_profile.lastname)
+            })),
+            nickname: Set(dto.nickname.clone()),
+            institution: Set(dto.institution.clone()),
+            iban: Set(dto.iban.clone()),
+            bic: Set(dto.bic.clone()),
+            is_primary: Set(is_primary),
+            date_created: NotSet,
+        }
+        .insert(db)
+        .await
+        .map_err(db_error)
+    }
+
+    #[cfg(test)]
+    mod test {
+        use api_models::models::BankAccount;
+        use entity::{
+            sea_orm::{
+                ActiveModelTrait, ActiveValue::NotSet, ColumnTrait, ConnectionTrait, EntityTrait,
+                QueryFilter, Set,
+            },
+            webhook_delivery, webhook_subscription,
         };
+        use serde_json::json;
+
+        use super::{create, delete, find_owned_account, resolve, update};
+        use crate::errors::ServiceError;
+        use crate::testing::{begin, insert_mandate, insert_profile};
+
+        const IBAN: &str = "DE89370400440532013000";
+        const OTHER_IBAN: &str = "DE02120300000000202051";
+
+        fn dto(iban: &str) -> BankAccount {
+            BankAccount {
+                institution: "Commerzbank".to_string(),
+                iban: iban.to_string(),
+                ..Default::default()
+            }
+        }
+
+        async fn is_primary<C: ConnectionTrait>(
+            db: &C,
+            user_profile: &entity::user_profile::Model,
+            api_id: uuid::Uuid,
+        ) -> bool {
+            find_owned_account(db, user_profile, api_id)
+                .await
+                .unwrap()
+                .unwrap()
+                .is_primary
+        }
+
+        #[actix_web::test]
+        #[ignore = "needs TEST_DATABASE_URL"]
+        async fn test_resolve() {
+            let txn = begin().await;
+            let profile = insert_profile(&txn).await;
+            let first = resolve(&txn, &profile, &dto(IBAN)).await.unwrap();
+            assert!(first.is_primary);
+            assert_eq!("Erika Mustermann", first.holder_name);
+            // the same IBAN is the same account
+            assert_eq!(
+                first.id,
+                resolve(&txn, &profile, &dto(IBAN)).await.unwrap().id
+            );
+            let second = resolve(&txn, &profile, &dto(OTHER_IBAN)).await.unwrap();
+            assert!(!second.is_primary);
+            let mut by_id = dto(IBAN);
+            by_id.api_id = Some(second.api_id);
+            assert_eq!(second.id, resolve(&txn, &profile, &by_id).await.unwrap().id);
+
+            let stranger = insert_profile(&txn).await;
+            assert!(matches!(
+                resolve(&txn, &stranger, &by_id).await,
+                Err(ServiceError::NotFound(_))
+            ));
+        }
+
+        #[actix_web::test]
+        #[ignore = "needs TEST_DATABASE_URL"]
+        async fn test_create_update_and_delete() {
+            let txn = begin().await;
+            let profile = insert_profile(&txn).await;
+            let first = create(&txn, &profile, &dto(IBAN)).await.unwrap();
+            assert!(matches!(
+                create(&txn, &profile, &dto(IBAN)).await,
+                Err(ServiceError::Conflict(_))
+            ));
+            let mut primary = dto(OTHER_IBAN);
+            primary.is_primary = true;
+            let second = create(&txn, &profile, &primary).await.unwrap();
+            assert!(second.is_primary);
+            assert!(!is_primary(&txn, &profile, first.api_id).await);
+
+            assert!(matches!(
+                update(&txn, &profile, first.api_id, &dto(OTHER_IBAN)).await,
+                Err(ServiceError::Conflict(_))
+            ));
+            let mut changed = dto(IBAN);
+            changed.nickname = Some("Household".to_string());
+            changed.is_primary = true;
+            let updated = update(&txn, &profile, first.api_id, &changed)
+                .await
+                .unwrap();
+            assert_eq!(Some("Household".to_string()), updated.nickname);
+            assert_eq!("Erika Mustermann", updated.holder_name);
+            assert!(updated.is_primary);
+            assert!(!is_primary(&txn, &profile, second.api_id).await);
+
+            insert_mandate(&txn, &second).await;
+            let subscription = webhook_subscription::ActiveModel {
+                id: NotSet,
+                api_id: Set(uuid::Uuid::new_v4()),
+                user_profile_id: Set(profile.id),
+                url: Set("https://example.com/hook".to_string()),
+                secret: Set("0123456789abcdef".to_string()),
+                events: Set(json!(["mandate.updated"])),
+                active: Set(true),
+                date_created: NotSet,
+            }
+            .insert(&txn)
+            .await
+            .unwrap();
+            let deliveries = || {
+                webhook_delivery::Entity::find()
+                    .filter(webhook_delivery::Column::SubscriptionId.eq(subscription.id))
+                    .all(&txn)
+            };
+            // the mandates collected from the account change with it
+            let mut changed = dto(OTHER_IBAN);
+            changed.bic = Some("BYLADEM1001".to_string());
+            update(&txn, &profile, second.api_id, &changed).await.unwrap();
+            let queued = deliveries().await.unwrap();
+            assert_eq!(1, queued.len());
+            assert_eq!("mandate.updated", queued[0].event);
+            assert_eq!(
+                json!("BYLADEM1001"),
+                queued[0].payload["data"]["bank_account"]["bic"]
+            );
+            // nothing changed
+            update(&txn, &profile, second.api_id, &changed).await.unwrap();
+            assert_eq!(1, deliveries().await.unwrap().len());
+
+            assert!(matches!(
+                delete(&txn, &profile, second.api_id).await,
+                Err(ServiceError::Conflict(_))
+            ));
+            // the remaining account takes over from the deleted primary one
+            delete(&txn, &profile, first.api_id).await.unwrap();
+            assert!(is_primary(&txn, &profile, second.api_id).await);
+            assert!(matches!(
+                delete(&txn, &profile, first.api_id).await,
+                Err(ServiceError::NotFound(_))
+            ));
+        }
+    }
+}
+
+pub mod tag {
+    use super::*;
+
+    use crate::errors::ServiceError;
+    use crate::history;
+    use api_models::{
+        models::{
+            tag::{merge_tags, normalize_tag},
+            TagCount, TagMerge, TagRename,
+        },
+        validator::Validate,
+    };
+    use entity::{
+        mandate::{ActiveModel as MandateActiveModel, Entity as MandateEntity},
+        sea_orm::{ConnectionTrait, DbBackend, ModelTrait, Statement, TransactionTrait},
+    };
+    use serde_json::json;
+
+    /// Tags of the user's mandates with their usage, most used first.
+    pub async fn get_tags(
+        auth: BearerAuth,
+        state: web::Data<AppState>,
+    ) -> Result<HttpResponse, ServiceError> {
+        let (user_profile, _) = profile::require_profile(&auth, &state).await?;
+        Ok(HttpResponse::Ok().json(tag_counts(&state, &user_profile).await?))
+    }
+
+    pub async fn rename_tag(
+        auth: BearerAuth,
+        state: web::Data<AppState>,
+        tag: web::Path<String>,
+        dto: web::Json<TagRename>,
+    ) -> Result<HttpResponse, ServiceError> {
+        dto.validate()?;
+        let (user_profile, auth_id) = profile::require_profile(&auth, &state).await?;
+        let from = vec![tag.into_inner()];
+        merge(&state, &user_profile, &auth_id, &from, &dto.name).await?;
+        Ok(HttpResponse::Ok().json(tag_counts(&state, &user_profile).await?))
+    }
+
+    pub async fn merge_tag(
+        auth: BearerAuth,
+        state: web::Data<AppState>,
+        dto: web::Json<TagMerge>,
+    ) -> Result<HttpResponse, ServiceError> {
+        dto.validate()?;
+        let (user_profile, auth_id) = profile::require_profile(&auth, &state).await?;
+        merge(&state, &user_profile, &auth_id, &dto.tags, &dto.into).await?;
+        Ok(HttpResponse::Ok().json(tag_counts(&state, &user_profile).await?))
+    }
+
+    async fn tag_counts(
+        state: &AppState,
+        user_profile: &Model,
+    ) -> Result<Vec<TagCount>, ServiceError> {
+        let sql = "select t.tag, count(*) as count
+            from mandate m, jsonb_array_elements_text(m.tags) as t(tag)
+            where m.user_profile_id = $1
+            group by t.tag
+            order by count desc, t.tag";
+        let stmt =
+            Statement::from_sql_and_values(DbBackend::Postgres, sql, vec![user_profile.id.into()]);
+        state
+            .connection
+            .query_all(stmt)
+            .await
+            .map_err(db_error)?
+            .iter()
+            .map(|row| {
+                Ok(TagCount {
+                    tag: row.try_get("", "tag")?,
+                    count: row.try_get::<i64>("", "count")? as u64,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(db_error)
+    }
+
+    /// Replaces `from` by `into` on all mandates of the user, recording the history.
+    async fn merge(
+        state: &AppState,
+        user_profile: &Model,
+        auth_id: &str,
+        from: &[String],
+        into: &str,
+    ) -> Result<usize, ServiceError> {
+        let into = normalize_tag(into);
+        let mandates = user_profile
+            .find_related(MandateEntity)
+            .all(&state.connection)
+            .await
+            .map_err(db_error)?;
+        let txn = state.connection.begin().await.map_err(db_error)?;
+        let mut changed = 0;
+        for mandate in mandates {
+            let tags: Vec<String> =
+                serde_json::from_value(mandate.tags.clone()).unwrap_or_default();
+            let merged = match merge_tags(&tags, from, &into) {
+                Some(merged) => merged,
+                None => continue,
+            };
+            let mut active_model: MandateActiveModel = mandate.clone().into();
+            active_model.tags = Set(json!(merged));
+            let saved = active_model.update(&txn).await.map_err(db_error)?;
+            history::record_changes(&txn, auth_id, Some(&mandate), &saved)
+                .await
+                .map_err(db_error)?;
+            changed += 1;
+        }
+        if changed == 0 {
+            return Err(ServiceError::NotFound(format!(
+                "No mandate is tagged {}",
+                from.join(", ")
+            )));
+        }
+        txn.commit().await.map_err(db_error)?;
+        Ok(changed)
+    }
+}
+
+pub mod export {
+    use super::*;
+
+    use crate::errors::ServiceError;
+    use crate::pain008::{DirectDebit, Pain008Builder};
+    use api_models::{
+        models::{Creditor, DirectDebitExport, PaymentType, SequenceType, Status},
+        validator::Validate,
+    };
+    use entity::{
+        mandate::{Column, Entity as MandateEntity},
+        sea_orm::ModelTrait,
+    };
+
+    pub async fn pain008(
+        auth: BearerAuth,
+        state: web::Data<AppState>,
+        dto: web::Json<DirectDebitExport>,
+    ) -> Result<HttpResponse, ServiceError> {
+        dto.validate()?;
+        let (user_profile, _) = profile::require_profile(&auth, &state).await?;
+        let api_ids: Vec<uuid::Uuid> = dto.collections.iter().map(|c| c.mandate_api_id).collect();
+        let mandates = user_profile
+            .find_related(MandateEntity)
+            .filter(Column::ApiId.is_in(api_ids))
+            .all(&state.connection)
+            .await
+            .map_err(|e| {
+                error!("Error fetching mandates for export {:?}", e);
+                ServiceError::InternalServerError
+            })?;
+
+        let accounts =
+            account::load_accounts(&state, mandates.iter().map(|m| m.bank_account_id)).await?;
+
+        let message_id = uuid::Uuid::new_v4().simple().to_string();
+        let debtor_name = format!("{} {}", user_profile.firstname, user_profile.lastname);
+        let mut creditor_account = dto.creditor_account.clone();
+        creditor_account.normalize();
+        let mut builder =
+            Pain008Builder::new(dto.version, &message_id, &debtor_name, creditor_account)?;
+        for (index, collection) in dto.collections.iter().enumerate() {
+            let mandate = mandates
+                .iter()
+                .find(|m| m.api_id == collection.mandate_api_id)
+                .ok_or_else(|| {
+                    ServiceError::BadRequest(format!(
+                        "Unknown mandate {}",
+                        collection.mandate_api_id
+                    ))
+                })?;
+            if Status::from(mandate.status.clone()) != Status::ACTIVE {
+                return Err(ServiceError::BadRequest(format!(
+                    "Mandate {} is not active",
+                    mandate.api_id
+                )));
+            }
+            // one-off mandates are collected exactly once, as OOFF
+            let payment_type = PaymentType::from(mandate.payment_type.clone());
+            let one_off = collection.sequence_type == SequenceType::OOFF;
+            if one_off != (payment_type == PaymentType::OneOff) {
+                return Err(ServiceError::BadRequest(format!(
+                    "Sequence type {:?} does not match the {:?} mandate {}",
+                    collection.sequence_type, payment_type, mandate.api_id
+                )));
+            }
+            let mandate_reference = mandate.unique_reference.clone().ok_or_else(|| {
+                ServiceError::BadRequest(format!("Mandate {} has no reference", mandate.api_id))
+            })?;
+            let creditor: Creditor =
+                serde_json::from_value(mandate.creditor.clone()).unwrap_or_default();
+            let debtor_account = accounts.get(&mandate.bank_account_id);
+            let creditor_identifier = creditor.creditor_identifier().ok_or_else(|| {
+                ServiceError::BadRequest(format!(
+                    "Creditor of mandate {} has no valid creditor identifier",
+                    mandate.api_id
+                ))
+            })?;
+            builder.add(DirectDebit {
+                end_to_end_id: format!("{}-{}", &message_id[0..24], index + 1),
+                amount: collection.amount,
+                collection_date: collection.collection_date,
+                scheme: mandate.scheme.clone().into(),
+                sequence_type: collection.sequence_type,
+                mandate_reference,
+                signature_date: mandate.signed_on(),
+                creditor_identifier: creditor_identifier.to_string(),
+                creditor,
+                debtor_name: mandate.account_holder.clone().unwrap_or_else(|| {
+                    debtor_account.map_or_else(|| debtor_name.clone(), |a| a.holder_name.clone())
+                }),
+                debtor_account: debtor_account.map(account::to_dto).unwrap_or_default(),
+                remittance_information: collection.remittance_information.clone(),
+            })?;
+        }
+        let xml = builder.build(chrono::Local::now().naive_local());
+        Ok(HttpResponse::Ok()
+            .content_type("application/xml")
+            .insert_header((
+                "Content-Disposition",
+                format!("attachment; filename=\"{}.xml\"", message_id),
+            ))
+            .body(xml))
+    }
+}
+
+pub mod letter {
+    use super::*;
+
+    use crate::errors::ServiceError;
+    use crate::letters::{self, Debit, Debtor, LetterData, MandateInfo};
+    use crate::mandate_form::{self, MandateForm};
+    use api_models::models::{
+        letter::letter_language, BankAccount, LetterKind, LetterQuery, MandateFormQuery,
+    };
+    use entity::{
+        bank_transaction::{self, Entity as BankTransactionEntity},
+        mandate::MandatePaymentType,
+        mandate_history::{self, Entity as MandateHistoryEntity},
+        sea_orm::QueryOrder,
+    };
+
+    /// One of the built-in letters to the creditor of a mandate, as text, HTML or PDF.
+    pub async fn get_letter(
+        auth: BearerAuth,
+        state: web::Data<AppState>,
+        path: web::Path<(uuid::Uuid, LetterKind)>,
+        query: web::Query<LetterQuery>,
+    ) -> Result<HttpResponse, ServiceError> {
+        let (user_profile, _) = profile::require_profile(&auth, &state).await?;
+        let (api_id, kind) = path.into_inner();
+        let mandate = mandate::find_owned_mandate(&state, &user_profile, api_id)
+            .await?
+            .ok_or_else(|| mandate::not_found(api_id))?;
+        let debtor_name = format!("{} {}", user_profile.firstname, user_profile.lastname);
+        let current_account = current_account(&state, &mandate, &debtor_name).await?;
+        let previous_account = match kind {
+            LetterKind::AccountChange => previous_account(&state, &mandate).await?,
+            _ => None,
+        };
+        let debit = match query.transaction {
+            Some(transaction) => Some(
+                find_debit(&state, &user_profile, &mandate, transaction, &current_account).await?,
+            ),
+            None => None,
+        };
+        let data = LetterData {
+            debtor: Debtor {
+                name: mandate.account_holder.clone().unwrap_or(debtor_name),
+                address: user_profile
+                    .address
+                    .clone()
+                    .and_then(|a| serde_json::from_value(a).ok()),
+            },
+            creditor: serde_json::from_value(mandate.creditor.clone()).unwrap_or_default(),
+            mandate: MandateInfo {
+                reference: mandate.unique_reference.clone(),
+                name: mandate.display_name.clone(),
+                signed: mandate.signed_on(),
+            },
+            account: current_account,
+            previous_account,
+            debit,
+        };
+        let lang = letter_language(
+            query
+                .lang
+                .as_deref()
+                .or(user_profile.preferred_language.as_deref()),
+        );
+        let today = chrono::Local::now().naive_local().date();
+        let body = letters::render(kind, lang, query.format, &data, today).map_err(|e| {
+            error!("Error rendering letter {:?} for mandate {}: {}", kind, api_id, e);
+            ServiceError::InternalServerError
+        })?;
+        Ok(HttpResponse::Ok()
+            .content_type(query.format.content_type())
+            .insert_header((
+                "Content-Disposition",
+                format!(
+                    "attachment; filename=\"{}.{}\"",
+                    letters::title(kind, &data),
+                    query.format.extension()
+                ),
+            ))
+            .body(body))
+    }
+
+    /// The EPC mandate form, pre-filled to be printed and signed.
+    pub async fn get_mandate_form(
+        auth: BearerAuth,
+        state: web::Data<AppState>,
+        api_id: web::Path<uuid::Uuid>,
+        query: web::Query<MandateFormQuery>,
+    ) -> Result<HttpResponse, ServiceError> {
+        let (user_profile, _) = profile::require_profile(&auth, &state).await?;
+        let api_id = api_id.into_inner();
+        let mandate = mandate::find_owned_mandate(&state, &user_profile, api_id)
+            .await?
+            .ok_or_else(|| mandate::not_found(api_id))?;
+        let debtor_name = format!("{} {}", user_profile.firstname, user_profile.lastname);
+        let account = current_account(&state, &mandate, &debtor_name).await?;
+        let debtor_address: Option<api_models::models::Address> = user_profile
+            .address
+            .clone()
+            .and_then(|a| serde_json::from_value(a).ok());
+        let form = MandateForm {
+            scheme: mandate.scheme.clone().into(),
+            creditor: serde_json::from_value(mandate.creditor.clone()).unwrap_or_default(),
+            mandate_reference: mandate.unique_reference.clone(),
+            debtor_name: mandate
+                .account_holder
+                .clone()
+                .or_else(|| account.holder_name.clone())
+                .unwrap_or(debtor_name),
+            // a form to be signed is dated by hand unless the signature date was entered
+            signed_place: mandate
+                .signature_place
+                .clone()
+                .or_else(|| debtor_address.as_ref().map(|a| a.place.clone())),
+            debtor_address,
+            account,
+            recurrent: Some(mandate.payment_type == MandatePaymentType::Recurrent),
+            signed_date: mandate.signature_date,
+        };
+        let lang = letter_language(
+            query
+                .lang
+                .as_deref()
+                .or(user_profile.preferred_language.as_deref()),
+        );
+        let body = mandate_form::render(&form, lang).map_err(|e| {
+            error!("Error rendering the form of mandate {}: {}", api_id, e);
+            ServiceError::InternalServerError
+        })?;
+        Ok(HttpResponse::Ok()
+            .content_type("application/pdf")
+            .insert_header((
+                "Content-Disposition",
+                format!(
+                    "attachment; filename=\"{}.pdf\"",
+                    mandate_form::file_name(&form, &mandate.display_name)
+                ),
+            ))
+            .body(body))
+    }
+
+    /// The account the mandate is collected from, the holder defaults to the user.
+    async fn current_account(
+        state: &AppState,
+        mandate: &entity::mandate::Model,
+        debtor_name: &str,
+    ) -> Result<BankAccount, ServiceError> {
+        let accounts = account::load_accounts(state, Some(mandate.bank_account_id)).await?;
+        let mut current = accounts
+            .get(&mandate.bank_account_id)
+            .map(account::to_dto)
+            .unwrap_or_default();
+        current.holder_name = current.holder_name.or_else(|| Some(debtor_name.to_string()));
+        Ok(current)
+    }
+
+    /// The account of the mandate before its last account change, as recorded in the history.
+    async fn previous_account(
+        state: &AppState,
+        mandate: &entity::mandate::Model,
+    ) -> Result<Option<BankAccount>, ServiceError> {
+        Ok(MandateHistoryEntity::find()
+            .filter(mandate_history::Column::MandateId.eq(mandate.id))
+            .filter(mandate_history::Column::Field.eq("bank_account"))
+            .filter(mandate_history::Column::OldValue.is_not_null())
+            .order_by_desc(mandate_history::Column::DateChanged)
+            .order_by_desc(mandate_history::Column::Id)
+            .one(&state.connection)
+            .await
+            .map_err(db_error)?
+            .and_then(|change| change.old_value)
+            .and_then(|value| serde_json::from_value(value).ok()))
+    }
+
+    /// An imported debit of the user, unmatched or matched to this mandate.
+    async fn find_debit(
+        state: &AppState,
+        user_profile: &Model,
+        mandate: &entity::mandate::Model,
+        api_id: uuid::Uuid,
+        account: &BankAccount,
+    ) -> Result<Debit, ServiceError> {
+        let transaction = BankTransactionEntity::find()
+            .filter(bank_transaction::Column::ApiId.eq(api_id))
+            .filter(bank_transaction::Column::UserProfileId.eq(user_profile.id))
+            .one(&state.connection)
+            .await
+            .map_err(db_error)?
+            .ok_or_else(|| ServiceError::NotFound(format!("Transaction {} not found", api_id)))?;
+        if matches!(transaction.mandate_id, Some(id) if id != mandate.id) {
+            return Err(ServiceError::BadRequest(format!(
+                "Transaction {} belongs to another mandate",
+                api_id
+            )));
+        }
+        Ok(Debit {
+            date: transaction.booking_date,
+            amount: transaction.amount,
+            currency: transaction.currency,
+            iban: transaction
+                .debtor_iban
+                .unwrap_or_else(|| account.iban.clone()),
+            end_to_end_id: transaction.end_to_end_id,
+        })
+    }
+}
+
+pub mod statement {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    use crate::errors::ServiceError;
+    use crate::history;
+    use crate::statement::{self, StatementDebit};
+    use crate::webhooks;
+    use api_models::models::{StatementImport, Transaction, TransactionQuery};
+    use entity::{
+        bank_transaction::{self, Entity as BankTransactionEntity},
+        mandate::Entity as MandateEntity,
+        sea_orm::{ModelTrait, QueryOrder, TransactionTrait},
+    };
+
+    pub async fn import_statement(
+        auth: BearerAuth,
+        state: web::Data<AppState>,
+        body: web::Bytes,
+    ) -> Result<HttpResponse, ServiceError> {
+        let (user_profile, auth_id) = profile::require_profile(&auth, &state).await?;
+        let content = String::from_utf8_lossy(&body);
+        let (format, debits) =
+            statement::parse(&content).map_err(|e| ServiceError::BadRequest(e.to_string()))?;
+
+        let mandates = user_profile
+            .find_related(MandateEntity)
+            .all(&state.connection)
+            .await
+            .map_err(db_error)?;
+        let accounts =
+            account::load_accounts(&state, mandates.iter().map(|m| m.bank_account_id)).await?;
+        let txn = state.connection.begin().await.map_err(db_error)?;
+        let mut result = StatementImport {
+            format: format.to_string(),
+            ..Default::default()
+        };
+        // the latest debit per mandate, it keeps the mandate from expiring
+        let mut last_collections: HashMap<i32, Option<Date>> = HashMap::new();
+        for debit in debits {
+            let fingerprint = debit.fingerprint();
+            let existing = BankTransactionEntity::find()
+                .filter(bank_transaction::Column::UserProfileId.eq(user_profile.id))
+                .filter(bank_transaction::Column::Fingerprint.eq(fingerprint.clone()))
+                .one(&txn)
+                .await
+                .map_err(db_error)?;
+            if existing.is_some() {
+                result.duplicates += 1;
+                continue;
+            }
+            let mandate = statement::match_mandate(&debit, &mandates, &accounts);
+            let saved = new_transaction(&debit, format, user_profile.id, mandate, fingerprint)
+                .insert(&txn)
+                .await
+                .map_err(db_error)?;
+            match mandate {
+                Some(m) => {
+                    if !saved.is_reversal {
+                        let debit_date = saved.value_date.unwrap_or(saved.booking_date);
+                        let last = last_collections.entry(m.id).or_insert(m.last_collection_date);
+                        *last = (*last).max(Some(debit_date));
+                    }
+                    result.matched.push(to_dto(&saved, Some(m.api_id)))
+                }
+                None => result.unmatched.push(to_dto(&saved, None)),
+            }
+        }
+        for m in &mandates {
+            let last_collection_date = match last_collections.get(&m.id) {
+                Some(last) if *last != m.last_collection_date => *last,
+                _ => continue,
+            };
+            let mut active_model: entity::mandate::ActiveModel = m.clone().into();
+            active_model.last_collection_date = Set(last_collection_date);
+            let saved = active_model.update(&txn).await.map_err(db_error)?;
+            // the history explains why the mandate expires, or not
+            history::record_changes(&txn, &auth_id, Some(m), &saved)
+                .await
+                .map_err(db_error)?;
+            let data = mandate::to_dto(&saved, accounts.get(&saved.bank_account_id));
+            webhooks::enqueue_mandate(&txn, Some(m), &saved, &data)
+                .await
+                .map_err(db_error)?;
+        }
+        txn.commit().await.map_err(db_error)?;
+        debug!(
+            "Imported {} statement for {}: {} matched, {} unmatched, {} duplicates",
+            format,
+            user_profile.id,
+            result.matched.len(),
+            result.unmatched.len(),
+            result.duplicates
+        );
+        Ok(HttpResponse::Ok().json(result))
+    }
+
+    pub async fn get_transactions(
+        auth: BearerAuth,
+        state: web::Data<AppState>,
+        query: web::Query<TransactionQuery>,
+    ) -> Result<HttpResponse, ServiceError> {
+        let (user_profile, _) = profile::require_profile(&auth, &state).await?;
+        let mandate_ids: HashMap<i32, uuid::Uuid> = user_profile
+            .find_related(MandateEntity)
+            .all(&state.connection)
+            .await
+            .map_err(db_error)?
+            .iter()
+            .map(|m| (m.id, m.api_id))
+            .collect();
+        let mut select = BankTransactionEntity::find()
+            .filter(bank_transaction::Column::UserProfileId.eq(user_profile.id));
+        if query.unmatched {
+            select = select.filter(bank_transaction::Column::MandateId.is_null());
+        }
+        let transactions: Vec<Transaction> = select
+            .order_by_desc(bank_transaction::Column::BookingDate)
+            .all(&state.connection)
+            .await
+            .map_err(db_error)?
+            .iter()
+            .map(|t| to_dto(t, t.mandate_id.and_then(|id| mandate_ids.get(&id).copied())))
+            .collect();
+        Ok(HttpResponse::Ok().json(transactions))
+    }
+
+    fn new_transaction(
+        debit: &StatementDebit,
+        source: &str,
+        user_profile_id: i32,
+        mandate: Option<&entity::mandate::Model>,
+        fingerprint: String,
+    ) -> bank_transaction::ActiveModel {
+        bank_transaction::ActiveModel {
+            id: NotSet,
+            api_id: Set(uuid::Uuid::new_v4()),
+            user_profile_id: Set(user_profile_id),
+            mandate_id: Set(mandate.map(|m| m.id)),
+            source: Set(source.to_string()),
+            booking_date: Set(debit.booking_date),
+            value_date: Set(debit.value_date),
+            amount: Set(debit.amount),
+            currency: Set(debit.currency.clone()),
+            is_reversal: Set(debit.is_reversal),
+            mandate_reference: Set(debit.mandate_reference.clone()),
+            creditor_identifier: Set(debit.creditor_identifier.clone()),
+            creditor_name: Set(debit.creditor_name.clone()),
+            debtor_iban: Set(debit.debtor_iban.clone()),
+            end_to_end_id: Set(debit.end_to_end_id.clone()),
+            remittance_information: Set(debit.remittance_information.clone()),
+            fingerprint: Set(fingerprint),
+            date_imported: NotSet,
+        }
+    }
+
+    pub(crate) fn to_dto(
+        t: &bank_transaction::Model,
+        mandate_api_id: Option<uuid::Uuid>,
+    ) -> Transaction {
+        Transaction {
+            api_id: t.api_id,
+            mandate_api_id,
+            booking_date: t.booking_date,
+            value_date: t.value_date,
+            amount: t.amount,
+            currency: t.currency.clone(),
+            is_reversal: t.is_reversal,
+            mandate_reference: t.mandate_reference.clone(),
+            creditor_identifier: t.creditor_identifier.clone(),
+            creditor_name: t.creditor_name.clone(),
+            debtor_iban: t.debtor_iban.clone(),
+            end_to_end_id: t.end_to_end_id.clone(),
+            remittance_information: t.remittance_information.clone(),
+        }
+    }
+}
+
+pub mod forecast {
+    use super::*;
+
+    use crate::errors::ServiceError;
+    use api_models::{
+        models::{
+            schedule::{self, DEFAULT_FORECAST_MONTHS},
+            Forecast, ForecastQuery, Mandate as MandateDto,
+        },
+        validator::Validate,
+    };
+    use entity::{
+        mandate::{Column, Entity as MandateEntity, MandateStatus},
+        sea_orm::ModelTrait,
+    };
+
+    /// Expected debits of the active mandates in the next months, with monthly totals per account.
+    pub async fn get_forecast(
+        auth: BearerAuth,
+        state: web::Data<AppState>,
+        query: web::Query<ForecastQuery>,
+    ) -> Result<HttpResponse, ServiceError> {
+        query.validate()?;
+        let (user_profile, _) = profile::require_profile(&auth, &state).await?;
+        let today = chrono::Local::now().naive_local().date();
+        let months = query.months.unwrap_or(DEFAULT_FORECAST_MONTHS);
+        Ok(HttpResponse::Ok().json(load_forecast(&state, &user_profile, today, months).await?))
+    }
+
+    pub(crate) async fn load_forecast(
+        state: &AppState,
+        user_profile: &Model,
+        from: Date,
+        months: u32,
+    ) -> Result<Forecast, ServiceError> {
+        let mandates = user_profile
+            .find_related(MandateEntity)
+            .filter(Column::Status.eq(MandateStatus::ACTIVE))
+            .filter(Column::AnchorDate.is_not_null())
+            .all(&state.connection)
+            .await
+            .map_err(db_error)?;
+        let accounts =
+            account::load_accounts(state, mandates.iter().map(|m| m.bank_account_id)).await?;
+        let mandates: Vec<MandateDto> = mandates
+            .iter()
+            .map(|m| mandate::to_dto(m, accounts.get(&m.bank_account_id)))
+            .collect();
+        Ok(schedule::forecast(&mandates, from, months))
+    }
+}
+
+pub mod deadline {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    use crate::errors::ServiceError;
+    use api_models::models::{deadline, DebitDeadline, Mandate as MandateDto, Transaction};
+    use entity::{
+        bank_transaction::{self, Entity as BankTransactionEntity},
+        mandate::Entity as MandateEntity,
+        mandate_history::{self, Entity as MandateHistoryEntity},
+        sea_orm::{ModelTrait, QueryOrder},
+    };
+    use serde_json::json;
+
+    /// Refund and unauthorised debit deadlines of the recorded debits of the mandates, with alerts
+    /// for debits collected after a mandate was canceled or deleted.
+    pub async fn get_deadlines(
+        auth: BearerAuth,
+        state: web::Data<AppState>,
+    ) -> Result<HttpResponse, ServiceError> {
+        let (user_profile, _) = profile::require_profile(&auth, &state).await?;
+        let today = chrono::Local::now().naive_local().date();
+        Ok(HttpResponse::Ok().json(load_deadlines(&state, &user_profile, today).await?))
+    }
+
+    pub(crate) async fn load_deadlines(
+        state: &AppState,
+        user_profile: &Model,
+        today: Date,
+    ) -> Result<Vec<DebitDeadline>, ServiceError> {
+        let mandates = user_profile
+            .find_related(MandateEntity)
+            .all(&state.connection)
+            .await
+            .map_err(db_error)?;
+        let accounts =
+            account::load_accounts(state, mandates.iter().map(|m| m.bank_account_id)).await?;
+        let mandate_ids: HashMap<i32, uuid::Uuid> =
+            mandates.iter().map(|m| (m.id, m.api_id)).collect();
+        let transactions: Vec<Transaction> = BankTransactionEntity::find()
+            .filter(bank_transaction::Column::UserProfileId.eq(user_profile.id))
+            .filter(bank_transaction::Column::MandateId.is_not_null())
+            .all(&state.connection)
+            .await
+            .map_err(db_error)?
+            .iter()
+            .map(|t| {
+                statement::to_dto(t, t.mandate_id.and_then(|id| mandate_ids.get(&id).copied()))
+            })
+            .collect();
+        let ended_on = ended_on(state, &mandate_ids).await?;
+        let mandates: Vec<MandateDto> = mandates
+            .iter()
+            .map(|m| mandate::to_dto(m, accounts.get(&m.bank_account_id)))
+            .collect();
+        Ok(deadline::debit_deadlines(
+            &transactions,
+            &mandates,
+            &ended_on,
+            today,
+        ))
+    }
+
+    /// The day each mandate was first canceled or deleted, from its history.
+    async fn ended_on(
+        state: &AppState,
+        mandate_ids: &HashMap<i32, uuid::Uuid>,
+    ) -> Result<HashMap<uuid::Uuid, Date>, ServiceError> {
+        let changes = MandateHistoryEntity::find()
+            .filter(mandate_history::Column::MandateId.is_in(mandate_ids.keys().copied()))
+            .filter(mandate_history::Column::Field.eq("status"))
+            .filter(mandate_history::Column::NewValue.is_in([json!("CANCELED"), json!("DELETED")]))
+            .order_by_asc(mandate_history::Column::DateChanged)
+            .all(&state.connection)
+            .await
+            .map_err(db_error)?;
+        let mut ended_on = HashMap::new();
+        for change in changes {
+            if let Some(api_id) = mandate_ids.get(&change.mandate_id) {
+                ended_on
+                    .entry(*api_id)
+                    .or_insert_with(|| change.date_changed.date());
+            }
+        }
+        Ok(ended_on)
+    }
+}
+
+pub mod webhook {
+    use super::*;
+
+    use crate::errors::ServiceError;
+    use crate::webhooks;
+    use api_models::{
+        models::{webhook::MAX_SUBSCRIPTIONS, WebhookDelivery, WebhookSubscription},
+        validator::{Validate, ValidationError, ValidationErrors},
+    };
+    use entity::{
+        sea_orm::{ModelTrait, PaginatorTrait, QueryOrder, QuerySelect},
+        webhook_delivery::{self, Entity as DeliveryEntity},
+        webhook_subscription::{self, Entity as SubscriptionEntity},
+    };
+    use serde_json::json;
+
+    /// Deliveries listed per subscription, the latest first.
+    const DELIVERY_LIMIT: u64 = 100;
+
+    pub async fn get_webhooks(
+        auth: BearerAuth,
+        state: web::Data<AppState>,
+    ) -> Result<HttpResponse, ServiceError> {
+        let (user_profile, _) = profile::require_profile(&auth, &state).await?;
+        let subscriptions: Vec<WebhookSubscription> = user_profile
+            .find_related(SubscriptionEntity)
+            .order_by_asc(webhook_subscription::Column::DateCreated)
+            .all(&state.connection)
+            .await
+            .map_err(db_error)?
+            .iter()
+            .map(to_dto)
+            .collect();
+        Ok(HttpResponse::Ok().json(subscriptions))
+    }
+
+    /// Creates (201) or replaces (200) the subscription at `api_id`, the secret is kept when
+    /// the body has none.
+    pub async fn put_webhook(
+        auth: BearerAuth,
+        state: web::Data<AppState>,
+        api_id: web::Path<uuid::Uuid>,
+        dto: web::Json<WebhookSubscription>,
+    ) -> Result<HttpResponse, ServiceError> {
+        dto.validate()?;
+        let api_id = api_id.into_inner();
+        if dto.api_id != api_id {
+            return Err(ServiceError::BadRequest(format!(
+                "Body is webhook {} but the path is webhook {}",
+                dto.api_id, api_id
+            )));
+        }
+        let (user_profile, _) = profile::require_profile(&auth, &state).await?;
+        let mut events = dto.events.clone();
+        events.sort();
+        events.dedup();
+        let saved = match find_owned_subscription(&state, &user_profile, api_id).await? {
+            Some(existing) => {
+                let mut active_model: webhook_subscription::ActiveModel = existing.into();
+                active_model.url = Set(dto.url.clone());
+                if let Some(secret) = &dto.secret {
+                    active_model.secret = Set(secret.clone());
+                }
+                active_model.events = Set(json!(events));
+                active_model.active = Set(dto.active);
+                let saved = active_model.update(&state.connection).await.map_err(db_error)?;
+                return Ok(HttpResponse::Ok().json(to_dto(&saved)));
+            }
+            None => {
+                let secret = dto.secret.clone().ok_or_else(|| {
+                    let mut errors = ValidationErrors::new();
+                    let mut error = ValidationError::new("required");
+                    error.message = Some("is needed for a new webhook".into());
+                    errors.add("secret", error);
+                    ServiceError::Validation(errors)
+                })?;
+                let count = user_profile
+                    .find_related(SubscriptionEntity)
+                    .count(&state.connection)
+                    .await
+                    .map_err(db_error)?;
+                if count >= MAX_SUBSCRIPTIONS {
+                    return Err(ServiceError::Conflict(format!(
+                        "At most {} webhooks are allowed",
+                        MAX_SUBSCRIPTIONS
+                    )));
+                }
+                webhook_subscription::ActiveModel {
+                    id: NotSet,
+                    api_id: Set(api_id),
+                    user_profile_id: Set(user_profile.id),
+                    url: Set(dto.url.clone()),
+                    secret: Set(secret),
+                    events: Set(json!(events)),
+                    active: Set(dto.active),
+                    date_created: NotSet,
+                }
+                .insert(&state.connection)
+                .await
+                .map_err(db_error)?
+            }
+        };
+        Ok(HttpResponse::Created()
+            .insert_header(("Location", format!("/api/webhooks/{}", api_id)))
+            .json(to_dto(&saved)))
+    }
+
+    /// Deletes the subscription with its deliveries, pending ones are no longer sent.
+    pub async fn delete_webhook(
+        auth: BearerAuth,
+        state: web::Data<AppState>,
+        api_id: web::Path<uuid::Uuid>,
+    ) -> Result<HttpResponse, ServiceError> {
+        let (user_profile, _) = profile::require_profile(&auth, &state).await?;
+        let api_id = api_id.into_inner();
+        let subscription = find_owned_subscription(&state, &user_profile, api_id)
+            .await?
+            .ok_or_else(|| not_found(api_id))?;
+        subscription
+            .delete(&state.connection)
+            .await
+            .map_err(db_error)?;
+        Ok(HttpResponse::NoContent().finish())
+    }
+
+    /// The delivery log of the subscription.
+    pub async fn get_deliveries(
+        auth: BearerAuth,
+        state: web::Data<AppState>,
+        api_id: web::Path<uuid::Uuid>,
+    ) -> Result<HttpResponse, ServiceError> {
+        let (user_profile, _) = profile::require_profile(&auth, &state).await?;
+        let api_id = api_id.into_inner();
+        let subscription = find_owned_subscription(&state, &user_profile, api_id)
+            .await?
+            .ok_or_else(|| not_found(api_id))?;
+        let deliveries: Vec<WebhookDelivery> = subscription
+            .find_related(DeliveryEntity)
+            .order_by_desc(webhook_delivery::Column::DateCreated)
+            .order_by_desc(webhook_delivery::Column::Id)
+            .limit(DELIVERY_LIMIT)
+            .all(&state.connection)
+            .await
+            .map_err(db_error)?
+            .iter()
+            .filter_map(webhooks::to_dto)
+            .collect();
+        Ok(HttpResponse::Ok().json(deliveries))
+    }
+
+    fn to_dto(s: &webhook_subscription::Model) -> WebhookSubscription {
+        WebhookSubscription {
+            api_id: s.api_id,
+            url: s.url.clone(),
+            secret: None,
+            events: serde_json::from_value(s.events.clone()).unwrap_or_default(),
+            active: s.active,
+            date_created: Some(s.date_created),
+        }
+    }
+
+    fn not_found(api_id: uuid::Uuid) -> ServiceError {
+        ServiceError::NotFound(format!("Webhook {} not found", api_id))
+    }
+
+    /// Like mandates, a subscription of another profile is reported as not found.
+    async fn find_owned_subscription(
+        state: &AppState,
+        user_profile: &Model,
+        api_id: uuid::Uuid,
+    ) -> Result<Option<webhook_subscription::Model>, ServiceError> {
+        match SubscriptionEntity::find()
+            .filter(webhook_subscription::Column::ApiId.eq(api_id))
+            .one(&state.connection)
+            .await
+            .map_err(db_error)?
+        {
+            Some(s) if s.user_profile_id != user_profile.id => Err(not_found(api_id)),
+            found => Ok(found),
+        }
+    }
+}
+
+pub mod admin {
+    use super::*;
+
+    use crate::errors::ServiceError;
+    use crate::jobs;
+    use api_models::{
+        models::{Job, JobInfo, JobRun, JobRunQuery},
+        validator::Validate,
+    };
+    use entity::{
+        job_run::{self, Entity as JobRunEntity},
+        sea_orm::{QueryOrder, QuerySelect},
+    };
+
+    /// Runs listed when the query has no limit.
+    const DEFAULT_RUN_LIMIT: u64 = 50;
+
+    /// The jobs with their schedule, next and last run.
+    pub async fn get_jobs(
+        auth: BearerAuth,
+        state: web::Data<AppState>,
+    ) -> Result<HttpResponse, ServiceError> {
+        require_admin(&auth, &state).await?;
+        let now = chrono::Utc::now().naive_utc();
+        let mut result = Vec::new();
+        for schedule in state.jobs.iter() {
+            let name: &'static str = schedule.job.into();
+            let last_run = JobRunEntity::find()
+                .filter(job_run::Column::Job.eq(name))
+                .order_by_desc(job_run::Column::Started)
+                .one(&state.connection)
+                .await
+                .map_err(db_error)?;
+            result.push(JobInfo {
+                job: schedule.job,
+                description: jobs::description(schedule.job).to_string(),
+                schedule: schedule.expression.clone(),
+                next_run: jobs::next_run(&schedule.schedule, now),
+                last_run: last_run.as_ref().and_then(jobs::to_dto),
+            });
+        }
+        Ok(HttpResponse::Ok().json(result))
+    }
+
+    /// The latest runs, of one job when the query names it.
+    pub async fn get_job_runs(
+        auth: BearerAuth,
+        state: web::Data<AppState>,
+        query: web::Query<JobRunQuery>,
+    ) -> Result<HttpResponse, ServiceError> {
+        query.validate()?;
+        require_admin(&auth, &state).await?;
+        let mut select = JobRunEntity::find();
+        if let Some(job) = query.job {
+            let name: &'static str = job.into();
+            select = select.filter(job_run::Column::Job.eq(name));
+        }
+        let runs: Vec<JobRun> = select
+            .order_by_desc(job_run::Column::Started)
+            .limit(query.limit.unwrap_or(DEFAULT_RUN_LIMIT))
+            .all(&state.connection)
+            .await
+            .map_err(db_error)?
+            .iter()
+            .filter_map(jobs::to_dto)
+            .collect();
+        Ok(HttpResponse::Ok().json(runs))
+    }
+
+    /// Runs the job now and answers the finished run, 409 while the job is running.
+    pub async fn run_job(
+        auth: BearerAuth,
+        state: web::Data<AppState>,
+        job: web::Path<Job>,
+    ) -> Result<HttpResponse, ServiceError> {
+        let subject = require_admin(&auth, &state).await?;
+        let job = job.into_inner();
+        let now = chrono::Utc::now().naive_utc();
+        match jobs::run(&state, job, now, Some(&subject)).await? {
+            Some(run) => Ok(HttpResponse::Ok().json(jobs::to_dto(&run))),
+            None => Err(ServiceError::Conflict(format!(
+                "Job {} is running",
+                <&'static str>::from(job)
+            ))),
+        }
+    }
+
+    /// The subject of the caller, if it is one of the configured admins.
+    async fn require_admin(
+        auth: &BearerAuth,
+        state: &web::Data<AppState>,
+    ) -> Result<String, ServiceError> {
+        let td = auth::get_token_data(auth.token(), &state.auth).await?;
+        match td.claims.get("sub").and_then(|sub| sub.as_str()) {
+            Some(sub) if state.auth.admins.iter().any(|admin| admin == sub) => Ok(sub.to_string()),
+            _ => Err(ServiceError::Forbidden("Admins only".to_string())),
+        }
     }
 }
@@ -0,0 +1,189 @@
+//! Audit trail of mandates: every saved change is stored per field in `mandate_history`.
+
+use api_models::models::MandateChange;
+use chrono::Utc;
+use entity::{
+    bank_account, mandate, mandate_history,
+    sea_orm::{ActiveModelTrait, ActiveValue::NotSet, ConnectionTrait, DbErr, EntityTrait, Set},
+};
+use serde_json::{json, Value};
+
+/// Fields of a mandate that are tracked, with their current values.
+fn tracked_fields(m: &mandate::Model, bank_account: Value) -> Vec<(&'static str, Value)> {
+    vec![
+        ("status", json!(m.status)),
+        ("unique_reference", json!(m.unique_reference)),
+        ("display_name", json!(m.display_name)),
+        ("tags", m.tags.clone()),
+        ("creditor", m.creditor.clone()),
+        ("bank_account", bank_account),
+        ("scheme", json!(m.scheme)),
+        ("payment_type", json!(m.payment_type)),
+        ("signature_date", json!(m.signature_date)),
+        ("signature_place", json!(m.signature_place)),
+        ("account_holder", json!(m.account_holder)),
+        ("schedule", json!(m.schedule())),
+        ("last_collection_date", json!(m.last_collection_date)),
+    ]
+}
+
+/// The referenced account as it was stored inside the mandate before accounts had their own table.
+async fn bank_account_value<C: ConnectionTrait>(db: &C, id: i32) -> Result<Value, DbErr> {
+    Ok(bank_account::Entity::find_by_id(id)
+        .one(db)
+        .await?
+        .map_or(Value::Null, |a| {
+            let mut value = json!({"institution": a.institution, "iban": a.iban});
+            if let Some(bic) = a.bic {
+                value["bic"] = json!(bic);
+            }
+            value
+        }))
+}
+
+/// Records the fields that differ between `old` and `new`, all non empty fields of a new mandate.
+/// Returns the number of recorded changes.
+pub async fn record_changes<C: ConnectionTrait>(
+    db: &C,
+    auth_id: &str,
+    old: Option<&mandate::Model>,
+    new: &mandate::Model,
+) -> Result<usize, DbErr> {
+    let date_changed = Utc::now().naive_utc();
+    let new_account = bank_account_value(db, new.bank_account_id).await?;
+    let old_fields = match old {
+        Some(o) if o.bank_account_id == new.bank_account_id => {
+            Some(tracked_fields(o, new_account.clone()))
+        }
+        Some(o) => Some(tracked_fields(o, bank_account_value(db, o.bank_account_id).await?)),
+        None => None,
+    };
+    let mut recorded = 0;
+    for (index, (field, new_value)) in tracked_fields(new, new_account).into_iter().enumerate() {
+        let old_value = old_fields
+            .as_ref()
+            .map(|fields| fields[index].1.clone())
+            .filter(|v| !v.is_null());
+        let new_value = Some(new_value).filter(|v| !v.is_null());
+        if old_value == new_value {
+            continue;
+        }
+        mandate_history::ActiveModel {
+            id: NotSet,
+            mandate_id: Set(new.id),
+            auth_id: Set(auth_id.to_string()),
+            date_changed: Set(date_changed),
+            field: Set(field.to_string()),
+            old_value: Set(old_value),
+            new_value: Set(new_value),
+        }
+        .insert(db)
+        .await?;
+        recorded += 1;
+    }
+    Ok(recorded)
+}
+
+pub fn to_dto(change: &mandate_history::Model) -> MandateChange {
+    MandateChange {
+        date_changed: change.date_changed,
+        changed_by: change.auth_id.clone(),
+        field: change.field.clone(),
+        old_value: change.old_value.clone(),
+        new_value: change.new_value.clone(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use entity::{
+        mandate::{ActiveModel, MandateStatus},
+        mandate_history,
+        sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set},
+    };
+    use serde_json::json;
+
+    use super::record_changes;
+    use crate::handlers::account::resolve;
+    use crate::testing::{begin, insert_mandate, insert_profile};
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn test_record_changes() {
+        let txn = begin().await;
+        let profile = insert_profile(&txn).await;
+        let account = resolve(
+            &txn,
+            &profile,
+            &api_models::models::BankAccount {
+                institution: "Commerzbank".to_string(),
+                iban: "DE89370400440532013000".to_string(),
+                bic: Some("COBADEFFXXX".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        let created = insert_mandate(&txn, &account).await;
+        let creditor: api_models::models::Creditor =
+            serde_json::from_value(created.creditor.clone()).unwrap();
+        assert!(creditor.creditor_identifier().is_some());
+        // status, unique_reference, display_name, tags, creditor, bank_account, scheme, payment_type
+        assert_eq!(8, record_changes(&txn, "auth|1", None, &created).await.unwrap());
+        assert_eq!(0, record_changes(&txn, "auth|1", Some(&created), &created).await.unwrap());
+
+        let other = resolve(
+            &txn,
+            &profile,
+            &api_models::models::BankAccount {
+                institution: "Postbank".to_string(),
+                iban: "DE02120300000000202051".to_string(),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        let mut changed: ActiveModel = created.clone().into();
+        changed.status = Set(MandateStatus::SUSPENDED);
+        changed.bank_account_id = Set(other.id);
+        let changed = changed.update(&txn).await.unwrap();
+        assert_eq!(2, record_changes(&txn, "auth|2", Some(&created), &changed).await.unwrap());
+
+        let recorded = mandate_history::Entity::find()
+            .filter(mandate_history::Column::MandateId.eq(created.id))
+            .filter(mandate_history::Column::AuthId.eq("auth|2"))
+            .order_by_asc(mandate_history::Column::Field)
+            .all(&txn)
+            .await
+            .unwrap();
+        assert_eq!("bank_account", recorded[0].field);
+        assert_eq!(
+            Some(json!({"institution": "Commerzbank", "iban": "DE89370400440532013000",
+                "bic": "COBADEFFXXX"})),
+            recorded[0].old_value
+        );
+        assert_eq!(
+            Some(json!({"institution": "Postbank", "iban": "DE02120300000000202051"})),
+            recorded[0].new_value
+        );
+        assert_eq!("status", recorded[1].field);
+        assert_eq!(Some(json!("ACTIVE")), recorded[1].old_value);
+        assert_eq!(Some(json!("SUSPENDED")), recorded[1].new_value);
+
+        // a debit of an imported statement
+        let mut collected: ActiveModel = changed.clone().into();
+        collected.last_collection_date = Set(chrono::NaiveDate::from_ymd_opt(2023, 3, 1));
+        let collected = collected.update(&txn).await.unwrap();
+        assert_eq!(1, record_changes(&txn, "auth|3", Some(&changed), &collected).await.unwrap());
+        let recorded = mandate_history::Entity::find()
+            .filter(mandate_history::Column::MandateId.eq(created.id))
+            .filter(mandate_history::Column::AuthId.eq("auth|3"))
+            .one(&txn)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!("last_collection_date", recorded.field);
+        assert_eq!(None, recorded.old_value);
+        assert_eq!(Some(json!("2023-03-01")), recorded.new_value);
+    }
+}
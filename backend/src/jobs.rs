@@ -0,0 +1,598 @@
+//! Periodic jobs, run by every replica of the backend. Each run is stored in `job_run`,
+//! a scheduled slot of a job is unique there so it is run once across restarts and replicas.
+//! A Postgres advisory lock keeps two runs of the same job from overlapping.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::error::Error;
+use std::str::FromStr;
+
+use api_models::calendar::add_months;
+use api_models::models::{
+    deadline::UNAUTHORISED_MONTHS, job::JOBS, mandate::usable_until, Job, JobRun,
+};
+use chrono::{Duration, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use cron::Schedule;
+use entity::{
+    bank_transaction::{self, Entity as BankTransactionEntity},
+    job_run::{self, Entity as JobRunEntity, RunStatus},
+    mandate::{self, Entity as MandateEntity, MandateStatus},
+    mandate_history::{self, Entity as MandateHistoryEntity},
+    sea_orm::{
+        ActiveModelTrait, ActiveValue::NotSet, ColumnTrait, ConnectionTrait, DatabaseBackend,
+        DatabaseTransaction, EntityTrait, QueryFilter, QueryOrder, Set, Statement, TransactionTrait,
+    },
+    user_profile::Entity as UserProfileEntity,
+};
+use log::{debug, error, info};
+use serde_json::json;
+
+use crate::errors::ServiceError;
+use crate::handlers::{account, db_error, mandate::to_dto as mandate_dto};
+use crate::history;
+use crate::notifications;
+use crate::webhooks;
+use crate::AppState;
+
+/// Recorded in the history as the author of changes made by jobs.
+const SYSTEM_AUTH_ID: &str = "system";
+
+/// How often the scheduler looks for due jobs.
+const TICK: std::time::Duration = std::time::Duration::from_secs(60);
+
+#[derive(Clone, Debug)]
+pub struct JobSchedule {
+    pub job: Job,
+    /// The cron expression, with seconds.
+    pub expression: String,
+    pub schedule: Schedule,
+}
+
+#[derive(Clone, Debug)]
+pub struct JobSchedules(Vec<JobSchedule>);
+
+impl JobSchedules {
+    /// Reads the schedule of each job from `JOB_SCHEDULE_<JOB>`, e.g. `JOB_SCHEDULE_PURGE_DELETED`,
+    /// as a cron expression with seconds in UTC.
+    pub fn from_env() -> Result<JobSchedules, Box<dyn Error>> {
+        let mut schedules = Vec::new();
+        for job in JOBS {
+            let name: &'static str = job.into();
+            let expression = std::env::var(format!("JOB_SCHEDULE_{}", name.to_uppercase()))
+                .unwrap_or_else(|_| default_schedule(job).to_string());
+            let schedule = Schedule::from_str(&expression)
+                .map_err(|e| format!("Invalid schedule {} of {}: {}", expression, name, e))?;
+            schedules.push(JobSchedule {
+                job,
+                expression,
+                schedule,
+            });
+        }
+        Ok(JobSchedules(schedules))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &JobSchedule> {
+        self.0.iter()
+    }
+}
+
+fn default_schedule(job: Job) -> &'static str {
+    match job {
+        Job::SendNotifications => "0 0 7 * * *",
+        Job::PurgeDeleted => "0 30 3 * * *",
+        Job::ExpireMandates => "0 0 3 * * *",
+    }
+}
+
+pub fn description(job: Job) -> &'static str {
+    match job {
+        Job::SendNotifications => "Sends the email notifications users opted in to",
+        Job::PurgeDeleted => "Removes deleted mandates no longer disputable and old webhook deliveries",
+        Job::ExpireMandates => "Expires active mandates without a collection in the last 36 months",
+    }
+}
+
+/// The next slot of the schedule after `after`.
+pub fn next_run(schedule: &Schedule, after: NaiveDateTime) -> Option<NaiveDateTime> {
+    schedule
+        .after(&Utc.from_utc_datetime(&after))
+        .next()
+        .map(|next| next.naive_utc())
+}
+
+pub(crate) fn to_dto(run: &job_run::Model) -> Option<JobRun> {
+    Some(JobRun {
+        id: run.id,
+        // runs of jobs that no longer exist are left out
+        job: Job::from_str(&run.job).ok()?,
+        scheduled_for: run.scheduled_for,
+        triggered_by: run.triggered_by.clone(),
+        status: run.status.clone().into(),
+        started: run.started,
+        finished: run.finished,
+        message: run.message.clone(),
+    })
+}
+
+/// Runs the jobs when they are due, starting with the slots after the start of the backend
+/// when a job never ran. Of several missed slots only the last one is run.
+pub fn spawn_scheduler(state: AppState) {
+    actix_web::rt::spawn(async move {
+        let started = Utc::now().naive_utc();
+        match fail_interrupted(&state.connection, started).await {
+            Ok(0) => {}
+            Ok(failed) => info!("{} interrupted job runs marked as failed", failed),
+            Err(e) => error!("Checking for interrupted job runs failed: {:?}", e),
+        }
+        let mut interval = actix_web::rt::time::interval(TICK);
+        loop {
+            interval.tick().await;
+            for schedule in state.jobs.iter() {
+                let slot = match due_slot(&state, schedule, started).await {
+                    Ok(Some(slot)) => slot,
+                    Ok(None) => continue,
+                    Err(e) => {
+                        error!("Checking job {:?} failed: {:?}", schedule.job, e);
+                        continue;
+                    }
+                };
+                if let Err(e) = run(&state, schedule.job, slot, None).await {
+                    error!("Job {:?} failed: {:?}", schedule.job, e);
+                }
+            }
+        }
+    });
+}
+
+async fn due_slot(
+    state: &AppState,
+    schedule: &JobSchedule,
+    started: NaiveDateTime,
+) -> Result<Option<NaiveDateTime>, ServiceError> {
+    let name: &'static str = schedule.job.into();
+    let last_slot = JobRunEntity::find()
+        .filter(job_run::Column::Job.eq(name))
+        .filter(job_run::Column::TriggeredBy.is_null())
+        .order_by_desc(job_run::Column::ScheduledFor)
+        .one(&state.connection)
+        .await
+        .map_err(db_error)?
+        .map_or(started, |run| run.scheduled_for);
+    Ok(last_due_slot(
+        &schedule.schedule,
+        last_slot,
+        Utc::now().naive_utc(),
+    ))
+}
+
+/// The latest slot of the schedule after `after` that is due at `now`.
+fn last_due_slot(
+    schedule: &Schedule,
+    after: NaiveDateTime,
+    now: NaiveDateTime,
+) -> Option<NaiveDateTime> {
+    schedule
+        .after(&Utc.from_utc_datetime(&after))
+        .map(|slot| slot.naive_utc())
+        .take_while(|slot| *slot <= now)
+        .last()
+}
+
+/// Takes the lock of the job until the end of the transaction, `false` when a run of the job
+/// holds it.
+async fn try_lock(txn: &DatabaseTransaction, name: &str) -> Result<bool, ServiceError> {
+    Ok(txn
+        .query_one(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            "select pg_try_advisory_xact_lock(hashtext($1)) as locked",
+            vec![format!("job_run:{}", name).into()],
+        ))
+        .await
+        .map_err(db_error)?
+        .map(|row| row.try_get::<bool>("", "locked"))
+        .transpose()
+        .map_err(db_error)?
+        .unwrap_or(false))
+}
+
+/// Marks the runs left `RUNNING` by a backend that stopped during the job as failed. A run holds
+/// the lock of its job until it is stored as finished, so no run of a job whose lock is free is
+/// still going. Returns the number of failed runs.
+async fn fail_interrupted<C: TransactionTrait>(
+    db: &C,
+    now: NaiveDateTime,
+) -> Result<usize, ServiceError> {
+    let txn = db.begin().await.map_err(db_error)?;
+    let running = JobRunEntity::find()
+        .filter(job_run::Column::Status.eq(RunStatus::Running))
+        .all(&txn)
+        .await
+        .map_err(db_error)?;
+    let jobs: BTreeSet<&str> = running.iter().map(|run| run.job.as_str()).collect();
+    let mut free = HashSet::new();
+    for job in jobs {
+        if try_lock(&txn, job).await? {
+            free.insert(job.to_string());
+        }
+    }
+    let mut failed = 0;
+    for run in running.into_iter().filter(|run| free.contains(&run.job)) {
+        let mut interrupted: job_run::ActiveModel = run.into();
+        interrupted.status = Set(RunStatus::Failed);
+        interrupted.finished = Set(Some(now));
+        interrupted.message = Set(Some("Interrupted by a stop of the backend".to_string()));
+        interrupted.update(&txn).await.map_err(db_error)?;
+        failed += 1;
+    }
+    txn.commit().await.map_err(db_error)?;
+    Ok(failed)
+}
+
+/// Runs `job` for the slot, or as requested by an admin when `triggered_by` is set.
+/// Returns `None` when the job is running elsewhere or the slot already ran.
+pub async fn run(
+    state: &AppState,
+    job: Job,
+    scheduled_for: NaiveDateTime,
+    triggered_by: Option<&str>,
+) -> Result<Option<job_run::Model>, ServiceError> {
+    let name: &'static str = job.into();
+    // the transaction holds the lock until the run is stored as finished
+    let lock = state.connection.begin().await.map_err(db_error)?;
+    if !try_lock(&lock, name).await? {
+        debug!("Job {} is running elsewhere", name);
+        return Ok(None);
+    }
+    let slot_taken = JobRunEntity::find()
+        .filter(job_run::Column::Job.eq(name))
+        .filter(job_run::Column::ScheduledFor.eq(scheduled_for))
+        .one(&state.connection)
+        .await
+        .map_err(db_error)?
+        .is_some();
+    if slot_taken {
+        return Ok(None);
+    }
+    let previous = JobRunEntity::find()
+        .filter(job_run::Column::Job.eq(name))
+        .filter(job_run::Column::Status.eq(RunStatus::Succeeded))
+        .order_by_desc(job_run::Column::Started)
+        .one(&state.connection)
+        .await
+        .map_err(db_error)?;
+    // stored outside of the lock transaction to show the run while it goes, a run left behind by
+    // a stop of the backend is failed by `fail_interrupted` on the next start
+    let run = job_run::ActiveModel {
+        id: NotSet,
+        job: Set(name.to_string()),
+        scheduled_for: Set(scheduled_for),
+        triggered_by: Set(triggered_by.map(str::to_string)),
+        status: Set(RunStatus::Running),
+        started: Set(Utc::now().naive_utc()),
+        finished: NotSet,
+        message: NotSet,
+    }
+    .insert(&state.connection)
+    .await
+    .map_err(db_error)?;
+    info!("Job {} started for {}", name, scheduled_for);
+
+    let result = execute(state, job, previous.map(|p| p.started), run.started).await;
+    let (status, message) = match result {
+        Ok(message) => (RunStatus::Succeeded, message),
+        Err(e) => (RunStatus::Failed, format!("{:?}", e)),
+    };
+    info!("Job {} {:?}: {}", name, status, message);
+    let mut finished: job_run::ActiveModel = run.into();
+    finished.status = Set(status);
+    finished.finished = Set(Some(Utc::now().naive_utc()));
+    finished.message = Set(Some(message));
+    let finished = finished.update(&state.connection).await.map_err(db_error)?;
+    lock.commit().await.map_err(db_error)?;
+    Ok(Some(finished))
+}
+
+/// Runs the job, `since` is the start of its previous successful run.
+async fn execute(
+    state: &AppState,
+    job: Job,
+    since: Option<NaiveDateTime>,
+    now: NaiveDateTime,
+) -> Result<String, ServiceError> {
+    match job {
+        Job::SendNotifications => {
+            let since = since.unwrap_or_else(|| now - Duration::days(1));
+            let sent = notifications::send_notifications(state, since, now).await?;
+            Ok(format!("{} mails sent", sent))
+        }
+        Job::PurgeDeleted => {
+            let purged = purge_deleted(state, now).await?;
+            let deliveries = webhooks::purge_log(state, now).await?;
+            Ok(format!(
+                "{} mandates purged, {} webhook deliveries removed",
+                purged, deliveries
+            ))
+        }
+        Job::ExpireMandates => {
+            let (expired, sent) = expire_mandates(state, now).await?;
+            Ok(format!("{} mandates expired, {} mails sent", expired, sent))
+        }
+    }
+}
+
+/// Removes deleted mandates with their history and debits, once neither the deletion nor their
+/// last debit is within the months in which a debit can be disputed.
+async fn purge_deleted(state: &AppState, now: NaiveDateTime) -> Result<usize, ServiceError> {
+    let deleted = MandateEntity::find()
+        .filter(mandate::Column::Status.eq(MandateStatus::DELETED))
+        .all(&state.connection)
+        .await
+        .map_err(db_error)?;
+    let mut purged = 0;
+    for m in deleted {
+        let deleted_on = MandateHistoryEntity::find()
+            .filter(mandate_history::Column::MandateId.eq(m.id))
+            .filter(mandate_history::Column::Field.eq("status"))
+            .filter(mandate_history::Column::NewValue.eq(json!("DELETED")))
+            .order_by_desc(mandate_history::Column::DateChanged)
+            .one(&state.connection)
+            .await
+            .map_err(db_error)?
+            .map_or(m.date_created.date(), |change| change.date_changed.date());
+        let last_debit = BankTransactionEntity::find()
+            .filter(bank_transaction::Column::MandateId.eq(m.id))
+            .order_by_desc(bank_transaction::Column::BookingDate)
+            .one(&state.connection)
+            .await
+            .map_err(db_error)?
+            .map(|t| t.booking_date);
+        let last_activity = last_debit.map_or(deleted_on, |debit| debit.max(deleted_on));
+        if add_months(last_activity, UNAUTHORISED_MONTHS) >= now.date() {
+            continue;
+        }
+        let txn = state.connection.begin().await.map_err(db_error)?;
+        BankTransactionEntity::delete_many()
+            .filter(bank_transaction::Column::MandateId.eq(m.id))
+            .exec(&txn)
+            .await
+            .map_err(db_error)?;
+        MandateHistoryEntity::delete_many()
+            .filter(mandate_history::Column::MandateId.eq(m.id))
+            .exec(&txn)
+            .await
+            .map_err(db_error)?;
+        MandateEntity::delete_by_id(m.id)
+            .exec(&txn)
+            .await
+            .map_err(db_error)?;
+        txn.commit().await.map_err(db_error)?;
+        purged += 1;
+    }
+    Ok(purged)
+}
+
+/// The mandates of `active` no longer usable on `today`. Mandates neither collected under nor
+/// with a signature date are kept: their creation is only when they were entered, often long
+/// after the last collection that was never imported.
+fn stale_mandates(active: Vec<mandate::Model>, today: NaiveDate) -> Vec<mandate::Model> {
+    active
+        .into_iter()
+        .filter(|m| {
+            matches!(m.last_collection_date.or(m.signature_date),
+                Some(last_used) if usable_until(last_used) < today)
+        })
+        .collect()
+}
+
+/// Moves the active mandates that were not collected under for 36 months to `EXPIRED`, records the
+/// change in their history, queues their webhook events and mails the users who opted in. Returns the expired mandates and
+/// the number of sent mails.
+async fn expire_mandates(
+    state: &AppState,
+    now: NaiveDateTime,
+) -> Result<(usize, usize), ServiceError> {
+    let active = MandateEntity::find()
+        .filter(mandate::Column::Status.eq(MandateStatus::ACTIVE))
+        .all(&state.connection)
+        .await
+        .map_err(db_error)?;
+    let stale = stale_mandates(active, now.date());
+    let accounts = account::load_accounts(state, stale.iter().map(|m| m.bank_account_id)).await?;
+    let mut expired: HashMap<i32, Vec<mandate::Model>> = HashMap::new();
+    for m in stale {
+        let txn = state.connection.begin().await.map_err(db_error)?;
+        let mut active_model: mandate::ActiveModel = m.clone().into();
+        active_model.status = Set(MandateStatus::EXPIRED);
+        let saved = active_model.update(&txn).await.map_err(db_error)?;
+        history::record_changes(&txn, SYSTEM_AUTH_ID, Some(&m), &saved)
+            .await
+            .map_err(db_error)?;
+        let data = mandate_dto(&saved, accounts.get(&saved.bank_account_id));
+        webhooks::enqueue_mandate(&txn, Some(&m), &saved, &data)
+            .await
+            .map_err(db_error)?;
+        txn.commit().await.map_err(db_error)?;
+        expired.entry(saved.user_profile_id).or_default().push(saved);
+    }
+    let count = expired.values().map(Vec::len).sum();
+    let mut sent = 0;
+    for (user_profile_id, mandates) in expired {
+        let profile = match UserProfileEntity::find_by_id(user_profile_id)
+            .one(&state.connection)
+            .await
+            .map_err(db_error)?
+        {
+            Some(profile) if profile.notify_expired_mandates => profile,
+            _ => continue,
+        };
+        if let Some(mail) = notifications::expired_mandates(&profile, &mandates)? {
+            sent += notifications::send_all(state.mailer.as_ref(), &profile, &[mail]).await;
+        }
+    }
+    Ok((count, sent))
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use api_models::models::job::JOBS;
+    use chrono::{NaiveDate, NaiveDateTime};
+    use cron::Schedule;
+    use entity::{
+        job_run::{self, RunStatus},
+        mandate::{self, MandatePaymentType, MandateScheme, MandateStatus},
+        sea_orm::{ActiveModelTrait, ActiveValue::NotSet, EntityTrait, Set},
+    };
+    use serde_json::json;
+
+    use super::{
+        default_schedule, fail_interrupted, last_due_slot, next_run, stale_mandates, try_lock,
+    };
+    use crate::testing::begin;
+
+    fn at(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    fn date(value: &str) -> NaiveDate {
+        value.parse().unwrap()
+    }
+
+    fn mandate(
+        id: i32,
+        last_collection_date: Option<&str>,
+        signature_date: Option<&str>,
+    ) -> mandate::Model {
+        mandate::Model {
+            id,
+            api_id: uuid::Uuid::new_v4(),
+            user_profile_id: 1,
+            tags: json!([]),
+            status: MandateStatus::ACTIVE,
+            unique_reference: None,
+            display_name: "Gas".to_string(),
+            date_created: at("2010-01-01 00:00:00"),
+            creditor: json!({"name": "Stadtwerke"}),
+            bank_account_id: 1,
+            scheme: MandateScheme::CORE,
+            payment_type: MandatePaymentType::Recurrent,
+            signature_date: signature_date.map(date),
+            signature_place: None,
+            account_holder: None,
+            expected_amount: None,
+            frequency: None,
+            interval_months: None,
+            anchor_date: None,
+            last_collection_date: last_collection_date.map(date),
+        }
+    }
+
+    #[test]
+    fn test_stale_mandates() {
+        let active = vec![
+            // collected 36 months and a day ago
+            mandate(1, Some("2020-03-01"), None),
+            // usable until today
+            mandate(2, Some("2020-03-02"), None),
+            // never collected, signed long ago
+            mandate(3, None, Some("2015-06-01")),
+            // collected recently, signed long ago
+            mandate(4, Some("2023-01-15"), Some("2015-06-01")),
+            // neither collected nor signed, entered long ago
+            mandate(5, None, None),
+        ];
+        let stale: Vec<i32> = stale_mandates(active, date("2023-03-02"))
+            .iter()
+            .map(|m| m.id)
+            .collect();
+        assert_eq!(vec![1, 3], stale);
+    }
+
+    #[test]
+    fn test_default_schedules() {
+        for job in JOBS {
+            assert!(Schedule::from_str(default_schedule(job)).is_ok(), "{:?}", job);
+        }
+    }
+
+    #[test]
+    fn test_next_run() {
+        let daily = Schedule::from_str("0 0 7 * * *").unwrap();
+        assert_eq!(
+            Some(at("2023-03-01 07:00:00")),
+            next_run(&daily, at("2023-03-01 06:59:59"))
+        );
+        assert_eq!(
+            Some(at("2023-03-02 07:00:00")),
+            next_run(&daily, at("2023-03-01 07:00:00"))
+        );
+        let monthly = Schedule::from_str("0 30 3 31 * *").unwrap();
+        assert_eq!(
+            Some(at("2023-03-31 03:30:00")),
+            next_run(&monthly, at("2023-02-01 00:00:00"))
+        );
+    }
+
+    #[test]
+    fn test_last_due_slot() {
+        let daily = Schedule::from_str("0 0 7 * * *").unwrap();
+        let last = at("2023-03-01 07:00:00");
+        // not due yet
+        assert_eq!(None, last_due_slot(&daily, last, at("2023-03-02 06:59:59")));
+        // due on the second
+        assert_eq!(
+            Some(at("2023-03-02 07:00:00")),
+            last_due_slot(&daily, last, at("2023-03-02 07:00:00"))
+        );
+        // of the missed slots only the last one
+        assert_eq!(
+            Some(at("2023-03-05 07:00:00")),
+            last_due_slot(&daily, last, at("2023-03-05 12:00:00"))
+        );
+        // the slot that ran isn't due again
+        assert_eq!(None, last_due_slot(&daily, last, last));
+        let hourly = Schedule::from_str("0 0 * * * *").unwrap();
+        assert_eq!(
+            Some(at("2023-03-01 00:00:00")),
+            last_due_slot(&hourly, at("2023-02-28 23:00:00"), at("2023-03-01 00:59:00"))
+        );
+    }
+
+    #[actix_web::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn test_fail_interrupted() {
+        let db = begin().await;
+        let insert = |job: &str| job_run::ActiveModel {
+            id: NotSet,
+            job: Set(job.to_string()),
+            scheduled_for: Set(at("2000-01-01 03:00:00")),
+            triggered_by: Set(None),
+            status: Set(RunStatus::Running),
+            started: Set(at("2000-01-01 03:00:01")),
+            finished: NotSet,
+            message: NotSet,
+        };
+        let interrupted = insert("purge_deleted").insert(&db).await.unwrap();
+        let going = insert("expire_mandates").insert(&db).await.unwrap();
+        // another backend runs the job
+        let other = begin().await;
+        assert!(try_lock(&other, "expire_mandates").await.unwrap());
+
+        let now = at("2000-01-01 04:00:00");
+        assert!(fail_interrupted(&db, now).await.unwrap() >= 1);
+        let interrupted = job_run::Entity::find_by_id(interrupted.id)
+            .one(&db)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(RunStatus::Failed, interrupted.status);
+        assert_eq!(Some(now), interrupted.finished);
+        assert!(interrupted.message.is_some());
+        let going = job_run::Entity::find_by_id(going.id)
+            .one(&db)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(RunStatus::Running, going.status);
+    }
+}
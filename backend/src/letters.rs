@@ -0,0 +1,358 @@
+//! Letters to the creditor of a mandate, rendered from per language Tera templates
+//! as plain text and turned into HTML or PDF.
+
+use std::fmt::Display;
+
+use api_models::models::{Address, BankAccount, Creditor, LetterFormat, LetterKind};
+use chrono::NaiveDate;
+use lazy_static::lazy_static;
+use printpdf::{BuiltinFont, Mm, PdfDocument};
+
+use crate::pdf::{file_name_part, to_win_ansi, wrap, PAGE_HEIGHT, PAGE_WIDTH};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use tera::{Context, Tera};
+
+lazy_static! {
+    static ref TEMPLATES: Tera = templates().expect("letter templates must be valid");
+}
+
+/// The templates are compiled into the binary, `<lang>/<kind>.txt` extends `<lang>/base.txt`.
+fn templates() -> tera::Result<Tera> {
+    let mut tera = Tera::default();
+    tera.add_raw_templates(vec![
+        (
+            "letter.html",
+            include_str!("../templates/letters/letter.html.tera"),
+        ),
+        (
+            "en/base.txt",
+            include_str!("../templates/letters/en/base.txt.tera"),
+        ),
+        (
+            "en/revocation.txt",
+            include_str!("../templates/letters/en/revocation.txt.tera"),
+        ),
+        (
+            "en/account_change.txt",
+            include_str!("../templates/letters/en/account_change.txt.tera"),
+        ),
+        (
+            "en/refund_request.txt",
+            include_str!("../templates/letters/en/refund_request.txt.tera"),
+        ),
+        (
+            "de/base.txt",
+            include_str!("../templates/letters/de/base.txt.tera"),
+        ),
+        (
+            "de/revocation.txt",
+            include_str!("../templates/letters/de/revocation.txt.tera"),
+        ),
+        (
+            "de/account_change.txt",
+            include_str!("../templates/letters/de/account_change.txt.tera"),
+        ),
+        (
+            "de/refund_request.txt",
+            include_str!("../templates/letters/de/refund_request.txt.tera"),
+        ),
+    ])?;
+    Ok(tera)
+}
+
+/// Everything a letter template can refer to.
+#[derive(Clone, Debug, Serialize)]
+pub struct LetterData {
+    pub debtor: Debtor,
+    pub creditor: Creditor,
+    pub mandate: MandateInfo,
+    /// The account the mandate is collected from now.
+    pub account: BankAccount,
+    /// The account the mandate was collected from before the last change, if any.
+    pub previous_account: Option<BankAccount>,
+    /// The disputed debit of a refund request.
+    pub debit: Option<Debit>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Debtor {
+    pub name: String,
+    pub address: Option<Address>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct MandateInfo {
+    pub reference: Option<String>,
+    pub name: String,
+    pub signed: NaiveDate,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Debit {
+    pub date: NaiveDate,
+    pub amount: Decimal,
+    pub currency: String,
+    /// The debited account as booked, which may differ from the account of the mandate.
+    pub iban: String,
+    pub end_to_end_id: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct LetterError(pub String);
+
+impl Display for LetterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Renders the letter of `kind` in `lang` (one of `LETTER_LANGUAGES`) dated `date`.
+pub fn render(
+    kind: LetterKind,
+    lang: &str,
+    format: LetterFormat,
+    data: &LetterData,
+    date: NaiveDate,
+) -> Result<Vec<u8>, LetterError> {
+    let text = render_text(kind, lang, data, date)?;
+    let title = title(kind, data);
+    match format {
+        LetterFormat::Txt => Ok(text.into_bytes()),
+        LetterFormat::Html => Ok(render_html(&text, lang, &title)?.into_bytes()),
+        LetterFormat::Pdf => render_pdf(&text, &title),
+    }
+}
+
+/// File name of the letter without extension, e.g. `revocation-ABC123`.
+pub fn title(kind: LetterKind, data: &LetterData) -> String {
+    let kind: &'static str = kind.into();
+    let reference = data
+        .mandate
+        .reference
+        .as_deref()
+        .unwrap_or(&data.mandate.name);
+    format!("{}-{}", kind, file_name_part(reference))
+}
+
+fn render_text(
+    kind: LetterKind,
+    lang: &str,
+    data: &LetterData,
+    date: NaiveDate,
+) -> Result<String, LetterError> {
+    let mut context = Context::from_serialize(data).map_err(template_error)?;
+    context.insert("date", &format_date(date, lang));
+    context.insert("signed", &format_date(data.mandate.signed, lang));
+    if let Some(debit) = &data.debit {
+        context.insert("debit_date", &format_date(debit.date, lang));
+        context.insert(
+            "debit_amount",
+            &format_amount(debit.amount, &debit.currency, lang),
+        );
+    }
+    let kind: &'static str = kind.into();
+    let text = TEMPLATES
+        .render(&format!("{}/{}.txt", lang, kind), &context)
+        .map_err(template_error)?;
+    let lines: Vec<&str> = text.trim().lines().map(str::trim_end).collect();
+    Ok(lines.join("\n") + "\n")
+}
+
+/// The text letter as HTML, one paragraph per block of lines.
+fn render_html(text: &str, lang: &str, title: &str) -> Result<String, LetterError> {
+    let paragraphs: Vec<Vec<&str>> = text
+        .split("\n\n")
+        .map(|block| block.trim_matches('\n').lines().collect::<Vec<&str>>())
+        .filter(|lines| !lines.is_empty())
+        .collect();
+    let mut context = Context::new();
+    context.insert("lang", lang);
+    context.insert("title", title);
+    context.insert("paragraphs", &paragraphs);
+    TEMPLATES
+        .render("letter.html", &context)
+        .map_err(template_error)
+}
+
+const MARGIN_LEFT: f32 = 25.0;
+const MARGIN_TOP: f32 = 27.0;
+const MARGIN_BOTTOM: f32 = 25.0;
+const FONT_SIZE: f32 = 11.0;
+const LINE_HEIGHT: f32 = 5.0;
+/// Characters of Helvetica 11pt that fit into the 160mm text width.
+const LINE_WIDTH: usize = 85;
+
+/// The text letter on A4 pages, long lines wrapped at word boundaries.
+fn render_pdf(text: &str, title: &str) -> Result<Vec<u8>, LetterError> {
+    let (doc, page, layer) = PdfDocument::new(title, Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Letter");
+    let font = doc
+        .add_builtin_font(BuiltinFont::Helvetica)
+        .map_err(pdf_error)?;
+    let mut current = doc.get_page(page).get_layer(layer);
+    let mut y = PAGE_HEIGHT - MARGIN_TOP;
+    for line in text.lines().flat_map(|line| wrap(line, LINE_WIDTH)) {
+        if y < MARGIN_BOTTOM {
+            let (page, layer) = doc.add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Letter");
+            current = doc.get_page(page).get_layer(layer);
+            y = PAGE_HEIGHT - MARGIN_TOP;
+        }
+        if !line.is_empty() {
+            current.use_text(to_win_ansi(&line), FONT_SIZE, Mm(MARGIN_LEFT), Mm(y), &font);
+        }
+        y -= LINE_HEIGHT;
+    }
+    doc.save_to_bytes().map_err(pdf_error)
+}
+
+pub(crate) fn format_date(date: NaiveDate, lang: &str) -> String {
+    match lang {
+        "de" => date.format("%d.%m.%Y").to_string(),
+        _ => date.format("%-d %B %Y").to_string(),
+    }
+}
+
+pub(crate) fn format_amount(amount: Decimal, currency: &str, lang: &str) -> String {
+    let amount = format!("{:.2}", amount.abs());
+    match lang {
+        "de" => format!("{} {}", amount.replace('.', ","), currency),
+        _ => format!("{} {}", amount, currency),
+    }
+}
+
+fn template_error(e: tera::Error) -> LetterError {
+    // the cause of a render error is where the template went wrong
+    let cause = std::error::Error::source(&e).map(|c| c.to_string());
+    LetterError(match cause {
+        Some(cause) => format!("{}: {}", e, cause),
+        None => e.to_string(),
+    })
+}
+
+fn pdf_error(e: printpdf::Error) -> LetterError {
+    LetterError(e.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use api_models::models::{
+        letter::LETTER_LANGUAGES, Address, BankAccount, Creditor, LetterFormat, LetterKind,
+    };
+    use chrono::NaiveDate;
+    use rust_decimal::Decimal;
+
+    use super::{format_amount, format_date, render, title, Debit, Debtor, LetterData, MandateInfo};
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::from_str(value).unwrap()
+    }
+
+    fn account(institution: &str, iban: &str) -> BankAccount {
+        BankAccount {
+            institution: institution.to_string(),
+            iban: iban.to_string(),
+            // the handler always sets the holder
+            holder_name: Some("Zoë Łukasiewicz".to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn data() -> LetterData {
+        LetterData {
+            debtor: Debtor {
+                name: "Zoë Łukasiewicz".to_string(),
+                address: Some(Address {
+                    street: "Hauptstraße".to_string(),
+                    house_number: "12".to_string(),
+                    zip: "10115".to_string(),
+                    place: "Berlin".to_string(),
+                }),
+            },
+            creditor: Creditor {
+                name: "Müller & Söhne".to_string(),
+                sepa_identifier: Some("DE98ZZZ09999999999".to_string()),
+                address: Address {
+                    street: "Marktplatz".to_string(),
+                    house_number: "1".to_string(),
+                    zip: "80331".to_string(),
+                    place: "München".to_string(),
+                },
+            },
+            mandate: MandateInfo {
+                reference: Some("GAS-4711".to_string()),
+                name: "Gas".to_string(),
+                signed: date("2021-05-17"),
+            },
+            account: account("Postbank", "DE02120300000000202051"),
+            previous_account: Some(account("Commerzbank", "DE89370400440532013000")),
+            debit: Some(Debit {
+                date: date("2023-03-01"),
+                amount: Decimal::from_str("-42.50").unwrap(),
+                currency: "EUR".to_string(),
+                iban: "DE89370400440532013000".to_string(),
+                end_to_end_id: Some("E2E-GAS-03".to_string()),
+            }),
+        }
+    }
+
+    #[test]
+    fn test_render_every_kind_language_and_format() {
+        let data = data();
+        for kind in LetterKind::ALL {
+            for lang in LETTER_LANGUAGES {
+                let render = |format| {
+                    render(kind, lang, format, &data, date("2023-03-02"))
+                        .unwrap_or_else(|e| panic!("{:?} in {}: {}", kind, lang, e))
+                };
+                let text = String::from_utf8(render(LetterFormat::Txt)).unwrap();
+                assert!(text.starts_with("Zoë Łukasiewicz\n"), "{}", text);
+                assert!(text.contains("Müller & Söhne"), "{}", text);
+                assert!(text.contains("GAS-4711"), "{}", text);
+                assert!(!text.contains("\n\n\n\n\n"), "{}", text);
+
+                let html = String::from_utf8(render(LetterFormat::Html)).unwrap();
+                assert!(html.contains(&format!("<html lang=\"{}\">", lang)));
+                assert!(html.contains("<p>Müller &amp; Söhne<br>Marktplatz 1<br>80331 München</p>"));
+
+                let pdf = render(LetterFormat::Pdf);
+                assert!(pdf.starts_with(b"%PDF-"));
+            }
+        }
+    }
+
+    #[test]
+    fn test_refund_request_with_debit() {
+        let text = render(
+            LetterKind::RefundRequest,
+            "de",
+            LetterFormat::Txt,
+            &data(),
+            date("2023-03-02"),
+        )
+        .unwrap();
+        let text = String::from_utf8(text).unwrap();
+        assert!(text.contains("Berlin, 02.03.2023"), "{}", text);
+        assert!(text.contains("42,50 EUR"), "{}", text);
+        assert!(text.contains("DE89370400440532013000"), "{}", text);
+    }
+
+    #[test]
+    fn test_title() {
+        let mut data = data();
+        assert_eq!("revocation-GAS-4711", title(LetterKind::Revocation, &data));
+        data.mandate.reference = None;
+        data.mandate.name = "Gas & Strom".to_string();
+        assert_eq!("account_change-Gas___Strom", title(LetterKind::AccountChange, &data));
+    }
+
+    #[test]
+    fn test_format_date_and_amount() {
+        assert_eq!("2 March 2023", format_date(date("2023-03-02"), "en"));
+        assert_eq!("02.03.2023", format_date(date("2023-03-02"), "de"));
+        let amount = Decimal::from_str("-1234.5").unwrap();
+        assert_eq!("1234.50 EUR", format_amount(amount, "EUR", "en"));
+        assert_eq!("1234,50 EUR", format_amount(amount, "EUR", "de"));
+    }
+}
@@ -1,10 +1,27 @@
+use std::sync::Arc;
+
 use entity::sea_orm::DatabaseConnection;
 
 pub mod auth;
 pub mod errors;
 pub mod handlers;
+pub mod history;
+pub mod jobs;
+pub mod letters;
+pub mod mail;
+pub mod mandate_form;
+pub mod notifications;
+pub mod pain008;
+pub mod pdf;
+pub mod statement;
+#[cfg(test)]
+mod testing;
+pub mod webhooks;
 
 #[derive(Debug, Clone)]
 pub struct AppState {
     pub connection: DatabaseConnection,
+    pub auth: auth::AuthConfig,
+    pub mailer: Arc<dyn mail::MailTransport>,
+    pub jobs: jobs::JobSchedules,
 }
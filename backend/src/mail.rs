@@ -0,0 +1,197 @@
+//! Outgoing email. A `MailTransport` delivers plain text mails over SMTP, or into files or the
+//! log during development.
+
+use std::env;
+use std::error::Error;
+use std::fmt::{Debug, Display};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use lettre::{
+    message::{header::ContentType, Mailbox},
+    transport::smtp::authentication::Credentials,
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+};
+use log::info;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Mail {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct MailError(pub String);
+
+impl Display for MailError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[async_trait]
+pub trait MailTransport: Debug + Send + Sync {
+    async fn send(&self, mail: &Mail) -> Result<(), MailError>;
+}
+
+/// Reads `MAIL_TRANSPORT` (`smtp`, `file` or `log`, the default) and `MAIL_FROM`.
+/// SMTP needs `SMTP_HOST` and takes `SMTP_PORT`, `SMTP_TLS` (`starttls`, the default, `tls`
+/// or `none` for a local test server like MailHog), `SMTP_USERNAME` and `SMTP_PASSWORD`.
+/// Files are written to `MAIL_DIR`, `mail` by default.
+pub fn from_env() -> Result<Arc<dyn MailTransport>, Box<dyn Error>> {
+    let from = env::var("MAIL_FROM").unwrap_or_else(|_| "SepaMa <noreply@localhost>".to_string());
+    match env::var("MAIL_TRANSPORT").as_deref().unwrap_or("log") {
+        "smtp" => Ok(Arc::new(SmtpTransport::from_env(&from)?)),
+        "file" => Ok(Arc::new(FileTransport {
+            from,
+            dir: PathBuf::from(env::var("MAIL_DIR").unwrap_or_else(|_| "mail".to_string())),
+        })),
+        "log" => Ok(Arc::new(LogTransport)),
+        other => Err(format!("Unknown MAIL_TRANSPORT {}", other).into()),
+    }
+}
+
+pub struct SmtpTransport {
+    host: String,
+    from: Mailbox,
+    mailer: AsyncSmtpTransport<Tokio1Executor>,
+}
+
+impl SmtpTransport {
+    fn from_env(from: &str) -> Result<SmtpTransport, Box<dyn Error>> {
+        let host = env::var("SMTP_HOST").map_err(|_| "SMTP_HOST must be set")?;
+        let mut builder = match env::var("SMTP_TLS").as_deref().unwrap_or("starttls") {
+            "starttls" => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&host)?,
+            "tls" => AsyncSmtpTransport::<Tokio1Executor>::relay(&host)?,
+            "none" => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&host),
+            other => return Err(format!("Unknown SMTP_TLS {}", other).into()),
+        };
+        if let Ok(port) = env::var("SMTP_PORT") {
+            builder = builder.port(port.parse().map_err(|_| "SMTP_PORT must be a port")?);
+        }
+        if let (Ok(username), Ok(password)) = (env::var("SMTP_USERNAME"), env::var("SMTP_PASSWORD"))
+        {
+            builder = builder.credentials(Credentials::new(username, password));
+        }
+        Ok(SmtpTransport {
+            host,
+            from: from.parse()?,
+            mailer: builder.build(),
+        })
+    }
+}
+
+impl Debug for SmtpTransport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SmtpTransport")
+            .field("host", &self.host)
+            .field("from", &self.from.to_string())
+            .finish()
+    }
+}
+
+#[async_trait]
+impl MailTransport for SmtpTransport {
+    async fn send(&self, mail: &Mail) -> Result<(), MailError> {
+        let to: Mailbox = mail
+            .to
+            .parse()
+            .map_err(|e| MailError(format!("Invalid recipient {}: {}", mail.to, e)))?;
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(mail.subject.clone())
+            .header(ContentType::TEXT_PLAIN)
+            .body(mail.body.clone())
+            .map_err(|e| MailError(e.to_string()))?;
+        self.mailer
+            .send(message)
+            .await
+            .map(|_| ())
+            .map_err(|e| MailError(e.to_string()))
+    }
+}
+
+/// Writes every mail as an `.eml` file, to look at them without a mail server.
+#[derive(Debug)]
+pub struct FileTransport {
+    from: String,
+    dir: PathBuf,
+}
+
+#[async_trait]
+impl MailTransport for FileTransport {
+    async fn send(&self, mail: &Mail) -> Result<(), MailError> {
+        let now = Utc::now();
+        let content = format!(
+            "Date: {}\r\nFrom: {}\r\nTo: {}\r\nSubject: {}\r\nContent-Type: text/plain; charset=utf-8\r\n\r\n{}",
+            now.to_rfc2822(),
+            self.from,
+            mail.to,
+            mail.subject,
+            mail.body.replace('\n', "\r\n")
+        );
+        let path = self.dir.join(format!(
+            "{}-{}.eml",
+            now.format("%Y%m%dT%H%M%S"),
+            uuid::Uuid::new_v4()
+        ));
+        std::fs::create_dir_all(&self.dir)
+            .and_then(|_| std::fs::write(&path, content))
+            .map_err(|e| MailError(format!("Writing {}: {}", path.display(), e)))
+    }
+}
+
+#[derive(Debug)]
+pub struct LogTransport;
+
+#[async_trait]
+impl MailTransport for LogTransport {
+    async fn send(&self, mail: &Mail) -> Result<(), MailError> {
+        info!("Mail to {}: {}\n{}", mail.to, mail.subject, mail.body);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{FileTransport, LogTransport, Mail, MailTransport};
+
+    fn mail() -> Mail {
+        Mail {
+            to: "erika@example.com".to_string(),
+            subject: "Bevorstehende Lastschriften".to_string(),
+            body: "Hallo Erika,\n\nGrüße\n".to_string(),
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_file_transport_writes_eml_files() {
+        let dir = std::env::temp_dir().join(format!("sepama-mail-{}", uuid::Uuid::new_v4()));
+        let transport = FileTransport {
+            from: "SepaMa <noreply@localhost>".to_string(),
+            dir: dir.clone(),
+        };
+        transport.send(&mail()).await.unwrap();
+        let files: Vec<_> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|f| f.unwrap().path())
+            .collect();
+        assert_eq!(1, files.len());
+        assert_eq!(Some("eml"), files[0].extension().and_then(|e| e.to_str()));
+        let content = std::fs::read_to_string(&files[0]).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(content.contains("\r\nFrom: SepaMa <noreply@localhost>\r\n"));
+        assert!(content.contains("\r\nTo: erika@example.com\r\n"));
+        assert!(content.contains("\r\nSubject: Bevorstehende Lastschriften\r\n"));
+        assert!(content.ends_with("\r\n\r\nHallo Erika,\r\n\r\nGrüße\r\n"));
+    }
+
+    #[actix_web::test]
+    async fn test_log_transport() {
+        assert_eq!(Ok(()), LogTransport.send(&mail()).await);
+    }
+}
@@ -1,14 +1,15 @@
 use actix_web::dev::ServiceRequest;
-use actix_web::web::{get, head, post, scope, Data};
+use actix_web::web::{
+    delete, get, head, post, put, resource, scope, Data, JsonConfig, PathConfig, QueryConfig,
+};
 use actix_web::{App, Error, HttpServer};
-use actix_web_httpauth::extractors::bearer::{BearerAuth, Config};
-use actix_web_httpauth::extractors::AuthenticationError;
+use actix_web_httpauth::extractors::bearer::BearerAuth;
 use actix_web_httpauth::middleware::HttpAuthentication;
 use migration::{Migrator, MigratorTrait};
 use entity::sea_orm;
 use std::env;
 
-use backend::{auth, handlers, AppState};
+use backend::{auth, errors::ServiceError, handlers, jobs, mail, webhooks, AppState};
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
@@ -19,11 +20,34 @@ async fn main() -> std::io::Result<()> {
     let static_dir = std::env::var("STATIC_FILES_DIR").unwrap_or_else(|_| "web_ui/web".to_string());
     let conn = sea_orm::Database::connect(&database_url).await.unwrap();
     Migrator::up(&conn, None).await.unwrap();
-    let state = AppState { connection: conn };
+    let auth_config = auth::AuthConfig::from_env()
+        .await
+        .expect("OpenID Connect configuration failed");
+    auth_config.jwks.spawn_refresh();
+    let mailer = mail::from_env().expect("Mail configuration failed");
+    let job_schedules = jobs::JobSchedules::from_env().expect("Job schedules are invalid");
+    let state = AppState {
+        connection: conn,
+        auth: auth_config,
+        mailer,
+        jobs: job_schedules,
+    };
+    jobs::spawn_scheduler(state.clone());
+    webhooks::spawn_dispatcher(state.clone());
     HttpServer::new(move || {
         let auth = HttpAuthentication::bearer(validator);
         App::new()
             .app_data(Data::new(state.clone()))
+            .app_data(JsonConfig::default().error_handler(|err, _| {
+                ServiceError::BadRequest(err.to_string()).into()
+            }))
+            .app_data(PathConfig::default().error_handler(|err, _| {
+                ServiceError::BadRequest(err.to_string()).into()
+            }))
+            .app_data(QueryConfig::default().error_handler(|err, _| {
+                ServiceError::BadRequest(err.to_string()).into()
+            }))
+            .service(resource("/api/config").route(get().to(handlers::config::get_config)))
             .service(
                 scope("/api")
                     .wrap(auth)
@@ -36,7 +60,73 @@ async fn main() -> std::io::Result<()> {
                     .service(
                         scope("/mandates")
                             .route("", get().to(handlers::mandate::get_mandates))
-                            .route("", post().to(handlers::mandate::save_mandate)),
+                            .route("", post().to(handlers::mandate::save_mandate))
+                            .route("/export/pain008", post().to(handlers::export::pain008))
+                            .service(
+                                resource("/{api_id}")
+                                    .route(get().to(handlers::mandate::get_mandate))
+                                    .route(put().to(handlers::mandate::put_mandate))
+                                    .route(delete().to(handlers::mandate::delete_mandate)),
+                            )
+                            .route(
+                                "/{api_id}/history",
+                                get().to(handlers::mandate::get_mandate_history),
+                            )
+                            .route(
+                                "/{api_id}/letters/{kind}",
+                                get().to(handlers::letter::get_letter),
+                            )
+                            .route(
+                                "/{api_id}/form.pdf",
+                                get().to(handlers::letter::get_mandate_form),
+                            ),
+                    )
+                    .service(
+                        scope("/accounts")
+                            .route("", get().to(handlers::account::get_accounts))
+                            .route("", post().to(handlers::account::create_account))
+                            .route("/switch", post().to(handlers::account::switch_account))
+                            .service(
+                                resource("/{api_id}")
+                                    .route(get().to(handlers::account::get_account))
+                                    .route(put().to(handlers::account::update_account))
+                                    .route(delete().to(handlers::account::delete_account)),
+                            ),
+                    )
+                    .service(
+                        scope("/tags")
+                            .route("", get().to(handlers::tag::get_tags))
+                            .route("/merge", post().to(handlers::tag::merge_tag))
+                            .route("/{tag}", put().to(handlers::tag::rename_tag)),
+                    )
+                    .service(
+                        scope("/statements")
+                            .route("", post().to(handlers::statement::import_statement)),
+                    )
+                    .service(
+                        scope("/transactions")
+                            .route("", get().to(handlers::statement::get_transactions)),
+                    )
+                    .route("/forecast", get().to(handlers::forecast::get_forecast))
+                    .route("/deadlines", get().to(handlers::deadline::get_deadlines))
+                    .service(
+                        scope("/webhooks")
+                            .route("", get().to(handlers::webhook::get_webhooks))
+                            .service(
+                                resource("/{api_id}")
+                                    .route(put().to(handlers::webhook::put_webhook))
+                                    .route(delete().to(handlers::webhook::delete_webhook)),
+                            )
+                            .route(
+                                "/{api_id}/deliveries",
+                                get().to(handlers::webhook::get_deliveries),
+                            ),
+                    )
+                    .service(
+                        scope("/admin/jobs")
+                            .route("", get().to(handlers::admin::get_jobs))
+                            .route("/runs", get().to(handlers::admin::get_job_runs))
+                            .route("/{job}/run", post().to(handlers::admin::run_job)),
                     ),
             )
             .service(
@@ -54,9 +144,12 @@ async fn validator(
     req: ServiceRequest,
     credentials: BearerAuth,
 ) -> Result<ServiceRequest, (Error, ServiceRequest)> {
-    let config = req.app_data::<Config>().cloned().unwrap_or_default();
-    match auth::get_token_data(credentials.token()).await {
+    let auth_config = match req.app_data::<Data<AppState>>() {
+        Some(state) => state.auth.clone(),
+        None => return Err((ServiceError::InternalServerError.into(), req)),
+    };
+    match auth::get_token_data(credentials.token(), &auth_config).await {
         Ok(_) => Ok(req),
-        Err(_) => Err((AuthenticationError::from(config).into(), req)),
+        Err(e) => Err((e.into(), req)),
     }
 }
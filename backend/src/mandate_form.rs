@@ -0,0 +1,410 @@
+//! The EPC SEPA Core Direct Debit Mandate form, pre-filled and drawn as an A4 PDF.
+
+use api_models::models::{Address, BankAccount, Creditor, Scheme};
+use chrono::NaiveDate;
+use printpdf::{
+    path::PaintMode, BuiltinFont, IndirectFontRef, Mm, PdfDocument, PdfLayerReference, Rect,
+};
+
+use crate::pdf::{file_name_part, to_win_ansi, wrap, PAGE_HEIGHT, PAGE_WIDTH};
+
+/// What is printed into the form, missing values are left blank to be filled in by hand.
+#[derive(Clone, Debug)]
+pub struct MandateForm {
+    pub scheme: Scheme,
+    pub creditor: Creditor,
+    pub mandate_reference: Option<String>,
+    /// The account holder.
+    pub debtor_name: String,
+    pub debtor_address: Option<Address>,
+    pub account: BankAccount,
+    /// `None` leaves both payment type boxes unticked.
+    pub recurrent: Option<bool>,
+    pub signed_place: Option<String>,
+    pub signed_date: Option<NaiveDate>,
+}
+
+/// Wording of the form, the authorisation text is the one of the EPC rulebook.
+struct Labels {
+    title: &'static str,
+    scheme: &'static str,
+    b2b_scheme: &'static str,
+    mandate_reference: &'static str,
+    creditor_identifier: &'static str,
+    creditor: &'static str,
+    creditor_name: &'static str,
+    street: &'static str,
+    city: &'static str,
+    country: &'static str,
+    /// `{creditor}` is replaced by the creditor name.
+    authorisation: &'static str,
+    b2b_authorisation: &'static str,
+    debtor: &'static str,
+    debtor_name: &'static str,
+    iban: &'static str,
+    bic: &'static str,
+    payment_type: &'static str,
+    recurrent: &'static str,
+    one_off: &'static str,
+    place: &'static str,
+    date: &'static str,
+    signature: &'static str,
+    note: &'static str,
+    date_format: &'static str,
+}
+
+const EN: Labels = Labels {
+    title: "SEPA Direct Debit Mandate",
+    scheme: "SEPA Core Direct Debit Scheme",
+    b2b_scheme: "SEPA Business-to-Business Direct Debit Scheme",
+    mandate_reference: "Mandate reference",
+    creditor_identifier: "Creditor identifier",
+    creditor: "Creditor",
+    creditor_name: "Creditor name",
+    street: "Street name and number",
+    city: "Postal code and city",
+    country: "Country",
+    authorisation: "By signing this mandate form, you authorise (A) {creditor} to send \
+        instructions to your bank to debit your account and (B) your bank to debit your account \
+        in accordance with the instructions from {creditor}. As part of your rights, you are \
+        entitled to a refund from your bank under the terms and conditions of your agreement \
+        with your bank. A refund must be claimed within 8 weeks starting from the date on which \
+        your account was debited.",
+    b2b_authorisation: "By signing this mandate form, you authorise (A) {creditor} to send \
+        instructions to your bank to debit your account and (B) your bank to debit your account \
+        in accordance with the instructions from {creditor}. This mandate is only intended for \
+        business-to-business transactions. You are not entitled to a refund from your bank after \
+        your account has been debited, but you are entitled to request your bank not to debit \
+        your account up until the day on which the payment is due.",
+    debtor: "Debtor",
+    debtor_name: "Name of the debtor (account holder)",
+    iban: "Account number - IBAN",
+    bic: "BIC",
+    payment_type: "Type of payment",
+    recurrent: "Recurrent payment",
+    one_off: "One-off payment",
+    place: "Signed at (place)",
+    date: "Date",
+    signature: "Signature(s)",
+    note: "Note: Your rights regarding the above mandate are explained in a statement that you \
+        can obtain from your bank.",
+    date_format: "%d/%m/%Y",
+};
+
+const DE: Labels = Labels {
+    title: "SEPA-Lastschriftmandat",
+    scheme: "SEPA-Basislastschriftverfahren",
+    b2b_scheme: "SEPA-Firmenlastschriftverfahren",
+    mandate_reference: "Mandatsreferenz",
+    creditor_identifier: "Gläubiger-Identifikationsnummer",
+    creditor: "Zahlungsempfänger",
+    creditor_name: "Name des Zahlungsempfängers",
+    street: "Straße und Hausnummer",
+    city: "Postleitzahl und Ort",
+    country: "Land",
+    authorisation: "Ich ermächtige {creditor}, Zahlungen von meinem Konto mittels Lastschrift \
+        einzuziehen. Zugleich weise ich mein Kreditinstitut an, die von {creditor} auf mein \
+        Konto gezogenen Lastschriften einzulösen. Hinweis: Ich kann innerhalb von acht Wochen, \
+        beginnend mit dem Belastungsdatum, die Erstattung des belasteten Betrages verlangen. Es \
+        gelten dabei die mit meinem Kreditinstitut vereinbarten Bedingungen.",
+    b2b_authorisation: "Wir ermächtigen {creditor}, Zahlungen von unserem Konto mittels \
+        Lastschrift einzuziehen. Zugleich weisen wir unser Kreditinstitut an, die von {creditor} \
+        auf unser Konto gezogenen Lastschriften einzulösen. Hinweis: Dieses Lastschriftmandat \
+        dient nur dem Einzug von Lastschriften, die auf Konten von Unternehmen gezogen sind. Wir \
+        sind nicht berechtigt, nach der erfolgten Einlösung eine Erstattung des belasteten \
+        Betrages zu verlangen. Wir sind berechtigt, unser Kreditinstitut bis zum Fälligkeitstag \
+        anzuweisen, Lastschriften nicht einzulösen.",
+    debtor: "Zahlungspflichtiger",
+    debtor_name: "Name des Zahlungspflichtigen (Kontoinhaber)",
+    iban: "IBAN",
+    bic: "BIC",
+    payment_type: "Zahlungsart",
+    recurrent: "Wiederkehrende Zahlung",
+    one_off: "Einmalige Zahlung",
+    place: "Ort",
+    date: "Datum",
+    signature: "Unterschrift(en)",
+    note: "Hinweis: Ihre Rechte zu dem obigen Mandat sind in einem Merkblatt erläutert, das Sie \
+        von Ihrem Kreditinstitut erhalten können.",
+    date_format: "%d.%m.%Y",
+};
+
+const LEFT: f32 = 20.0;
+const WIDTH: f32 = 170.0;
+const FIELD_HEIGHT: f32 = 6.5;
+const VALUE_SIZE: f32 = 10.0;
+
+/// Renders the form in `lang` (one of `LETTER_LANGUAGES`).
+pub fn render(form: &MandateForm, lang: &str) -> Result<Vec<u8>, printpdf::Error> {
+    let labels = match lang {
+        "de" => &DE,
+        _ => &EN,
+    };
+    let (doc, page, layer) =
+        PdfDocument::new(labels.title, Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Form");
+    let canvas = Canvas {
+        layer: doc.get_page(page).get_layer(layer),
+        regular: doc.add_builtin_font(BuiltinFont::Helvetica)?,
+        bold: doc.add_builtin_font(BuiltinFont::HelveticaBold)?,
+    };
+    canvas.layer.set_outline_thickness(0.5);
+
+    canvas.text(labels.title, 15.0, LEFT, 277.0, true);
+    let (scheme, authorisation) = match form.scheme {
+        Scheme::CORE => (labels.scheme, labels.authorisation),
+        Scheme::B2B => (labels.b2b_scheme, labels.b2b_authorisation),
+    };
+    canvas.text(scheme, 9.0, LEFT, 271.0, false);
+    canvas.field(
+        labels.mandate_reference,
+        form.mandate_reference.as_deref().unwrap_or_default(),
+        LEFT,
+        263.0,
+        100.0,
+    );
+    let creditor_identifier = form
+        .creditor
+        .creditor_identifier()
+        .map(|ci| ci.to_string())
+        .unwrap_or_default();
+    canvas.field(
+        labels.creditor_identifier,
+        &creditor_identifier,
+        125.0,
+        263.0,
+        65.0,
+    );
+
+    canvas.text(labels.creditor, 10.0, LEFT, 248.0, true);
+    let address = &form.creditor.address;
+    canvas.field(
+        labels.creditor_name,
+        &form.creditor.name,
+        LEFT,
+        243.0,
+        WIDTH,
+    );
+    canvas.field(
+        labels.street,
+        &format!("{} {}", address.street, address.house_number),
+        LEFT,
+        232.0,
+        WIDTH,
+    );
+    canvas.field(
+        labels.city,
+        &format!("{} {}", address.zip, address.place),
+        LEFT,
+        221.0,
+        130.0,
+    );
+    canvas.field(
+        labels.country,
+        country(&creditor_identifier),
+        155.0,
+        221.0,
+        35.0,
+    );
+
+    let authorisation = authorisation.replace("{creditor}", &form.creditor.name);
+    let mut y = 206.0;
+    for line in wrap(&authorisation, 105) {
+        canvas.text(&line, 9.0, LEFT, y, false);
+        y -= 4.2;
+    }
+
+    canvas.text(labels.debtor, 10.0, LEFT, 172.0, true);
+    canvas.field(labels.debtor_name, &form.debtor_name, LEFT, 167.0, WIDTH);
+    let (street, city) = form
+        .debtor_address
+        .as_ref()
+        .map_or_else(Default::default, |a| {
+            (
+                format!("{} {}", a.street, a.house_number),
+                format!("{} {}", a.zip, a.place),
+            )
+        });
+    canvas.field(labels.street, &street, LEFT, 156.0, WIDTH);
+    canvas.field(labels.city, &city, LEFT, 145.0, 130.0);
+    canvas.field(
+        labels.country,
+        country(&form.account.iban),
+        155.0,
+        145.0,
+        35.0,
+    );
+    canvas.field(
+        labels.iban,
+        &group_iban(&form.account.iban),
+        LEFT,
+        134.0,
+        115.0,
+    );
+    canvas.field(
+        labels.bic,
+        form.account.bic.as_deref().unwrap_or_default(),
+        140.0,
+        134.0,
+        50.0,
+    );
+
+    canvas.text(labels.payment_type, 10.0, LEFT, 118.0, true);
+    canvas.checkbox(labels.recurrent, form.recurrent == Some(true), LEFT, 112.0);
+    canvas.checkbox(labels.one_off, form.recurrent == Some(false), 90.0, 112.0);
+
+    canvas.field(
+        labels.place,
+        form.signed_place.as_deref().unwrap_or_default(),
+        LEFT,
+        103.0,
+        100.0,
+    );
+    let date = form
+        .signed_date
+        .map(|d| d.format(labels.date_format).to_string())
+        .unwrap_or_default();
+    canvas.field(labels.date, &date, 125.0, 103.0, 65.0);
+    canvas.text(labels.signature, 7.0, LEFT, 89.0, false);
+    canvas.rect(LEFT, 67.0, WIDTH, 20.0);
+
+    let mut y = 58.0;
+    for line in wrap(labels.note, 120) {
+        canvas.text(&line, 8.0, LEFT, y, false);
+        y -= 3.8;
+    }
+    doc.save_to_bytes()
+}
+
+/// File name of the form without extension, e.g. `mandate-ABC123`.
+pub fn file_name(form: &MandateForm, display_name: &str) -> String {
+    let reference = form.mandate_reference.as_deref().unwrap_or(display_name);
+    format!("mandate-{}", file_name_part(reference))
+}
+
+struct Canvas {
+    layer: PdfLayerReference,
+    regular: IndirectFontRef,
+    bold: IndirectFontRef,
+}
+
+impl Canvas {
+    fn text(&self, text: &str, size: f32, x: f32, y: f32, bold: bool) {
+        let font = if bold { &self.bold } else { &self.regular };
+        self.layer
+            .use_text(to_win_ansi(text), size, Mm(x), Mm(y), font);
+    }
+
+    fn rect(&self, x: f32, y: f32, width: f32, height: f32) {
+        self.layer.add_rect(
+            Rect::new(Mm(x), Mm(y), Mm(x + width), Mm(y + height)).with_mode(PaintMode::Stroke),
+        );
+    }
+
+    /// A labelled box, `y` is the baseline of the label above it.
+    fn field(&self, label: &str, value: &str, x: f32, y: f32, width: f32) {
+        self.text(label, 7.0, x, y, false);
+        self.rect(x, y - 1.0 - FIELD_HEIGHT, width, FIELD_HEIGHT);
+        // Helvetica 10pt needs about 2mm per character
+        let fitting: String = value.chars().take(((width - 3.0) / 2.0) as usize).collect();
+        self.text(&fitting, VALUE_SIZE, x + 1.5, y - 5.8, false);
+    }
+
+    fn checkbox(&self, label: &str, checked: bool, x: f32, y: f32) {
+        self.rect(x, y, 4.0, 4.0);
+        if checked {
+            self.text("X", VALUE_SIZE, x + 0.8, y + 0.6, true);
+        }
+        self.text(label, 9.0, x + 6.0, y + 0.8, false);
+    }
+}
+
+/// IBAN in groups of four, as printed on forms.
+fn group_iban(iban: &str) -> String {
+    let compact: Vec<char> = iban.chars().filter(|c| !c.is_whitespace()).collect();
+    compact
+        .chunks(4)
+        .map(|group| group.iter().collect::<String>())
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Country code of an IBAN or creditor identifier.
+fn country(identifier: &str) -> &str {
+    identifier.get(0..2).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use api_models::models::{letter::LETTER_LANGUAGES, Address, BankAccount, Creditor, Scheme};
+    use chrono::NaiveDate;
+
+    use super::{country, file_name, group_iban, render, MandateForm};
+
+    fn form(scheme: Scheme) -> MandateForm {
+        MandateForm {
+            scheme,
+            creditor: Creditor {
+                name: "Stadtwerke Łódź".to_string(),
+                sepa_identifier: Some("DE98ZZZ09999999999".to_string()),
+                address: Address {
+                    street: "Marktplatz".to_string(),
+                    house_number: "1".to_string(),
+                    zip: "80331".to_string(),
+                    place: "München".to_string(),
+                },
+            },
+            mandate_reference: Some("GAS-4711".to_string()),
+            debtor_name: "Erika Mustermann".to_string(),
+            debtor_address: None,
+            account: BankAccount {
+                institution: "Commerzbank".to_string(),
+                iban: "DE89370400440532013000".to_string(),
+                bic: Some("COBADEFFXXX".to_string()),
+                ..Default::default()
+            },
+            recurrent: Some(true),
+            signed_place: Some("Berlin".to_string()),
+            signed_date: NaiveDate::from_str("2023-03-02").ok(),
+        }
+    }
+
+    #[test]
+    fn test_render_every_scheme_and_language() {
+        for scheme in [Scheme::CORE, Scheme::B2B] {
+            for lang in LETTER_LANGUAGES {
+                let pdf = render(&form(scheme), lang).unwrap();
+                assert!(pdf.starts_with(b"%PDF-"), "{:?} in {}", scheme, lang);
+            }
+        }
+    }
+
+    #[test]
+    fn test_render_blank_form() {
+        let mut blank = form(Scheme::CORE);
+        blank.creditor = Creditor::default();
+        blank.mandate_reference = None;
+        blank.account = BankAccount::default();
+        blank.recurrent = None;
+        blank.signed_place = None;
+        blank.signed_date = None;
+        assert!(render(&blank, "en").unwrap().starts_with(b"%PDF-"));
+    }
+
+    #[test]
+    fn test_file_name() {
+        let mut form = form(Scheme::CORE);
+        assert_eq!("mandate-GAS-4711", file_name(&form, "Gas"));
+        form.mandate_reference = None;
+        assert_eq!("mandate-Gas___Strom", file_name(&form, "Gas & Strom"));
+    }
+
+    #[test]
+    fn test_iban_and_country() {
+        assert_eq!("DE89 3704 0044 0532 0130 00", group_iban("DE89370400440532013000"));
+        assert_eq!("de89 3704", group_iban("de89 37 04"));
+        assert_eq!("DE", country("DE98ZZZ09999999999"));
+        assert_eq!("", country("D"));
+    }
+}
@@ -0,0 +1,531 @@
+//! Emails the users opted in to: debits due in the next days, debits collected after the end of
+//! their mandate, a reminder to complete the profile and mandates that expired. The per language
+//! Tera templates render the subject on their first line.
+
+use std::collections::HashSet;
+
+use api_models::models::{letter::letter_language, mandate::usable_until, DebitAlert};
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use entity::{
+    bank_transaction::{self, Entity as BankTransactionEntity},
+    mandate,
+    sea_orm::{ColumnTrait, Condition, EntityTrait, QueryFilter},
+    user_profile::{self, Entity as UserProfileEntity, ProfileStatus},
+};
+use lazy_static::lazy_static;
+use log::{debug, error};
+use serde::Serialize;
+use tera::{Context, Tera};
+
+use crate::errors::ServiceError;
+use crate::handlers::{db_error, deadline, forecast};
+use crate::letters::{format_amount, format_date};
+use crate::mail::{Mail, MailTransport};
+use crate::AppState;
+
+/// Debits are announced this many days before they are due.
+const UPCOMING_DAYS: i64 = 3;
+
+/// Incomplete profiles are reminded once, this many days after they were created.
+const PROFILE_REMINDER_DAYS: i64 = 7;
+
+lazy_static! {
+    static ref TEMPLATES: Tera = templates().expect("mail templates must be valid");
+}
+
+fn templates() -> tera::Result<Tera> {
+    let mut tera = Tera::default();
+    tera.add_raw_templates(vec![
+        (
+            "en/upcoming_debits.txt",
+            include_str!("../templates/mails/en/upcoming_debits.txt.tera"),
+        ),
+        (
+            "en/debit_alerts.txt",
+            include_str!("../templates/mails/en/debit_alerts.txt.tera"),
+        ),
+        (
+            "en/profile_reminder.txt",
+            include_str!("../templates/mails/en/profile_reminder.txt.tera"),
+        ),
+        (
+            "en/expired_mandates.txt",
+            include_str!("../templates/mails/en/expired_mandates.txt.tera"),
+        ),
+        (
+            "de/upcoming_debits.txt",
+            include_str!("../templates/mails/de/upcoming_debits.txt.tera"),
+        ),
+        (
+            "de/debit_alerts.txt",
+            include_str!("../templates/mails/de/debit_alerts.txt.tera"),
+        ),
+        (
+            "de/profile_reminder.txt",
+            include_str!("../templates/mails/de/profile_reminder.txt.tera"),
+        ),
+        (
+            "de/expired_mandates.txt",
+            include_str!("../templates/mails/de/expired_mandates.txt.tera"),
+        ),
+    ])?;
+    Ok(tera)
+}
+
+/// A debit as listed in a mail, formatted in the language of the mail.
+#[derive(Debug, Serialize)]
+struct DebitLine {
+    date: String,
+    name: String,
+    creditor: Option<String>,
+    amount: String,
+    iban: Option<String>,
+    /// Last day to dispute the debit.
+    until: Option<String>,
+    canceled: bool,
+}
+
+/// A mandate as listed in a mail.
+#[derive(Debug, Serialize)]
+struct MandateLine {
+    name: String,
+    creditor: Option<String>,
+    reference: Option<String>,
+    /// Day of the last collection, or of the signature when there was none.
+    last_used: String,
+    expired_on: String,
+}
+
+/// Sends what became due after `since` until `now`, with `since` being the time of the previous
+/// run every debit and reminder is sent once. A failed mail is logged and not sent again.
+/// Returns the number of sent mails.
+pub async fn send_notifications(
+    state: &AppState,
+    since: NaiveDateTime,
+    now: NaiveDateTime,
+) -> Result<usize, ServiceError> {
+    let profiles = UserProfileEntity::find()
+        .filter(user_profile::Column::Email.is_not_null())
+        .filter(
+            Condition::any()
+                .add(user_profile::Column::NotifyUpcomingDebits.eq(true))
+                .add(user_profile::Column::NotifyDebitAlerts.eq(true))
+                .add(user_profile::Column::NotifyProfileReminders.eq(true)),
+        )
+        .all(&state.connection)
+        .await
+        .map_err(db_error)?;
+    let mut sent = 0;
+    for profile in profiles {
+        let mails = mails_for(state, &profile, since, now).await?;
+        sent += send_all(state.mailer.as_ref(), &profile, &mails).await;
+    }
+    debug!("Sent {} notifications for {} to {}", sent, since, now);
+    Ok(sent)
+}
+
+async fn mails_for(
+    state: &AppState,
+    profile: &user_profile::Model,
+    since: NaiveDateTime,
+    now: NaiveDateTime,
+) -> Result<Vec<Mail>, ServiceError> {
+    let to = match &profile.email {
+        Some(to) => to,
+        None => return Ok(Vec::new()),
+    };
+    let lang = letter_language(profile.preferred_language.as_deref());
+    let mut mails = Vec::new();
+
+    if profile.notify_upcoming_debits {
+        let (from, until) = upcoming_window(since, now);
+        let debits: Vec<DebitLine> = forecast::load_forecast(state, profile, from, 1)
+            .await?
+            .debits
+            .iter()
+            .filter(|d| d.due_date <= until)
+            .map(|d| DebitLine {
+                date: format_date(d.due_date, lang),
+                name: d.display_name.clone(),
+                creditor: Some(d.creditor_name.clone()),
+                amount: format_amount(d.amount, "EUR", lang),
+                iban: Some(d.iban.clone()),
+                until: None,
+                canceled: false,
+            })
+            .collect();
+        if !debits.is_empty() {
+            mails.push(render(
+                to,
+                lang,
+                "upcoming_debits",
+                profile,
+                "debits",
+                &debits,
+            )?);
+        }
+    }
+
+    if profile.notify_debit_alerts {
+        let imported: HashSet<uuid::Uuid> = BankTransactionEntity::find()
+            .filter(bank_transaction::Column::UserProfileId.eq(profile.id))
+            .filter(bank_transaction::Column::DateImported.gte(since))
+            .filter(bank_transaction::Column::DateImported.lt(now))
+            .all(&state.connection)
+            .await
+            .map_err(db_error)?
+            .iter()
+            .map(|t| t.api_id)
+            .collect();
+        if !imported.is_empty() {
+            let alerts: Vec<DebitLine> = deadline::load_deadlines(state, profile, now.date())
+                .await?
+                .iter()
+                .filter(|d| d.alert.is_some() && imported.contains(&d.transaction.api_id))
+                .map(|d| DebitLine {
+                    date: format_date(d.debit_date, lang),
+                    name: d.display_name.clone(),
+                    creditor: d.transaction.creditor_name.clone(),
+                    amount: format_amount(d.transaction.amount, &d.transaction.currency, lang),
+                    iban: d.transaction.debtor_iban.clone(),
+                    until: Some(format_date(d.unauthorised_until, lang)),
+                    canceled: d.alert == Some(DebitAlert::CollectedAfterCancellation),
+                })
+                .collect();
+            if !alerts.is_empty() {
+                mails.push(render(
+                    to,
+                    lang,
+                    "debit_alerts",
+                    profile,
+                    "debits",
+                    &alerts,
+                )?);
+            }
+        }
+    }
+
+    if profile.notify_profile_reminders && is_reminder_due(profile, since, now) {
+        mails.push(render::<DebitLine>(
+            to,
+            lang,
+            "profile_reminder",
+            profile,
+            "debits",
+            &[],
+        )?);
+    }
+    Ok(mails)
+}
+
+/// Due dates of the debits announced by the run from `since` to `now`. Due dates are business
+/// days, each one falls into exactly one run.
+fn upcoming_window(since: NaiveDateTime, now: NaiveDateTime) -> (NaiveDate, NaiveDate) {
+    (
+        since.date() + Duration::days(UPCOMING_DAYS + 1),
+        now.date() + Duration::days(UPCOMING_DAYS),
+    )
+}
+
+/// Whether the run from `since` to `now` reminds the incomplete profile.
+fn is_reminder_due(
+    profile: &user_profile::Model,
+    since: NaiveDateTime,
+    now: NaiveDateTime,
+) -> bool {
+    let due = profile.date_created + Duration::days(PROFILE_REMINDER_DAYS);
+    profile.status == ProfileStatus::ProfileIncomplete && since <= due && due < now
+}
+
+/// Sends the mails of the profile, a failed one is logged. Returns the number of sent mails.
+pub async fn send_all(
+    mailer: &dyn MailTransport,
+    profile: &user_profile::Model,
+    mails: &[Mail],
+) -> usize {
+    let mut sent = 0;
+    for mail in mails {
+        match mailer.send(mail).await {
+            Ok(()) => sent += 1,
+            Err(e) => error!(
+                "Sending \"{}\" to profile {} failed: {}",
+                mail.subject, profile.id, e
+            ),
+        }
+    }
+    sent
+}
+
+/// The mail about mandates that just expired, `None` when the profile has no address.
+pub fn expired_mandates(
+    profile: &user_profile::Model,
+    mandates: &[mandate::Model],
+) -> Result<Option<Mail>, ServiceError> {
+    let to = match &profile.email {
+        Some(to) => to,
+        None => return Ok(None),
+    };
+    let lang = letter_language(profile.preferred_language.as_deref());
+    let lines: Vec<MandateLine> = mandates
+        .iter()
+        .map(|m| MandateLine {
+            name: m.display_name.clone(),
+            creditor: m
+                .creditor
+                .get("name")
+                .and_then(|name| name.as_str())
+                .map(str::to_string),
+            reference: m.unique_reference.clone(),
+            last_used: format_date(m.last_used_on(), lang),
+            expired_on: format_date(usable_until(m.last_used_on()) + Duration::days(1), lang),
+        })
+        .collect();
+    render(to, lang, "expired_mandates", profile, "mandates", &lines).map(Some)
+}
+
+fn render<T: Serialize>(
+    to: &str,
+    lang: &str,
+    kind: &str,
+    profile: &user_profile::Model,
+    key: &str,
+    items: &[T],
+) -> Result<Mail, ServiceError> {
+    let mut context = Context::new();
+    context.insert("name", &profile.firstname);
+    context.insert(key, items);
+    let text = TEMPLATES
+        .render(&format!("{}/{}.txt", lang, kind), &context)
+        .map_err(|e| {
+            error!("Mail template {}/{} failed: {:?}", lang, kind, e);
+            ServiceError::InternalServerError
+        })?;
+    let text = text.trim();
+    let (subject, body) = text.split_once('\n').unwrap_or((text, ""));
+    let lines: Vec<&str> = body.trim().lines().map(str::trim_end).collect();
+    Ok(Mail {
+        to: to.to_string(),
+        subject: subject.trim().to_string(),
+        body: lines.join("\n") + "\n",
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use api_models::models::letter::LETTER_LANGUAGES;
+    use chrono::{Duration, NaiveDate, NaiveDateTime};
+    use entity::{
+        mandate::{self, MandatePaymentType, MandateScheme, MandateStatus},
+        user_profile::{Model, ProfileStatus},
+    };
+    use serde_json::json;
+
+    use super::{
+        expired_mandates, is_reminder_due, render, send_all, templates, upcoming_window, DebitLine,
+        MandateLine,
+    };
+    use crate::mail::Mail;
+    use crate::testing::MemoryTransport;
+
+    const KINDS: [&str; 4] = [
+        "upcoming_debits",
+        "debit_alerts",
+        "profile_reminder",
+        "expired_mandates",
+    ];
+
+    fn at(date: &str, hour: u32) -> NaiveDateTime {
+        date.parse::<NaiveDate>()
+            .unwrap()
+            .and_hms_opt(hour, 0, 0)
+            .unwrap()
+    }
+
+    fn profile(status: ProfileStatus) -> Model {
+        Model {
+            id: 1,
+            auth_id: "auth|1".to_string(),
+            date_created: at("2023-03-01", 12),
+            firstname: "Erika".to_string(),
+            lastname: "Mustermann".to_string(),
+            address: None,
+            preferred_language: Some("de".to_string()),
+            date_of_birth: None,
+            status,
+            email: Some("erika@example.com".to_string()),
+            notify_upcoming_debits: true,
+            notify_debit_alerts: true,
+            notify_profile_reminders: true,
+            notify_expired_mandates: true,
+        }
+    }
+
+    fn debit() -> DebitLine {
+        DebitLine {
+            date: "02.03.2023".to_string(),
+            name: "Gas".to_string(),
+            creditor: Some("Stadtwerke".to_string()),
+            amount: "42,50 EUR".to_string(),
+            iban: Some("DE89370400440532013000".to_string()),
+            until: Some("02.05.2023".to_string()),
+            canceled: true,
+        }
+    }
+
+    #[test]
+    fn test_templates_compile() {
+        let tera = templates().unwrap();
+        for lang in LETTER_LANGUAGES {
+            for kind in KINDS {
+                let name = format!("{}/{}.txt", lang, kind);
+                assert!(tera.get_template_names().any(|n| n == name), "{}", name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_render_every_mail() {
+        let profile = profile(ProfileStatus::ProfileIncomplete);
+        let mandates = [MandateLine {
+            name: "Gas".to_string(),
+            creditor: Some("Stadtwerke".to_string()),
+            reference: Some("GAS-4711".to_string()),
+            last_used: "01.03.2020".to_string(),
+            expired_on: "02.03.2023".to_string(),
+        }];
+        for lang in LETTER_LANGUAGES {
+            for kind in KINDS {
+                let mail = match kind {
+                    "expired_mandates" => {
+                        render("a@b.c", lang, kind, &profile, "mandates", &mandates)
+                    }
+                    _ => render("a@b.c", lang, kind, &profile, "debits", &[debit()]),
+                }
+                .unwrap();
+                assert!(!mail.subject.is_empty() && !mail.subject.contains('\n'));
+                assert!(mail.body.contains("Erika"), "{}", mail.body);
+                assert!(mail.body.ends_with("SepaMa\n"), "{}", mail.body);
+            }
+        }
+    }
+
+    #[test]
+    fn test_render_splits_subject_and_body() {
+        let mail = render(
+            "erika@example.com",
+            "en",
+            "upcoming_debits",
+            &profile(ProfileStatus::ProfileComplete),
+            "debits",
+            &[debit()],
+        )
+        .unwrap();
+        assert_eq!("erika@example.com", mail.to);
+        assert_eq!("Upcoming direct debits", mail.subject);
+        assert!(mail.body.starts_with("Hello Erika,\n\n"), "{}", mail.body);
+        assert!(mail
+            .body
+            .contains("02.03.2023  42,50 EUR  Gas (Stadtwerke), account DE89370400440532013000\n"));
+        assert!(!mail.body.lines().any(|line| line.ends_with(' ')));
+    }
+
+    #[test]
+    fn test_upcoming_window_covers_every_day_once() {
+        // daily runs, the one of the 4th missed
+        let runs = [
+            at("2023-03-01", 6),
+            at("2023-03-02", 6),
+            at("2023-03-03", 6),
+            at("2023-03-05", 6),
+            at("2023-03-06", 7),
+        ];
+        let windows: Vec<(NaiveDate, NaiveDate)> = runs
+            .windows(2)
+            .map(|run| upcoming_window(run[0], run[1]))
+            .collect();
+        assert_eq!(
+            ("2023-03-05".parse().unwrap(), "2023-03-05".parse().unwrap()),
+            windows[0]
+        );
+        assert_eq!("2023-03-08".parse::<NaiveDate>().unwrap(), windows[2].1);
+        for pair in windows.windows(2) {
+            assert_eq!(pair[0].1 + Duration::days(1), pair[1].0);
+        }
+    }
+
+    #[test]
+    fn test_profile_reminder_is_due_once() {
+        let incomplete = profile(ProfileStatus::ProfileIncomplete);
+        let runs: Vec<NaiveDateTime> = (0..20 * 24)
+            .map(|hour| incomplete.date_created + Duration::hours(hour))
+            .collect();
+        let due = |profile: &Model| {
+            runs.windows(2)
+                .filter(|run| is_reminder_due(profile, run[0], run[1]))
+                .count()
+        };
+        assert_eq!(1, due(&incomplete));
+        assert_eq!(0, due(&profile(ProfileStatus::ProfileComplete)));
+    }
+
+    #[actix_web::test]
+    async fn test_send_all_counts_the_sent_mails() {
+        let transport = MemoryTransport {
+            failing: Some("full@example.com".to_string()),
+            ..Default::default()
+        };
+        let mail = |to: &str| Mail {
+            to: to.to_string(),
+            subject: "Subject".to_string(),
+            body: "Body\n".to_string(),
+        };
+        let mails = [mail("full@example.com"), mail("erika@example.com")];
+        let profile = profile(ProfileStatus::ProfileComplete);
+        assert_eq!(1, send_all(&transport, &profile, &mails).await);
+        assert_eq!(
+            vec![mail("erika@example.com")],
+            *transport.sent.lock().unwrap()
+        );
+    }
+
+    #[actix_web::test]
+    async fn test_expired_mandates() {
+        let mandate = mandate::Model {
+            id: 1,
+            api_id: uuid::Uuid::new_v4(),
+            user_profile_id: 1,
+            tags: json!([]),
+            status: MandateStatus::EXPIRED,
+            unique_reference: Some("GAS-4711".to_string()),
+            display_name: "Gas".to_string(),
+            date_created: at("2019-01-01", 0),
+            creditor: json!({"name": "Stadtwerke"}),
+            bank_account_id: 1,
+            scheme: MandateScheme::CORE,
+            payment_type: MandatePaymentType::Recurrent,
+            signature_date: None,
+            signature_place: None,
+            account_holder: None,
+            expected_amount: None,
+            frequency: None,
+            interval_months: None,
+            anchor_date: None,
+            last_collection_date: "2020-03-01".parse().ok(),
+        };
+        let mut profile = profile(ProfileStatus::ProfileComplete);
+        let mail = expired_mandates(&profile, std::slice::from_ref(&mandate))
+            .unwrap()
+            .unwrap();
+        assert!(mail.body.contains("Gas"), "{}", mail.body);
+        assert!(mail.body.contains("GAS-4711"), "{}", mail.body);
+        assert!(mail.body.contains("01.03.2020"), "{}", mail.body);
+        assert!(mail.body.contains("02.03.2023"), "{}", mail.body);
+
+        let transport = MemoryTransport::default();
+        assert_eq!(1, send_all(&transport, &profile, &[mail]).await);
+        assert_eq!("erika@example.com", transport.sent.lock().unwrap()[0].to);
+
+        profile.email = None;
+        assert_eq!(None, expired_mandates(&profile, &[mandate]).unwrap());
+    }
+}
@@ -0,0 +1,569 @@
+//! ISO 20022 pain.008 (customer direct debit initiation) XML builder.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+use api_models::models::{Address, BankAccount, Creditor, Pain008Version, Scheme, SequenceType};
+use api_models::validator::{ValidationError, ValidationErrors};
+use chrono::{NaiveDate, NaiveDateTime};
+use rust_decimal::Decimal;
+
+/// A single direct debit transaction, already checked against its mandate.
+#[derive(Clone, Debug)]
+pub struct DirectDebit {
+    pub end_to_end_id: String,
+    pub amount: Decimal,
+    pub collection_date: NaiveDate,
+    pub scheme: Scheme,
+    pub sequence_type: SequenceType,
+    pub mandate_reference: String,
+    pub signature_date: NaiveDate,
+    pub creditor: Creditor,
+    pub creditor_identifier: String,
+    pub debtor_name: String,
+    pub debtor_account: BankAccount,
+    pub remittance_information: Option<String>,
+}
+
+pub struct Pain008Builder {
+    version: Pain008Version,
+    message_id: String,
+    initiating_party: String,
+    creditor_account: BankAccount,
+    debits: Vec<DirectDebit>,
+}
+
+impl Pain008Builder {
+    /// Fails when the message id is no valid SEPA identifier.
+    pub fn new(
+        version: Pain008Version,
+        message_id: &str,
+        initiating_party: &str,
+        creditor_account: BankAccount,
+    ) -> Result<Pain008Builder, ValidationErrors> {
+        check_ids(&[("message_id", message_id)])?;
+        Ok(Pain008Builder {
+            version,
+            message_id: message_id.to_string(),
+            initiating_party: initiating_party.to_string(),
+            creditor_account,
+            debits: Vec::new(),
+        })
+    }
+
+    /// Fails when the end to end id or the mandate reference is no valid SEPA identifier, they
+    /// are passed on unchanged as the bank matches them against the signed mandate.
+    pub fn add(&mut self, debit: DirectDebit) -> Result<&mut Self, ValidationErrors> {
+        check_ids(&[
+            ("end_to_end_id", &debit.end_to_end_id),
+            ("mandate_reference", &debit.mandate_reference),
+        ])?;
+        self.debits.push(debit);
+        Ok(self)
+    }
+
+    /// Writes the document, one payment information block per creditor, scheme, sequence type
+    /// and due date.
+    pub fn build(&self, created: NaiveDateTime) -> String {
+        let mut blocks: BTreeMap<(&str, Scheme, SequenceType, NaiveDate), Vec<&DirectDebit>> =
+            BTreeMap::new();
+        for debit in &self.debits {
+            blocks
+                .entry((
+                    debit.creditor_identifier.as_str(),
+                    debit.scheme,
+                    debit.sequence_type,
+                    debit.collection_date,
+                ))
+                .or_default()
+                .push(debit);
+        }
+
+        let message = self.version.message_name();
+        let mut xml = XmlWriter::new();
+        xml.start_with_attrs(
+            "Document",
+            &[
+                ("xmlns", &format!("urn:iso:std:iso:20022:tech:xsd:{}", message)),
+                ("xmlns:xsi", "http://www.w3.org/2001/XMLSchema-instance"),
+            ],
+        );
+        xml.start("CstmrDrctDbtInitn");
+
+        xml.start("GrpHdr");
+        xml.element("MsgId", &self.message_id);
+        xml.element("CreDtTm", &created.format("%Y-%m-%dT%H:%M:%S").to_string());
+        xml.element("NbOfTxs", &self.debits.len().to_string());
+        xml.element("CtrlSum", &amount(self.debits.iter().map(|d| d.amount).sum()));
+        xml.start("InitgPty");
+        xml.element("Nm", &sepa_text(&self.initiating_party, 70));
+        xml.end();
+        xml.end();
+
+        for (index, ((creditor_identifier, scheme, sequence_type, collection_date), debits)) in
+            blocks.iter().enumerate()
+        {
+            let creditor = &debits[0].creditor;
+            let scheme: &str = (*scheme).into();
+            let sequence_type: &str = (*sequence_type).into();
+            xml.start("PmtInf");
+            let suffix = format!("-{}", index + 1);
+            let prefix_length = self.message_id.len().min(ID_LENGTH - suffix.len());
+            xml.element(
+                "PmtInfId",
+                &format!("{}{}", &self.message_id[..prefix_length], suffix),
+            );
+            xml.element("PmtMtd", "DD");
+            xml.element("BtchBookg", "true");
+            xml.element("NbOfTxs", &debits.len().to_string());
+            xml.element("CtrlSum", &amount(debits.iter().map(|d| d.amount).sum()));
+            xml.start("PmtTpInf");
+            xml.start("SvcLvl");
+            xml.element("Cd", "SEPA");
+            xml.end();
+            xml.start("LclInstrm");
+            xml.element("Cd", scheme);
+            xml.end();
+            xml.element("SeqTp", sequence_type);
+            xml.end();
+            xml.element("ReqdColltnDt", &collection_date.to_string());
+            xml.start("Cdtr");
+            xml.element("Nm", &sepa_text(&creditor.name, 70));
+            self.write_address(&mut xml, &creditor.address, &creditor_identifier[0..2]);
+            xml.end();
+            self.write_account(&mut xml, "CdtrAcct", &self.creditor_account);
+            self.write_agent(&mut xml, "CdtrAgt", &self.creditor_account);
+            xml.element("ChrgBr", "SLEV");
+            xml.start("CdtrSchmeId");
+            xml.start("Id");
+            xml.start("PrvtId");
+            xml.start("Othr");
+            xml.element("Id", creditor_identifier);
+            xml.start("SchmeNm");
+            xml.element("Prtry", "SEPA");
+            xml.end();
+            xml.end();
+            xml.end();
+            xml.end();
+            xml.end();
+            for debit in debits {
+                self.write_transaction(&mut xml, debit);
+            }
+            xml.end();
+        }
+
+        xml.end();
+        xml.end();
+        xml.finish()
+    }
+
+    fn write_transaction(&self, xml: &mut XmlWriter, debit: &DirectDebit) {
+        xml.start("DrctDbtTxInf");
+        xml.start("PmtId");
+        xml.element("EndToEndId", &debit.end_to_end_id);
+        xml.end();
+        xml.element_with_attrs("InstdAmt", &[("Ccy", "EUR")], &amount(debit.amount));
+        xml.start("DrctDbtTx");
+        xml.start("MndtRltdInf");
+        xml.element("MndtId", &debit.mandate_reference);
+        xml.element("DtOfSgntr", &debit.signature_date.to_string());
+        xml.end();
+        xml.end();
+        self.write_agent(xml, "DbtrAgt", &debit.debtor_account);
+        xml.start("Dbtr");
+        xml.element("Nm", &sepa_text(&debit.debtor_name, 70));
+        xml.end();
+        self.write_account(xml, "DbtrAcct", &debit.debtor_account);
+        if let Some(info) = &debit.remittance_information {
+            xml.start("RmtInf");
+            xml.element("Ustrd", &sepa_text(info, 140));
+            xml.end();
+        }
+        xml.end();
+    }
+
+    fn write_account(&self, xml: &mut XmlWriter, tag: &str, account: &BankAccount) {
+        xml.start(tag);
+        xml.start("Id");
+        xml.element("IBAN", &account.iban);
+        xml.end();
+        xml.end();
+    }
+
+    fn write_agent(&self, xml: &mut XmlWriter, tag: &str, account: &BankAccount) {
+        xml.start(tag);
+        xml.start("FinInstnId");
+        match &account.bic {
+            Some(bic) => {
+                let bic_tag = match self.version {
+                    Pain008Version::V02 => "BIC",
+                    Pain008Version::V08 => "BICFI",
+                };
+                xml.element(bic_tag, bic);
+            }
+            None => {
+                xml.start("Othr");
+                xml.element("Id", "NOTPROVIDED");
+                xml.end();
+            }
+        }
+        xml.end();
+        xml.end();
+    }
+
+    fn write_address(&self, xml: &mut XmlWriter, address: &Address, country: &str) {
+        xml.start("PstlAdr");
+        match self.version {
+            Pain008Version::V02 => {
+                xml.element("Ctry", country);
+                xml.element(
+                    "AdrLine",
+                    &sepa_text(&format!("{} {}", address.street, address.house_number), 70),
+                );
+                xml.element(
+                    "AdrLine",
+                    &sepa_text(&format!("{} {}", address.zip, address.place), 70),
+                );
+            }
+            Pain008Version::V08 => {
+                xml.element("StrtNm", &sepa_text(&address.street, 70));
+                xml.element("BldgNb", &sepa_text(&address.house_number, 16));
+                xml.element("PstCd", &sepa_text(&address.zip, 16));
+                xml.element("TwnNm", &sepa_text(&address.place, 35));
+                xml.element("Ctry", country);
+            }
+        }
+        xml.end();
+    }
+}
+
+fn amount(value: Decimal) -> String {
+    format!("{:.2}", value.round_dp(2))
+}
+
+/// Restricts free text to the SEPA character set, transliterating common non-latin characters.
+fn sepa_text(value: &str, max_length: usize) -> String {
+    let mut result = String::with_capacity(value.len());
+    for c in value.trim().chars() {
+        match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '/' | '-' | '?' | ':' | '(' | ')' | '.' | ','
+            | '\'' | '+' | ' ' => result.push(c),
+            'ä' => result.push_str("ae"),
+            'ö' => result.push_str("oe"),
+            'ü' => result.push_str("ue"),
+            'Ä' => result.push_str("Ae"),
+            'Ö' => result.push_str("Oe"),
+            'Ü' => result.push_str("Ue"),
+            'ß' => result.push_str("ss"),
+            '&' => result.push('+'),
+            'à' | 'á' | 'â' | 'ã' | 'å' => result.push('a'),
+            'è' | 'é' | 'ê' | 'ë' => result.push('e'),
+            'ì' | 'í' | 'î' | 'ï' => result.push('i'),
+            'ò' | 'ó' | 'ô' | 'õ' => result.push('o'),
+            'ù' | 'ú' | 'û' => result.push('u'),
+            'ç' => result.push('c'),
+            'ñ' => result.push('n'),
+            _ => result.push(' '),
+        }
+    }
+    result.chars().take(max_length).collect::<String>().trim().to_string()
+}
+
+/// Maximum length of identifiers like the mandate reference.
+const ID_LENGTH: usize = 35;
+
+/// Identifiers use the restricted SEPA character set without spaces, have at most 35 characters
+/// and must not contain `//` nor start or end with a slash.
+pub fn validate_sepa_id(value: &str) -> Result<(), ValidationError> {
+    let message = if value.is_empty() {
+        Some("must not be empty".to_string())
+    } else if value.chars().count() > ID_LENGTH {
+        Some(format!("must have at most {} characters", ID_LENGTH))
+    } else if let Some(c) = value.chars().find(|c| {
+        !matches!(c, 'a'..='z' | 'A'..='Z' | '0'..='9' | '/' | '-' | '?' | ':' | '(' | ')' | '.'
+            | ',' | '\'' | '+')
+    }) {
+        Some(format!("must not contain {:?}", c))
+    } else if value.starts_with('/') || value.ends_with('/') || value.contains("//") {
+        Some("must not start or end with a slash nor contain //".to_string())
+    } else {
+        None
+    };
+    match message {
+        Some(message) => {
+            let mut error = ValidationError::new("sepa_id");
+            error.message = Some(Cow::from(format!("{} {}", value, message)));
+            Err(error)
+        }
+        None => Ok(()),
+    }
+}
+
+fn check_ids(ids: &[(&'static str, &str)]) -> Result<(), ValidationErrors> {
+    let mut errors = ValidationErrors::new();
+    for (field, value) in ids {
+        if let Err(error) = validate_sepa_id(value) {
+            errors.add(field, error);
+        }
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+struct XmlWriter {
+    out: String,
+    open: Vec<String>,
+}
+
+impl XmlWriter {
+    fn new() -> XmlWriter {
+        XmlWriter {
+            out: String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n"),
+            open: Vec::new(),
+        }
+    }
+
+    fn indent(&mut self) {
+        for _ in 0..self.open.len() {
+            self.out.push_str("  ");
+        }
+    }
+
+    fn start(&mut self, tag: &str) {
+        self.start_with_attrs(tag, &[]);
+    }
+
+    fn start_with_attrs(&mut self, tag: &str, attrs: &[(&str, &str)]) {
+        self.indent();
+        self.out.push('<');
+        self.out.push_str(tag);
+        self.push_attrs(attrs);
+        self.out.push_str(">\n");
+        self.open.push(tag.to_string());
+    }
+
+    fn end(&mut self) {
+        if let Some(tag) = self.open.pop() {
+            self.indent();
+            self.out.push_str(&format!("</{}>\n", tag));
+        }
+    }
+
+    fn element(&mut self, tag: &str, text: &str) {
+        self.element_with_attrs(tag, &[], text);
+    }
+
+    fn element_with_attrs(&mut self, tag: &str, attrs: &[(&str, &str)], text: &str) {
+        self.indent();
+        self.out.push('<');
+        self.out.push_str(tag);
+        self.push_attrs(attrs);
+        self.out.push('>');
+        self.out.push_str(&escape(text));
+        self.out.push_str(&format!("</{}>\n", tag));
+    }
+
+    fn push_attrs(&mut self, attrs: &[(&str, &str)]) {
+        for (name, value) in attrs {
+            self.out.push_str(&format!(" {}=\"{}\"", name, escape(value)));
+        }
+    }
+
+    fn finish(mut self) -> String {
+        while !self.open.is_empty() {
+            self.end();
+        }
+        self.out
+    }
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use api_models::models::{Address, BankAccount, Creditor, Pain008Version, Scheme, SequenceType};
+    use chrono::NaiveDate;
+    use rust_decimal::Decimal;
+
+    use super::{validate_sepa_id, DirectDebit, Pain008Builder};
+
+    fn account(iban: &str) -> BankAccount {
+        BankAccount {
+            institution: "Bank".to_string(),
+            iban: iban.to_string(),
+            bic: Some("COBADEFFXXX".to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn debit(
+        creditor: &str,
+        creditor_identifier: &str,
+        sequence_type: SequenceType,
+        amount: &str,
+        end_to_end_id: &str,
+    ) -> DirectDebit {
+        DirectDebit {
+            end_to_end_id: end_to_end_id.to_string(),
+            amount: Decimal::from_str(amount).unwrap(),
+            collection_date: NaiveDate::from_ymd_opt(2023, 3, 1).unwrap(),
+            scheme: Scheme::CORE,
+            sequence_type,
+            mandate_reference: format!("REF-{}", end_to_end_id),
+            signature_date: NaiveDate::from_ymd_opt(2022, 1, 15).unwrap(),
+            creditor: Creditor {
+                name: creditor.to_string(),
+                sepa_identifier: Some(creditor_identifier.to_string()),
+                address: Address {
+                    street: "Hauptstraße".to_string(),
+                    house_number: "1".to_string(),
+                    zip: "10115".to_string(),
+                    place: "Berlin".to_string(),
+                },
+            },
+            creditor_identifier: creditor_identifier.to_string(),
+            debtor_name: "O'Brien".to_string(),
+            debtor_account: account("DE89370400440532013000"),
+            remittance_information: None,
+        }
+    }
+
+    fn build() -> String {
+        let mut builder = Pain008Builder::new(
+            Pain008Version::V02,
+            "MSG-1",
+            "Jane Doe",
+            account("DE02120300000000202051"),
+        )
+        .unwrap();
+        for debit in [
+            debit("Stadtwerke", "DE98ZZZ09999999999", SequenceType::RCUR, "20.50", "E2E-1"),
+            debit("Stadtwerke", "DE98ZZZ09999999999", SequenceType::FRST, "10", "E2E-2"),
+            debit("Stadtwerke", "DE98ZZZ09999999999", SequenceType::RCUR, "4.50", "E2E-3"),
+            debit(
+                "Müller & Söhne <GmbH>",
+                "AT61ZZZ01234567890",
+                SequenceType::RCUR,
+                "7",
+                "E2E-4",
+            ),
+        ] {
+            builder.add(debit).unwrap();
+        }
+        let created = NaiveDate::from_ymd_opt(2023, 2, 20)
+            .unwrap()
+            .and_hms_opt(10, 30, 0)
+            .unwrap();
+        builder.build(created)
+    }
+
+    #[test]
+    fn test_group_header_totals() {
+        let xml = build();
+        let header = &xml[xml.find("<GrpHdr>").unwrap()..xml.find("</GrpHdr>").unwrap()];
+        assert!(header.contains("<MsgId>MSG-1</MsgId>"));
+        assert!(header.contains("<CreDtTm>2023-02-20T10:30:00</CreDtTm>"));
+        assert!(header.contains("<NbOfTxs>4</NbOfTxs>"));
+        assert!(header.contains("<CtrlSum>42.00</CtrlSum>"));
+    }
+
+    #[test]
+    fn test_payment_information_per_creditor_and_sequence_type() {
+        let xml = build();
+        let blocks: Vec<&str> = xml.split("<PmtInf>").skip(1).collect();
+        assert_eq!(3, blocks.len());
+
+        assert!(blocks[0].contains("<Id>AT61ZZZ01234567890</Id>"));
+        assert!(blocks[0].contains("<SeqTp>RCUR</SeqTp>"));
+        assert!(blocks[0].contains("<NbOfTxs>1</NbOfTxs>"));
+        assert!(blocks[0].contains("<CtrlSum>7.00</CtrlSum>"));
+        assert!(blocks[0].contains("<Ctry>AT</Ctry>"));
+
+        assert!(blocks[1].contains("<Id>DE98ZZZ09999999999</Id>"));
+        assert!(blocks[1].contains("<SeqTp>FRST</SeqTp>"));
+        assert!(blocks[1].contains("<NbOfTxs>1</NbOfTxs>"));
+        assert!(blocks[1].contains("<EndToEndId>E2E-2</EndToEndId>"));
+
+        assert!(blocks[2].contains("<SeqTp>RCUR</SeqTp>"));
+        assert!(blocks[2].contains("<NbOfTxs>2</NbOfTxs>"));
+        assert!(blocks[2].contains("<CtrlSum>25.00</CtrlSum>"));
+        assert!(blocks[2].contains("<EndToEndId>E2E-1</EndToEndId>"));
+        assert!(blocks[2].contains("<EndToEndId>E2E-3</EndToEndId>"));
+        assert!(blocks[2].contains("<InstdAmt Ccy=\"EUR\">4.50</InstdAmt>"));
+    }
+
+    #[test]
+    fn test_names_are_transliterated_and_escaped() {
+        let xml = build();
+        assert!(xml.contains("<Nm>Mueller + Soehne  GmbH</Nm>"));
+        assert!(xml.contains("<Nm>O&apos;Brien</Nm>"));
+        assert!(xml.contains("<AdrLine>Hauptstrasse 1</AdrLine>"));
+        assert!(!xml.contains("<GmbH>"));
+    }
+
+    #[test]
+    fn test_identifiers_are_validated_not_changed() {
+        for valid in ["GAS-4711", "A/B:C(1).2,3'4+5?", "12345678901234567890123456789012345"] {
+            assert_eq!(Ok(()), validate_sepa_id(valid).map_err(|e| e.message), "{}", valid);
+        }
+        for invalid in [
+            "",
+            "GAS 4711",
+            "Müller-1",
+            "/GAS",
+            "GAS/",
+            "GAS//1",
+            "123456789012345678901234567890123456",
+        ] {
+            assert!(validate_sepa_id(invalid).is_err(), "{}", invalid);
+        }
+
+        let mut builder = Pain008Builder::new(
+            Pain008Version::V08,
+            "MSG-1",
+            "Jane Doe",
+            account("DE02120300000000202051"),
+        )
+        .unwrap();
+        let mut invalid =
+            debit("Stadtwerke", "DE98ZZZ09999999999", SequenceType::RCUR, "1", "E2E 1");
+        invalid.mandate_reference = "GAS 4711".to_string();
+        let errors = match builder.add(invalid) {
+            Err(errors) => errors,
+            Ok(_) => panic!("invalid identifiers are accepted"),
+        };
+        assert!(errors.errors().contains_key("end_to_end_id"));
+        assert!(errors.errors().contains_key("mandate_reference"));
+        assert!(Pain008Builder::new(
+            Pain008Version::V08,
+            "MSG 1",
+            "Jane Doe",
+            account("DE02120300000000202051"),
+        )
+        .is_err());
+
+        builder
+            .add(debit("Stadtwerke", "DE98ZZZ09999999999", SequenceType::RCUR, "1", "E2E-1"))
+            .unwrap();
+        let xml = builder.build(
+            NaiveDate::from_ymd_opt(2023, 2, 20)
+                .unwrap()
+                .and_hms_opt(10, 30, 0)
+                .unwrap(),
+        );
+        assert!(xml.contains("<MndtId>REF-E2E-1</MndtId>"));
+        assert!(xml.contains("<PmtInfId>MSG-1-1</PmtInfId>"));
+    }
+}
@@ -0,0 +1,127 @@
+//! Helpers shared by the generated PDF documents (letters and mandate forms).
+
+/// A4 in millimetres.
+pub const PAGE_WIDTH: f32 = 210.0;
+pub const PAGE_HEIGHT: f32 = 297.0;
+
+/// Splits a line into lines of at most `width` characters, a longer word gets a line of its own.
+pub fn wrap(line: &str, width: usize) -> Vec<String> {
+    let mut lines = vec![String::new()];
+    for word in line.split_whitespace() {
+        let current = lines.last_mut().expect("lines is never empty");
+        if current.is_empty() {
+            current.push_str(word);
+        } else if current.chars().count() + 1 + word.chars().count() <= width {
+            current.push(' ');
+            current.push_str(word);
+        } else {
+            lines.push(word.to_string());
+        }
+    }
+    lines
+}
+
+/// The built-in PDF fonts only cover Windows-1252, other letters lose their accent (`ć` becomes `c`).
+pub fn to_win_ansi(text: &str) -> String {
+    const FOLDS: [(&str, char); 38] = [
+        ("ĀĂĄ", 'A'),
+        ("āăą", 'a'),
+        ("ĆĈĊČ", 'C'),
+        ("ćĉċč", 'c'),
+        ("ĎĐ", 'D'),
+        ("ďđ", 'd'),
+        ("ĒĔĖĘĚ", 'E'),
+        ("ēĕėęě", 'e'),
+        ("ĜĞĠĢ", 'G'),
+        ("ĝğġģ", 'g'),
+        ("ĤĦ", 'H'),
+        ("ĥħ", 'h'),
+        ("ĨĪĬĮİ", 'I'),
+        ("ĩīĭįı", 'i'),
+        ("Ĵ", 'J'),
+        ("ĵ", 'j'),
+        ("Ķ", 'K'),
+        ("ķ", 'k'),
+        ("ĹĻĽĿŁ", 'L'),
+        ("ĺļľŀł", 'l'),
+        ("ŃŅŇ", 'N'),
+        ("ńņň", 'n'),
+        ("ŌŎŐ", 'O'),
+        ("ōŏő", 'o'),
+        ("ŔŖŘ", 'R'),
+        ("ŕŗř", 'r'),
+        ("ŚŜŞ", 'S'),
+        ("śŝş", 's'),
+        ("ŢŤŦ", 'T'),
+        ("ţťŧ", 't'),
+        ("ŨŪŬŮŰŲ", 'U'),
+        ("ũūŭůűų", 'u'),
+        ("Ŵ", 'W'),
+        ("ŵ", 'w'),
+        ("Ŷ", 'Y'),
+        ("ŷ", 'y'),
+        ("ŹŻ", 'Z'),
+        ("źż", 'z'),
+    ];
+    text.chars()
+        .map(|c| {
+            if matches!(c as u32, 0x20..=0x7E | 0xA0..=0xFF)
+                || "€‚ƒ„…†‡ˆ‰Š‹ŒŽ‘’“”•–—˜™š›œžŸ".contains(c)
+            {
+                c
+            } else {
+                FOLDS
+                    .iter()
+                    .find(|(letters, _)| letters.contains(c))
+                    .map_or('?', |(_, base)| *base)
+            }
+        })
+        .collect()
+}
+
+/// A value usable in a download file name, other characters become `_`.
+pub fn file_name_part(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{file_name_part, to_win_ansi, wrap};
+
+    #[test]
+    fn test_wrap() {
+        assert_eq!(vec![""], wrap("", 10));
+        assert_eq!(vec!["one two", "three"], wrap("one  two three", 7));
+        // a word longer than the line is not split
+        assert_eq!(
+            vec!["a", "IBAN:DE89370400440532013000", "b"],
+            wrap("a IBAN:DE89370400440532013000 b", 5)
+        );
+        // characters, not bytes
+        assert_eq!(vec!["äöü ßé"], wrap("äöü ßé", 6));
+    }
+
+    #[test]
+    fn test_to_win_ansi() {
+        assert_eq!("Zoë Müller – 5 € „Straße“", to_win_ansi("Zoë Müller – 5 € „Straße“"));
+        assert_eq!("Lukasiewicz, Dvorák, Lódz", to_win_ansi("Łukasiewicz, Dvořák, Łódź"));
+        assert_eq!("? ?", to_win_ansi("→ 日"));
+    }
+
+    #[test]
+    fn test_file_name_part() {
+        assert_eq!("GAS-4711", file_name_part("GAS-4711"));
+        assert_eq!("Gas___Strom_2023", file_name_part("Gas & Strom/2023"));
+        assert_eq!("Müller", file_name_part("Müller"));
+        assert_eq!("__", file_name_part(".."));
+    }
+}
@@ -0,0 +1,331 @@
+//! ISO 20022 camt.053 (account statement) and camt.054 (debit notification) parser.
+
+use chrono::NaiveDate;
+use quick_xml::{events::Event, Reader};
+use rust_decimal::Decimal;
+use std::str::FromStr;
+
+use super::{StatementDebit, StatementError, StatementParser};
+
+/// Values of one `Ntry` or `TxDtls` element keyed by their path relative to it.
+#[derive(Default)]
+struct Fields(Vec<(String, String)>);
+
+impl Fields {
+    fn get(&self, paths: &[&str]) -> Option<String> {
+        paths.iter().find_map(|path| {
+            self.0
+                .iter()
+                .find(|(p, _)| p == path)
+                .map(|(_, v)| v.clone())
+        })
+    }
+
+    fn set(&mut self, path: String, value: String) {
+        self.0.push((path, value));
+    }
+}
+
+#[derive(Default)]
+struct Entry {
+    /// IBAN of the statement or notification the entry belongs to.
+    account_iban: Option<String>,
+    fields: Fields,
+    transactions: Vec<Fields>,
+}
+
+pub struct CamtParser;
+
+impl StatementParser for CamtParser {
+    /// Returns `camt.053` or `camt.054` when the document is one of the supported camt messages.
+    fn detect(&self, content: &str) -> Option<&'static str> {
+        let head: String = content.chars().take(1024).collect();
+        if head.contains("camt.053") || head.contains("BkToCstmrStmt") {
+            Some("camt.053")
+        } else if head.contains("camt.054") || head.contains("BkToCstmrDbtCdtNtfctn") {
+            Some("camt.054")
+        } else {
+            None
+        }
+    }
+
+    fn parse(&self, content: &str) -> Result<Vec<StatementDebit>, StatementError> {
+        parse(content)
+    }
+}
+
+fn parse(content: &str) -> Result<Vec<StatementDebit>, StatementError> {
+    let mut reader = Reader::from_str(content);
+    reader.trim_text(true);
+    let mut buf = Vec::new();
+    let mut path: Vec<String> = Vec::new();
+    let mut account_iban: Option<String> = None;
+    let mut entries: Vec<Entry> = Vec::new();
+    let mut entry: Option<Entry> = None;
+    let mut transaction: Option<Fields> = None;
+
+    loop {
+        match reader.read_event(&mut buf) {
+            Ok(Event::Start(ref e)) => {
+                let name = String::from_utf8_lossy(e.local_name()).to_string();
+                match name.as_str() {
+                    // a file can hold the statements of several accounts
+                    "Stmt" | "Ntfctn" | "Rpt" => account_iban = None,
+                    "Ntry" => {
+                        entry = Some(Entry {
+                            account_iban: account_iban.clone(),
+                            ..Default::default()
+                        })
+                    }
+                    "TxDtls" if entry.is_some() => transaction = Some(Fields::default()),
+                    _ => {}
+                }
+                path.push(name);
+                if let Some(currency) = e
+                    .attributes()
+                    .flatten()
+                    .find(|a| a.key == b"Ccy")
+                    .and_then(|a| a.unescape_and_decode_value(&reader).ok())
+                {
+                    record(&path, &mut entry, &mut transaction, "@Ccy", currency);
+                }
+            }
+            Ok(Event::Text(e)) => {
+                let text = e
+                    .unescape_and_decode(&reader)
+                    .map_err(|e| StatementError(format!("Invalid camt document: {}", e)))?;
+                if entry.is_none() && path.ends_with(&["Acct", "Id", "IBAN"].map(String::from)) {
+                    account_iban = Some(text.clone());
+                }
+                record(&path, &mut entry, &mut transaction, "", text);
+            }
+            Ok(Event::End(_)) => match path.pop().as_deref() {
+                Some("TxDtls") => {
+                    if let (Some(e), Some(t)) = (entry.as_mut(), transaction.take()) {
+                        e.transactions.push(t);
+                    }
+                }
+                Some("Ntry") => entries.extend(entry.take()),
+                _ => {}
+            },
+            Ok(Event::Eof) => break,
+            Err(e) => {
+                return Err(StatementError(format!(
+                    "Invalid camt document at position {}: {}",
+                    reader.buffer_position(),
+                    e
+                )))
+            }
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    let mut debits = Vec::new();
+    for entry in entries {
+        if entry.transactions.is_empty() {
+            debits.extend(to_debit(
+                &entry.fields,
+                &Fields::default(),
+                &entry.account_iban,
+            )?);
+        }
+        for transaction in &entry.transactions {
+            debits.extend(to_debit(&entry.fields, transaction, &entry.account_iban)?);
+        }
+    }
+    Ok(debits)
+}
+
+/// Stores a value under its path relative to the innermost open `TxDtls` or `Ntry`.
+fn record(
+    path: &[String],
+    entry: &mut Option<Entry>,
+    transaction: &mut Option<Fields>,
+    suffix: &str,
+    value: String,
+) {
+    let relative = |root: &str| {
+        path.iter()
+            .rposition(|p| p == root)
+            .map(|i| format!("{}{}", path[i + 1..].join("/"), suffix))
+    };
+    if let Some(t) = transaction.as_mut() {
+        if let Some(key) = relative("TxDtls") {
+            t.set(key, value);
+        }
+    } else if let Some(e) = entry.as_mut() {
+        if let Some(key) = relative("Ntry") {
+            e.fields.set(key, value);
+        }
+    }
+}
+
+fn to_debit(
+    entry: &Fields,
+    transaction: &Fields,
+    account_iban: &Option<String>,
+) -> Result<Option<StatementDebit>, StatementError> {
+    let both = |paths: &[&str]| transaction.get(paths).or_else(|| entry.get(paths));
+
+    let mandate_reference = transaction.get(&["Refs/MndtId"]);
+    let creditor_identifier = transaction.get(&[
+        "RltdPties/Cdtr/Id/PrvtId/Othr/Id",
+        "RltdPties/Cdtr/Pty/Id/PrvtId/Othr/Id",
+        "RltdPties/UltmtCdtr/Id/PrvtId/Othr/Id",
+        "RltdPties/UltmtCdtr/Pty/Id/PrvtId/Othr/Id",
+    ]);
+    let family = entry.get(&["BkTxCd/Domn/Fmly/Cd"]);
+    let is_direct_debit = mandate_reference.is_some()
+        || creditor_identifier.is_some()
+        || matches!(family.as_deref(), Some("RDDT") | Some("IDDT"));
+    if !is_direct_debit {
+        return Ok(None);
+    }
+
+    let booking_date = entry
+        .get(&["BookgDt/Dt", "BookgDt/DtTm"])
+        .and_then(|d| parse_date(&d))
+        .ok_or_else(|| StatementError("Entry without booking date".to_string()))?;
+    let (amount, currency) = match transaction.get(&["AmtDtls/TxAmt/Amt", "Amt"]) {
+        Some(amount) => (
+            amount,
+            transaction.get(&["AmtDtls/TxAmt/Amt@Ccy", "Amt@Ccy"]),
+        ),
+        None => (
+            entry
+                .get(&["Amt"])
+                .ok_or_else(|| StatementError("Entry without amount".to_string()))?,
+            entry.get(&["Amt@Ccy"]),
+        ),
+    };
+    let amount = Decimal::from_str(&amount)
+        .map_err(|_| StatementError(format!("Invalid amount {}", amount)))?;
+    let credit_debit = both(&["CdtDbtInd"]);
+    let reversal = entry.get(&["RvslInd"]).as_deref() == Some("true");
+
+    Ok(Some(StatementDebit {
+        booking_date,
+        value_date: entry
+            .get(&["ValDt/Dt", "ValDt/DtTm"])
+            .and_then(|d| parse_date(&d)),
+        amount,
+        currency: currency.unwrap_or_else(|| "EUR".to_string()),
+        is_reversal: reversal || credit_debit.as_deref() == Some("CRDT"),
+        mandate_reference,
+        creditor_identifier,
+        creditor_name: transaction.get(&["RltdPties/Cdtr/Nm", "RltdPties/Cdtr/Pty/Nm"]),
+        debtor_iban: transaction
+            .get(&["RltdPties/DbtrAcct/Id/IBAN"])
+            .or_else(|| account_iban.clone()),
+        end_to_end_id: transaction
+            .get(&["Refs/EndToEndId"])
+            .filter(|id| id != "NOTPROVIDED"),
+        bank_reference: both(&["Refs/AcctSvcrRef", "AcctSvcrRef"]),
+        remittance_information: transaction
+            .get(&["RmtInf/Ustrd"])
+            .or_else(|| entry.get(&["AddtlNtryInf"])),
+    }))
+}
+
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    value
+        .get(0..10)
+        .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use chrono::NaiveDate;
+    use rust_decimal::Decimal;
+
+    use super::CamtParser;
+    use crate::statement::{StatementDebit, StatementParser};
+
+    fn parse(content: &str) -> Vec<StatementDebit> {
+        CamtParser.parse(content).unwrap()
+    }
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::from_str(value).unwrap()
+    }
+
+    #[test]
+    fn test_camt053_single_account() {
+        let content = include_str!("../../tests/fixtures/camt053_single.xml");
+        assert_eq!(Some("camt.053"), CamtParser.detect(content));
+        let debits = parse(content);
+        // the salary is no direct debit
+        assert_eq!(2, debits.len());
+        assert_eq!(
+            StatementDebit {
+                booking_date: date("2023-03-01"),
+                value_date: Some(date("2023-03-01")),
+                amount: Decimal::from_str("42.50").unwrap(),
+                currency: "EUR".to_string(),
+                is_reversal: false,
+                mandate_reference: Some("GAS-4711".to_string()),
+                creditor_identifier: None,
+                creditor_name: Some("Stadtwerke & Co".to_string()),
+                debtor_iban: Some("DE89370400440532013000".to_string()),
+                end_to_end_id: Some("E2E-GAS-03".to_string()),
+                bank_reference: Some("BANK-REF-1".to_string()),
+                remittance_information: Some("Abschlag Maerz".to_string()),
+            },
+            debits[0]
+        );
+        assert!(debits[1].is_reversal);
+        assert_eq!(debits[0].end_to_end_id, debits[1].end_to_end_id);
+    }
+
+    #[test]
+    fn test_camt053_several_accounts() {
+        let debits = parse(include_str!("../../tests/fixtures/camt053_multi.xml"));
+        let found: Vec<(Option<&str>, Option<&str>, Decimal)> = debits
+            .iter()
+            .map(|d| {
+                (
+                    d.mandate_reference.as_deref(),
+                    d.debtor_iban.as_deref(),
+                    d.amount,
+                )
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                (
+                    Some("GAS-4711"),
+                    Some("DE89370400440532013000"),
+                    Decimal::from_str("42.50").unwrap()
+                ),
+                (
+                    Some("PHONE-1"),
+                    Some("DE02120300000000202051"),
+                    Decimal::from_str("9.99").unwrap()
+                ),
+                // the account of the transaction wins over the one of the statement
+                (
+                    Some("PHONE-2"),
+                    Some("DE75512108001245126199"),
+                    Decimal::from_str("5.00").unwrap()
+                ),
+            ],
+            found
+        );
+    }
+
+    #[test]
+    fn test_camt054_notifications() {
+        let content = include_str!("../../tests/fixtures/camt054.xml");
+        assert_eq!(Some("camt.054"), CamtParser.detect(content));
+        let debits = parse(content);
+        assert_eq!(2, debits.len());
+        assert_eq!(date("2023-03-01"), debits[0].booking_date);
+        assert_eq!(None, debits[0].end_to_end_id);
+        assert_eq!(Some("DE89370400440532013000"), debits[0].debtor_iban.as_deref());
+        assert_eq!(Some("CLUB-7"), debits[1].mandate_reference.as_deref());
+        assert_eq!(Some("DE02120300000000202051"), debits[1].debtor_iban.as_deref());
+    }
+}
@@ -0,0 +1,139 @@
+//! Bank statement import: parsing of statement files and matching of the booked
+//! direct debits to the stored mandates.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use api_models::models::{Creditor, CreditorIdentifier, Iban};
+use chrono::NaiveDate;
+use entity::{bank_account, mandate};
+use rust_decimal::Decimal;
+
+pub mod camt;
+pub mod mt940;
+
+/// A direct debit (or its return) as found on a bank statement.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StatementDebit {
+    pub booking_date: NaiveDate,
+    pub value_date: Option<NaiveDate>,
+    pub amount: Decimal,
+    pub currency: String,
+    pub is_reversal: bool,
+    pub mandate_reference: Option<String>,
+    pub creditor_identifier: Option<String>,
+    pub creditor_name: Option<String>,
+    pub debtor_iban: Option<String>,
+    pub end_to_end_id: Option<String>,
+    pub bank_reference: Option<String>,
+    pub remittance_information: Option<String>,
+}
+
+impl StatementDebit {
+    /// Identifies a debit across repeated uploads of the same (or overlapping) statements.
+    pub fn fingerprint(&self) -> String {
+        [
+            self.booking_date.to_string(),
+            self.amount.to_string(),
+            self.is_reversal.to_string(),
+            self.bank_reference.clone().unwrap_or_default(),
+            self.end_to_end_id.clone().unwrap_or_default(),
+            self.mandate_reference.clone().unwrap_or_default(),
+            self.creditor_identifier.clone().unwrap_or_default(),
+        ]
+        .join("|")
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct StatementError(pub String);
+
+impl Display for StatementError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A bank statement format the import understands.
+pub trait StatementParser {
+    /// Name of the format (e.g. `camt.053`) when the content is in this format.
+    fn detect(&self, content: &str) -> Option<&'static str>;
+
+    /// Extracts the direct debits, other bookings of the statement are skipped.
+    fn parse(&self, content: &str) -> Result<Vec<StatementDebit>, StatementError>;
+}
+
+/// All supported statement formats, in the order they are tried.
+pub fn parsers() -> Vec<Box<dyn StatementParser>> {
+    vec![Box::new(camt::CamtParser), Box::new(mt940::Mt940Parser)]
+}
+
+/// Detects the format of the statement and parses it, returns the format name with the debits.
+pub fn parse(content: &str) -> Result<(&'static str, Vec<StatementDebit>), StatementError> {
+    for parser in parsers() {
+        if let Some(format) = parser.detect(content) {
+            return Ok((format, parser.parse(content)?));
+        }
+    }
+    Err(StatementError(
+        "Unsupported statement format, expected camt.053, camt.054 or MT940".to_string(),
+    ))
+}
+
+/// Finds the mandate a debit was collected under.
+/// The mandate reference together with a matching creditor identifier wins,
+/// without a reference the creditor identifier and the debtor IBAN must both match.
+pub fn match_mandate<'a>(
+    debit: &StatementDebit,
+    mandates: &'a [mandate::Model],
+    accounts: &HashMap<i32, bank_account::Model>,
+) -> Option<&'a mandate::Model> {
+    let reference = debit.mandate_reference.as_deref().map(normalize_reference);
+    let creditor_identifier = debit
+        .creditor_identifier
+        .as_deref()
+        .and_then(|ci| CreditorIdentifier::parse(ci).ok());
+    let debtor_iban = debit
+        .debtor_iban
+        .as_deref()
+        .and_then(|iban| Iban::parse(iban).ok());
+
+    mandates
+        .iter()
+        .filter_map(|m| {
+            let creditor: Creditor = serde_json::from_value(m.creditor.clone()).ok()?;
+            let same_creditor = match (&creditor_identifier, creditor.creditor_identifier()) {
+                (Some(ci), Some(mandate_ci)) => Some(ci.is_same_creditor(&mandate_ci)),
+                _ => None,
+            };
+            if same_creditor == Some(false) {
+                return None;
+            }
+            let same_reference = match (&reference, &m.unique_reference) {
+                (Some(r), Some(mandate_reference)) => {
+                    Some(*r == normalize_reference(mandate_reference))
+                }
+                _ => None,
+            };
+            let same_iban = match (&debtor_iban, accounts.get(&m.bank_account_id)) {
+                (Some(iban), Some(account)) => Iban::parse(&account.iban).ok().map(|i| i == *iban),
+                _ => None,
+            };
+            match (same_reference, same_creditor, same_iban) {
+                (Some(true), Some(true), _) => Some((3, m)),
+                (Some(true), None, _) => Some((2, m)),
+                (None, Some(true), Some(true)) => Some((1, m)),
+                _ => None,
+            }
+        })
+        .max_by_key(|(score, m)| (*score, m.id))
+        .map(|(_, m)| m)
+}
+
+fn normalize_reference(reference: &str) -> String {
+    reference
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_uppercase()
+}
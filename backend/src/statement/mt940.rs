@@ -0,0 +1,326 @@
+//! SWIFT MT940 statement parser, including the structured `:86:` purpose field
+//! used by German banks (`?20`-`?29` with the SEPA keywords `EREF+`, `MREF+`, `CRED+`, ...).
+
+use chrono::NaiveDate;
+use lazy_static::lazy_static;
+use regex::Regex;
+use rust_decimal::Decimal;
+use std::str::FromStr;
+
+use api_models::models::Iban;
+
+use super::{StatementDebit, StatementError, StatementParser};
+
+lazy_static! {
+    static ref RE_FIELD: Regex = Regex::new(r"^:(\d{2}[A-Z]?):(.*)$").unwrap();
+    static ref RE_STATEMENT_LINE: Regex = Regex::new(
+        r"^(\d{6})(\d{4})?(RC|RD|C|D)([A-Z])?(\d{1,12},\d{0,2})([A-Z][A-Z0-9]{3})([^/\n]*)(?://([^\n]*))?"
+    )
+    .unwrap();
+    static ref RE_SUBFIELD: Regex = Regex::new(r"\?(\d{2})").unwrap();
+}
+
+/// SEPA keywords of the purpose text, in the order banks usually write them.
+const KEYWORDS: &[&str] = &[
+    "EREF+", "KREF+", "MREF+", "CRED+", "DEBT+", "COAM+", "OAMT+", "SVWZ+", "ABWA+", "ABWE+",
+];
+
+/// Business transaction codes (GVC) of SEPA direct debits and their returns.
+const DIRECT_DEBIT_CODES: &[&str] = &["104", "105", "106", "107", "108", "109"];
+const RETURN_CODES: &[&str] = &["108", "109"];
+
+pub struct Mt940Parser;
+
+impl StatementParser for Mt940Parser {
+    fn detect(&self, content: &str) -> Option<&'static str> {
+        if content.contains(":20:")
+            && (content.contains(":60F:") || content.contains(":60M:"))
+            && content.contains(":61:")
+        {
+            Some("MT940")
+        } else {
+            None
+        }
+    }
+
+    fn parse(&self, content: &str) -> Result<Vec<StatementDebit>, StatementError> {
+        let mut debits = Vec::new();
+        let mut account_iban: Option<String> = None;
+        let mut currency = "EUR".to_string();
+        let mut statement_line: Option<String> = None;
+
+        for (tag, value) in fields(content) {
+            match tag.as_str() {
+                "25" => {
+                    let account = value.rsplit('/').next().unwrap_or_default();
+                    account_iban = Iban::parse(account).ok().map(|iban| iban.to_string());
+                }
+                "60F" | "60M" => {
+                    if let Some(c) = value.get(7..10) {
+                        currency = c.to_string();
+                    }
+                }
+                "61" => {
+                    if let Some(line) = statement_line.take() {
+                        debits.extend(to_debit(&line, "", &currency, &account_iban)?);
+                    }
+                    statement_line = Some(value);
+                }
+                "86" => {
+                    if let Some(line) = statement_line.take() {
+                        debits.extend(to_debit(&line, &value, &currency, &account_iban)?);
+                    }
+                }
+                _ => {}
+            }
+        }
+        if let Some(line) = statement_line.take() {
+            debits.extend(to_debit(&line, "", &currency, &account_iban)?);
+        }
+        Ok(debits)
+    }
+}
+
+/// Splits the statement into `(tag, value)` pairs, continuation lines are joined with a line break.
+fn fields(content: &str) -> Vec<(String, String)> {
+    let mut fields: Vec<(String, String)> = Vec::new();
+    for line in content.lines().map(|l| l.trim_end_matches('\r')) {
+        if line.starts_with('{') || line.starts_with("-}") || line == "-" {
+            continue;
+        }
+        if let Some(captures) = RE_FIELD.captures(line) {
+            fields.push((captures[1].to_string(), captures[2].to_string()));
+        } else if let Some((_, value)) = fields.last_mut() {
+            value.push('\n');
+            value.push_str(line);
+        }
+    }
+    fields
+}
+
+fn to_debit(
+    line: &str,
+    information: &str,
+    currency: &str,
+    account_iban: &Option<String>,
+) -> Result<Option<StatementDebit>, StatementError> {
+    let captures = RE_STATEMENT_LINE
+        .captures(line)
+        .ok_or_else(|| StatementError(format!("Invalid :61: statement line {}", line)))?;
+    let value_date = parse_date(&captures[1])
+        .ok_or_else(|| StatementError(format!("Invalid value date in {}", line)))?;
+    let booking_date = captures
+        .get(2)
+        .and_then(|entry_date| booking_date(value_date, entry_date.as_str()))
+        .unwrap_or(value_date);
+    let mark = &captures[3];
+    let amount = captures[5].replace(',', ".");
+    let amount = Decimal::from_str(amount.trim_end_matches('.'))
+        .map_err(|_| StatementError(format!("Invalid amount in {}", line)))?;
+    let transaction_type = &captures[6];
+    let customer_reference = captures[7].trim();
+    let bank_reference = captures.get(8).map(|r| r.as_str().trim().to_string());
+
+    let purpose = Purpose::parse(information);
+    let is_direct_debit = purpose.mandate_reference.is_some()
+        || purpose.creditor_identifier.is_some()
+        || transaction_type == "NDDT"
+        || matches!(purpose.transaction_code.as_deref(), Some(code) if DIRECT_DEBIT_CODES.contains(&code));
+    if !is_direct_debit {
+        return Ok(None);
+    }
+    let is_return = matches!(
+        purpose.transaction_code.as_deref(),
+        Some(code) if RETURN_CODES.contains(&code)
+    );
+
+    Ok(Some(StatementDebit {
+        booking_date,
+        value_date: Some(value_date),
+        amount,
+        currency: currency.to_string(),
+        is_reversal: is_return || mark == "RD" || mark == "C",
+        mandate_reference: purpose.mandate_reference,
+        creditor_identifier: purpose.creditor_identifier,
+        creditor_name: purpose.counterparty_name,
+        debtor_iban: account_iban.clone(),
+        end_to_end_id: purpose.end_to_end_id.or_else(|| {
+            Some(customer_reference.to_string())
+                .filter(|r| !r.is_empty() && r != "NONREF" && r != "NOTPROVIDED")
+        }),
+        bank_reference,
+        remittance_information: purpose.remittance_information,
+    }))
+}
+
+/// Content of the `:86:` information to account owner field.
+#[derive(Debug, Default, PartialEq)]
+struct Purpose {
+    transaction_code: Option<String>,
+    end_to_end_id: Option<String>,
+    mandate_reference: Option<String>,
+    creditor_identifier: Option<String>,
+    counterparty_name: Option<String>,
+    remittance_information: Option<String>,
+}
+
+impl Purpose {
+    fn parse(information: &str) -> Purpose {
+        let information = information.replace('\n', "");
+        // `get` as the text may start with multibyte characters
+        let transaction_code = match (information.get(0..3), information.get(3..)) {
+            (Some(code), Some(rest))
+                if code.chars().all(|c| c.is_ascii_digit()) && rest.starts_with('?') =>
+            {
+                Some(code.to_string())
+            }
+            _ => return Purpose::from_text(None, &information, None),
+        };
+        let mut purpose_text = String::new();
+        let mut name = String::new();
+        let positions: Vec<(usize, usize, &str)> = RE_SUBFIELD
+            .captures_iter(&information)
+            .filter_map(|c| {
+                let all = c.get(0)?;
+                Some((all.start(), all.end(), c.get(1)?.as_str()))
+            })
+            .collect();
+        for (index, (_, end, code)) in positions.iter().enumerate() {
+            let next = positions
+                .get(index + 1)
+                .map_or(information.len(), |(start, _, _)| *start);
+            let value = &information[*end..next];
+            match *code {
+                "20" | "21" | "22" | "23" | "24" | "25" | "26" | "27" | "28" | "29" | "60"
+                | "61" | "62" | "63" => purpose_text.push_str(value),
+                "32" | "33" => name.push_str(value),
+                _ => {}
+            }
+        }
+        Purpose::from_text(
+            transaction_code,
+            &purpose_text,
+            Some(name.trim().to_string()).filter(|n| !n.is_empty()),
+        )
+    }
+
+    fn from_text(
+        transaction_code: Option<String>,
+        text: &str,
+        counterparty_name: Option<String>,
+    ) -> Purpose {
+        let mut found: Vec<(usize, &str)> = KEYWORDS
+            .iter()
+            .filter_map(|keyword| text.find(keyword).map(|position| (position, *keyword)))
+            .collect();
+        found.sort_unstable();
+        let value = |keyword: &str| {
+            found
+                .iter()
+                .position(|(_, k)| *k == keyword)
+                .map(|index| {
+                    let start = found[index].0 + keyword.len();
+                    let end = found.get(index + 1).map_or(text.len(), |(p, _)| *p);
+                    text[start..end].trim().to_string()
+                })
+                .filter(|v| !v.is_empty() && v != "NOTPROVIDED")
+        };
+        let remittance_information = if found.is_empty() {
+            Some(text.trim().to_string()).filter(|t| !t.is_empty())
+        } else {
+            value("SVWZ+")
+        };
+        Purpose {
+            transaction_code,
+            end_to_end_id: value("EREF+"),
+            mandate_reference: value("MREF+"),
+            creditor_identifier: value("CRED+"),
+            counterparty_name: counterparty_name.or_else(|| value("ABWE+")),
+            remittance_information,
+        }
+    }
+}
+
+fn parse_date(yymmdd: &str) -> Option<NaiveDate> {
+    let year = 2000 + yymmdd.get(0..2)?.parse::<i32>().ok()?;
+    let month = yymmdd.get(2..4)?.parse().ok()?;
+    let day = yymmdd.get(4..6)?.parse().ok()?;
+    NaiveDate::from_ymd_opt(year, month, day)
+}
+
+/// The entry date only carries month and day, the year is taken from the value date
+/// and corrected when both dates lie on different sides of a year end.
+fn booking_date(value_date: NaiveDate, mmdd: &str) -> Option<NaiveDate> {
+    use chrono::Datelike;
+    let month: u32 = mmdd.get(0..2)?.parse().ok()?;
+    let day: u32 = mmdd.get(2..4)?.parse().ok()?;
+    let year = match (value_date.month(), month) {
+        (1, 12) => value_date.year() - 1,
+        (12, 1) => value_date.year() + 1,
+        _ => value_date.year(),
+    };
+    NaiveDate::from_ymd_opt(year, month, day)
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use chrono::NaiveDate;
+    use rust_decimal::Decimal;
+
+    use super::{Mt940Parser, Purpose};
+    use crate::statement::{StatementDebit, StatementParser};
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::from_str(value).unwrap()
+    }
+
+    #[test]
+    fn test_parse_statement() {
+        let content = include_str!("../../tests/fixtures/mt940.sta");
+        assert_eq!(Some("MT940"), Mt940Parser.detect(content));
+        let debits = Mt940Parser.parse(content).unwrap();
+        // the salary is no direct debit
+        assert_eq!(2, debits.len());
+        assert_eq!(
+            StatementDebit {
+                booking_date: date("2023-03-01"),
+                value_date: Some(date("2023-03-01")),
+                amount: Decimal::from_str("42.50").unwrap(),
+                currency: "EUR".to_string(),
+                is_reversal: false,
+                mandate_reference: Some("GAS-4711".to_string()),
+                creditor_identifier: Some("DE98ZZZ09999999999".to_string()),
+                creditor_name: Some("Stadtwerke GmbH".to_string()),
+                debtor_iban: Some("DE89370400440532013000".to_string()),
+                end_to_end_id: Some("E2E-GAS-03".to_string()),
+                bank_reference: Some("BANKREF1".to_string()),
+                remittance_information: Some("Abschlag Maerz".to_string()),
+            },
+            debits[0]
+        );
+        assert!(debits[1].is_reversal);
+        assert_eq!(date("2023-03-03"), debits[1].booking_date);
+        assert_eq!(Some("GAS-4711"), debits[1].mandate_reference.as_deref());
+    }
+
+    #[test]
+    fn test_purpose_with_multibyte_characters() {
+        assert_eq!(
+            Some("ÄÖÜ Miete".to_string()),
+            Purpose::parse("ÄÖÜ Miete").remittance_information
+        );
+        assert_eq!(
+            Purpose {
+                remittance_information: Some("12Ä?20x".to_string()),
+                ..Default::default()
+            },
+            Purpose::parse("12Ä?20x")
+        );
+        assert_eq!(
+            Some("12Ä".to_string()),
+            Purpose::parse("12Ä").remittance_information
+        );
+    }
+}
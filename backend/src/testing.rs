@@ -0,0 +1,108 @@
+//! Support of the tests: a mail transport keeping the mails in memory, and the database of the
+//! tests that need one. Those are ignored by default and run with
+//! `TEST_DATABASE_URL=postgres://... cargo test -- --ignored` against a migrated database
+//! (`cargo run -p migration -- up`). Every test works in a transaction that is rolled back.
+
+use std::sync::Mutex;
+
+use api_models::models::{Address, Creditor};
+use async_trait::async_trait;
+use entity::{
+    bank_account,
+    mandate::{self, MandatePaymentType, MandateScheme, MandateStatus},
+    sea_orm::{
+        ActiveModelTrait, ActiveValue::NotSet, ConnectionTrait, Database, DatabaseTransaction,
+        Set, TransactionTrait,
+    },
+    user_profile::{self, ProfileStatus},
+};
+use serde_json::json;
+
+use crate::mail::{Mail, MailError, MailTransport};
+
+/// Keeps the sent mails, the ones to `failing` fail.
+#[derive(Debug, Default)]
+pub(crate) struct MemoryTransport {
+    pub sent: Mutex<Vec<Mail>>,
+    pub failing: Option<String>,
+}
+
+#[async_trait]
+impl MailTransport for MemoryTransport {
+    async fn send(&self, mail: &Mail) -> Result<(), MailError> {
+        if self.failing.as_deref() == Some(mail.to.as_str()) {
+            return Err(MailError(format!("Mailbox {} is full", mail.to)));
+        }
+        self.sent.lock().unwrap().push(mail.clone());
+        Ok(())
+    }
+}
+
+pub(crate) async fn begin() -> DatabaseTransaction {
+    let url = std::env::var("TEST_DATABASE_URL").expect("TEST_DATABASE_URL must be set");
+    let db = Database::connect(url).await.expect("Can't connect to the test database");
+    db.begin().await.unwrap()
+}
+
+pub(crate) async fn insert_profile<C: ConnectionTrait>(db: &C) -> user_profile::Model {
+    user_profile::ActiveModel {
+        id: NotSet,
+        auth_id: Set(uuid::Uuid::new_v4().to_string()),
+        date_created: NotSet,
+        firstname: Set("Erika".to_string()),
+        lastname: Set("Mustermann".to_string()),
+        address: Set(None),
+        preferred_language: Set(None),
+        date_of_birth: Set(None),
+        status: Set(ProfileStatus::ProfileComplete),
+        email: Set(None),
+        notify_upcoming_debits: Set(false),
+        notify_debit_alerts: Set(false),
+        notify_profile_reminders: Set(false),
+        notify_expired_mandates: Set(false),
+    }
+    .insert(db)
+    .await
+    .unwrap()
+}
+
+/// An ACTIVE mandate collected from `account`.
+pub(crate) async fn insert_mandate<C: ConnectionTrait>(
+    db: &C,
+    account: &bank_account::Model,
+) -> mandate::Model {
+    mandate::ActiveModel {
+        id: NotSet,
+        api_id: Set(uuid::Uuid::new_v4()),
+        user_profile_id: Set(account.user_profile_id),
+        tags: Set(json!(["energy"])),
+        status: Set(MandateStatus::ACTIVE),
+        unique_reference: Set(Some("GAS-4711".to_string())),
+        display_name: Set("Gas".to_string()),
+        date_created: NotSet,
+        creditor: Set(json!(Creditor {
+            name: "Stadtwerke".to_string(),
+            sepa_identifier: Some("DE98ZZZ09999999999".to_string()),
+            address: Address {
+                street: "Hauptstraße".to_string(),
+                house_number: "12".to_string(),
+                zip: "10115".to_string(),
+                place: "Berlin".to_string(),
+            },
+        })),
+        bank_account_id: Set(account.id),
+        scheme: Set(MandateScheme::CORE),
+        payment_type: Set(MandatePaymentType::Recurrent),
+        signature_date: Set(None),
+        signature_place: Set(None),
+        account_holder: Set(None),
+        expected_amount: Set(None),
+        frequency: Set(None),
+        interval_months: Set(None),
+        anchor_date: Set(None),
+        last_collection_date: Set(None),
+    }
+    .insert(db)
+    .await
+    .unwrap()
+}
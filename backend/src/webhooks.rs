@@ -0,0 +1,433 @@
+//! Outgoing webhooks. A change writes one delivery per subscription of the event in its own
+//! transaction, so no event is lost when the process dies after the commit. A dispatcher on
+//! every replica claims the pending deliveries, posts them signed with the secret of the
+//! subscription and retries failed ones with exponential backoff.
+//!
+//! Deliveries carry the headers `X-SepaMa-Event`, `X-SepaMa-Delivery`, `X-SepaMa-Timestamp`
+//! (unix seconds) and `X-SepaMa-Signature`: `sha256=` and the hex HMAC-SHA256 of
+//! `<timestamp>.<body>`.
+//!
+//! Subscriptions name any https URL, so a delivery is refused when the host resolves to a
+//! loopback, private, link-local or other internal address, and redirects are not followed.
+//! The client resolves the hosts itself and connects to the checked addresses, a second lookup
+//! can't point it elsewhere.
+
+use std::error::Error;
+use std::future::Future;
+use std::net::{IpAddr, SocketAddr, ToSocketAddrs};
+use std::pin::Pin;
+
+use actix_tls::connect::{Connector as TcpConnector, Resolve, Resolver};
+use actix_web::{http::Uri, web};
+use api_models::models::{
+    webhook::retry_delay, DeliveryStatus as DeliveryStatusDto, Mandate, WebhookDelivery,
+    WebhookEvent, WebhookPayload,
+};
+use chrono::{Duration, NaiveDateTime, Utc};
+use entity::{
+    mandate,
+    sea_orm::{
+        ActiveModelTrait, ActiveValue::NotSet, ColumnTrait, ConnectionTrait, DatabaseBackend,
+        DbErr, EntityTrait, QueryFilter, Set, Statement,
+    },
+    webhook_delivery::{self, DeliveryStatus, Entity as DeliveryEntity},
+    webhook_subscription::{self, Entity as SubscriptionEntity},
+};
+use hmac::{Hmac, Mac};
+use log::{debug, error};
+use serde::Serialize;
+use sha2::Sha256;
+
+use crate::errors::ServiceError;
+use crate::handlers::db_error;
+use crate::AppState;
+
+/// How often the dispatcher looks for due deliveries.
+const TICK: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Deliveries claimed per tick.
+const BATCH: i64 = 20;
+
+/// A claimed delivery is attempted again after this when its attempt never finished.
+const LEASE_MINUTES: i64 = 5;
+
+const TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Finished deliveries stay in the log this long.
+pub const LOG_DAYS: i64 = 30;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Queues `event` for every active subscription of the user to it, within the transaction of
+/// the change. Returns the number of queued deliveries.
+pub async fn enqueue<C: ConnectionTrait, T: Serialize>(
+    db: &C,
+    user_profile_id: i32,
+    event: WebhookEvent,
+    data: &T,
+) -> Result<usize, DbErr> {
+    let subscriptions: Vec<webhook_subscription::Model> = SubscriptionEntity::find()
+        .filter(webhook_subscription::Column::UserProfileId.eq(user_profile_id))
+        .filter(webhook_subscription::Column::Active.eq(true))
+        .all(db)
+        .await?
+        .into_iter()
+        .filter(|s| {
+            serde_json::from_value::<Vec<WebhookEvent>>(s.events.clone())
+                .unwrap_or_default()
+                .contains(&event)
+        })
+        .collect();
+    if subscriptions.is_empty() {
+        return Ok(0);
+    }
+    let now = Utc::now().naive_utc();
+    let payload = WebhookPayload {
+        id: uuid::Uuid::new_v4(),
+        event,
+        created: now,
+        data: serde_json::to_value(data).map_err(|e| DbErr::Custom(e.to_string()))?,
+    };
+    let value = serde_json::json!(payload);
+    let name: &'static str = event.into();
+    for subscription in &subscriptions {
+        webhook_delivery::ActiveModel {
+            id: NotSet,
+            api_id: Set(uuid::Uuid::new_v4()),
+            subscription_id: Set(subscription.id),
+            event_id: Set(payload.id),
+            event: Set(name.to_string()),
+            payload: Set(value.clone()),
+            status: Set(DeliveryStatus::Pending),
+            attempts: Set(0),
+            next_attempt: Set(Some(now)),
+            last_attempt: NotSet,
+            response_status: NotSet,
+            error: NotSet,
+            date_created: NotSet,
+        }
+        .insert(db)
+        .await?;
+    }
+    Ok(subscriptions.len())
+}
+
+/// Queues the events of a saved mandate, `old` is `None` when it was created.
+pub async fn enqueue_mandate<C: ConnectionTrait>(
+    db: &C,
+    old: Option<&mandate::Model>,
+    new: &mandate::Model,
+    data: &Mandate,
+) -> Result<usize, DbErr> {
+    let events: &[WebhookEvent] = match old {
+        None => &[WebhookEvent::MandateCreated],
+        Some(old) if old == new => &[],
+        Some(old) if old.status != new.status => &[
+            WebhookEvent::MandateUpdated,
+            WebhookEvent::MandateStatusChanged,
+        ],
+        Some(_) => &[WebhookEvent::MandateUpdated],
+    };
+    let mut queued = 0;
+    for event in events {
+        queued += enqueue(db, new.user_profile_id, *event, data).await?;
+    }
+    Ok(queued)
+}
+
+pub(crate) fn to_dto(delivery: &webhook_delivery::Model) -> Option<WebhookDelivery> {
+    let status: DeliveryStatusDto = delivery.status.clone().into();
+    Some(WebhookDelivery {
+        api_id: delivery.api_id,
+        event_id: delivery.event_id,
+        event: delivery.event.parse().ok()?,
+        status,
+        attempts: delivery.attempts as u32,
+        date_created: delivery.date_created,
+        last_attempt: delivery.last_attempt,
+        next_attempt: delivery
+            .next_attempt
+            .filter(|_| status == DeliveryStatusDto::Pending),
+        response_status: delivery.response_status.map(|status| status as u16),
+        error: delivery.error.clone(),
+    })
+}
+
+pub fn spawn_dispatcher(state: AppState) {
+    actix_web::rt::spawn(async move {
+        // a redirect could lead to an internal address after the check of the target
+        let connector = awc::Connector::new()
+            .connector(TcpConnector::new(Resolver::custom(PublicResolver)).service());
+        let client = awc::Client::builder()
+            .connector(connector)
+            .timeout(TIMEOUT)
+            .disable_redirects()
+            .finish();
+        let mut interval = actix_web::rt::time::interval(TICK);
+        loop {
+            interval.tick().await;
+            match dispatch(&state, &client).await {
+                Ok(0) => {}
+                Ok(attempted) => debug!("Attempted {} webhook deliveries", attempted),
+                Err(e) => error!("Dispatching webhooks failed: {:?}", e),
+            }
+        }
+    });
+}
+
+/// Claims the due deliveries by moving their next attempt past the lease, replicas skip the rows
+/// another one is claiming. Returns the number of attempted deliveries.
+async fn dispatch(state: &AppState, client: &awc::Client) -> Result<usize, ServiceError> {
+    let now = Utc::now().naive_utc();
+    let claimed = DeliveryEntity::find()
+        .from_raw_sql(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            "update webhook_delivery set next_attempt = $1
+            where id in (
+                select id from webhook_delivery
+                where status = 'PENDING' and next_attempt <= $2
+                order by next_attempt
+                limit $3
+                for update skip locked)
+            returning *",
+            vec![
+                (now + Duration::minutes(LEASE_MINUTES)).into(),
+                now.into(),
+                BATCH.into(),
+            ],
+        ))
+        .all(&state.connection)
+        .await
+        .map_err(db_error)?;
+    let attempted = claimed.len();
+    for delivery in claimed {
+        deliver(state, client, delivery).await?;
+    }
+    Ok(attempted)
+}
+
+async fn deliver(
+    state: &AppState,
+    client: &awc::Client,
+    delivery: webhook_delivery::Model,
+) -> Result<(), ServiceError> {
+    let subscription = match SubscriptionEntity::find_by_id(delivery.subscription_id)
+        .one(&state.connection)
+        .await
+        .map_err(db_error)?
+    {
+        Some(subscription) => subscription,
+        // deleted meanwhile, its deliveries went with it
+        None => return Ok(()),
+    };
+    let body = delivery.payload.to_string();
+    let timestamp = Utc::now().timestamp();
+    let result = match check_target(&subscription.url) {
+        Ok(()) => client
+            .post(subscription.url.as_str())
+            .content_type("application/json")
+            .insert_header(("X-SepaMa-Event", delivery.event.clone()))
+            .insert_header(("X-SepaMa-Delivery", delivery.api_id.to_string()))
+            .insert_header(("X-SepaMa-Timestamp", timestamp.to_string()))
+            .insert_header((
+                "X-SepaMa-Signature",
+                format!("sha256={}", sign(&subscription.secret, timestamp, &body)),
+            ))
+            .send_body(body)
+            .await
+            .map_err(|e| e.to_string()),
+        Err(e) => Err(e),
+    };
+    let (response_status, error) = match result {
+        Ok(response) if response.status().is_success() => (Some(response.status().as_u16()), None),
+        Ok(response) => (
+            Some(response.status().as_u16()),
+            Some(format!("Responded with HTTP {}", response.status().as_u16())),
+        ),
+        Err(e) => (None, Some(e)),
+    };
+    let now = Utc::now().naive_utc();
+    let attempts = delivery.attempts as u32 + 1;
+    let (status, next_attempt): (DeliveryStatus, Option<NaiveDateTime>) = match &error {
+        None => (DeliveryStatus::Delivered, None),
+        Some(_) => match retry_delay(attempts) {
+            Some(delay) => (DeliveryStatus::Pending, Some(now + delay)),
+            None => (DeliveryStatus::Failed, None),
+        },
+    };
+    if let Some(e) = &error {
+        debug!(
+            "Webhook delivery {} to {} failed, attempt {}: {}",
+            delivery.api_id, subscription.url, attempts, e
+        );
+    }
+    let mut attempted: webhook_delivery::ActiveModel = delivery.into();
+    attempted.status = Set(status);
+    attempted.attempts = Set(attempts as i32);
+    attempted.next_attempt = Set(next_attempt);
+    attempted.last_attempt = Set(Some(now));
+    attempted.response_status = Set(response_status.map(i32::from));
+    attempted.error = Set(error);
+    attempted.update(&state.connection).await.map_err(db_error)?;
+    Ok(())
+}
+
+/// Refuses URLs whose host is an internal IP address. The client connects to those without a
+/// lookup, other hosts are checked by [`PublicResolver`].
+fn check_target(url: &str) -> Result<(), String> {
+    let uri: Uri = url.parse().map_err(|e| format!("Invalid URL {}: {}", url, e))?;
+    let host = uri.host().ok_or_else(|| format!("No host in URL {}", url))?;
+    match host.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>() {
+        Ok(ip) if !is_public(ip) => Err(format!("{} is an internal address", ip)),
+        _ => Ok(()),
+    }
+}
+
+/// Resolver of the webhook client, a host resolves only when all its addresses are public.
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn lookup<'a>(
+        &'a self,
+        host: &'a str,
+        port: u16,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<SocketAddr>, Box<dyn Error>>> + 'a>> {
+        Box::pin(async move {
+            let lookup = host.to_string();
+            let addresses: Vec<SocketAddr> = web::block(move || {
+                (lookup.as_str(), port)
+                    .to_socket_addrs()
+                    .map(Iterator::collect)
+            })
+            .await??;
+            Ok(public_addresses(host, addresses)?)
+        })
+    }
+}
+
+fn public_addresses(host: &str, addresses: Vec<SocketAddr>) -> Result<Vec<SocketAddr>, String> {
+    match addresses.iter().find(|address| !is_public(address.ip())) {
+        Some(address) => Err(format!("{} resolves to the internal address {}", host, address.ip())),
+        None if addresses.is_empty() => Err(format!("{} has no address", host)),
+        None => Ok(addresses),
+    }
+}
+
+/// Whether `ip` is reachable on the internet, rather than the host itself or a private network.
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [first, second, ..] = ip.octets();
+            !(ip.is_unspecified()
+                || ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                || ip.is_documentation()
+                // "this network" and the shared address space of carrier-grade NAT
+                || first == 0
+                || (first == 100 && (64..128).contains(&second)))
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(mapped) => is_public(IpAddr::V4(mapped)),
+            None => {
+                let first = ip.segments()[0];
+                !(ip.is_unspecified()
+                    || ip.is_loopback()
+                    || ip.is_multicast()
+                    // unique local fc00::/7 and link-local fe80::/10
+                    || first & 0xfe00 == 0xfc00
+                    || first & 0xffc0 == 0xfe80)
+            }
+        },
+    }
+}
+
+/// Hex HMAC-SHA256 of `<timestamp>.<body>` with the secret of the subscription.
+fn sign(secret: &str, timestamp: i64, body: &str) -> String {
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC takes keys of any length");
+    mac.update(format!("{}.", timestamp).as_bytes());
+    mac.update(body.as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// Removes the delivered and failed deliveries older than `LOG_DAYS`.
+pub async fn purge_log(state: &AppState, now: NaiveDateTime) -> Result<u64, ServiceError> {
+    DeliveryEntity::delete_many()
+        .filter(webhook_delivery::Column::Status.ne(DeliveryStatus::Pending))
+        .filter(webhook_delivery::Column::DateCreated.lt(now - Duration::days(LOG_DAYS)))
+        .exec(&state.connection)
+        .await
+        .map(|result| result.rows_affected)
+        .map_err(db_error)
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::IpAddr;
+
+    use actix_tls::connect::Resolve;
+
+    use super::{check_target, is_public, sign, PublicResolver};
+
+    #[test]
+    fn test_sign() {
+        assert_eq!(
+            "407ef7b94e834b186a2e09c72f8b18077d78ec93920d0fe97e9f15464de7a5a7",
+            sign(
+                "whsec_0123456789abcdef",
+                1678000000,
+                r#"{"event":"mandate.created"}"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_is_public() {
+        let public = |ip: &str| is_public(ip.parse::<IpAddr>().unwrap());
+        for ip in ["93.184.216.34", "8.8.8.8", "2606:2800:220:1::1", "::ffff:8.8.8.8"] {
+            assert!(public(ip), "{}", ip);
+        }
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.178.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "255.255.255.255",
+            "::1",
+            "::",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+            "::ffff:169.254.169.254",
+        ] {
+            assert!(!public(ip), "{}", ip);
+        }
+    }
+
+    #[test]
+    fn test_internal_targets_are_refused() {
+        for url in [
+            "https://127.0.0.1/hook",
+            "https://[::1]:8443/hook",
+            "https://169.254.169.254/latest/meta-data",
+            "https://user@10.0.0.1:443/hook",
+        ] {
+            let error = check_target(url).unwrap_err();
+            assert!(error.contains("internal address"), "{}: {}", url, error);
+        }
+        assert!(check_target("https://93.184.216.34/hook").is_ok());
+        assert!(check_target("https://example.com/hook").is_ok());
+    }
+
+    #[actix_web::test]
+    async fn test_resolver_refuses_internal_hosts() {
+        let error = PublicResolver.lookup("localhost", 443).await.unwrap_err();
+        assert!(error.to_string().contains("internal address"), "{}", error);
+    }
+}
@@ -0,0 +1,62 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Debtor account of the user, shared by all mandates collected from it.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize)]
+#[sea_orm(table_name = "bank_account")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    #[serde(skip_deserializing)]
+    pub id: i32,
+
+    #[sea_orm(unique)]
+    pub api_id: Uuid,
+
+    pub user_profile_id: i32,
+
+    pub holder_name: String,
+
+    pub nickname: Option<String>,
+
+    pub institution: String,
+
+    pub iban: String,
+
+    pub bic: Option<String>,
+
+    pub is_primary: bool,
+
+    pub date_created: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter)]
+pub enum Relation {
+    UserProfile,
+    Mandates,
+}
+
+impl RelationTrait for Relation {
+    fn def(&self) -> RelationDef {
+        match self {
+            Self::UserProfile => Entity::belongs_to(super::user_profile::Entity)
+                .from(Column::UserProfileId)
+                .to(super::user_profile::Column::Id)
+                .into(),
+            Self::Mandates => Entity::has_many(super::mandate::Entity).into(),
+        }
+    }
+}
+
+impl Related<super::user_profile::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::UserProfile.def()
+    }
+}
+
+impl Related<super::mandate::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Mandates.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,81 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Direct debit booked on a statement of the user, linked to the mandate it was collected under.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Deserialize, Serialize)]
+#[sea_orm(table_name = "bank_transaction")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    #[serde(skip_deserializing)]
+    pub id: i32,
+
+    #[sea_orm(unique)]
+    pub api_id: Uuid,
+
+    pub user_profile_id: i32,
+
+    pub mandate_id: Option<i32>,
+
+    pub source: String,
+
+    pub booking_date: Date,
+
+    pub value_date: Option<Date>,
+
+    pub amount: Decimal,
+
+    pub currency: String,
+
+    pub is_reversal: bool,
+
+    pub mandate_reference: Option<String>,
+
+    pub creditor_identifier: Option<String>,
+
+    pub creditor_name: Option<String>,
+
+    pub debtor_iban: Option<String>,
+
+    pub end_to_end_id: Option<String>,
+
+    pub remittance_information: Option<String>,
+
+    pub fingerprint: String,
+
+    pub date_imported: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter)]
+pub enum Relation {
+    UserProfile,
+    Mandate,
+}
+
+impl RelationTrait for Relation {
+    fn def(&self) -> RelationDef {
+        match self {
+            Self::UserProfile => Entity::belongs_to(super::user_profile::Entity)
+                .from(Column::UserProfileId)
+                .to(super::user_profile::Column::Id)
+                .into(),
+            Self::Mandate => Entity::belongs_to(super::mandate::Entity)
+                .from(Column::MandateId)
+                .to(super::mandate::Column::Id)
+                .into(),
+        }
+    }
+}
+
+impl Related<super::user_profile::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::UserProfile.def()
+    }
+}
+
+impl Related<super::mandate::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Mandate.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
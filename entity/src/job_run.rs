@@ -0,0 +1,65 @@
+use sea_orm::entity::prelude::*;
+use api_models::models::JobRunStatus;
+use serde::{Deserialize, Serialize};
+
+/// A run of a periodic job, a scheduled slot of a job is run only once.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize)]
+#[sea_orm(table_name = "job_run")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    #[serde(skip_deserializing)]
+    pub id: i32,
+
+    /// Name of the `api_models::models::Job`.
+    pub job: String,
+
+    pub scheduled_for: DateTime,
+
+    pub triggered_by: Option<String>,
+
+    pub status: RunStatus,
+
+    pub started: DateTime,
+
+    pub finished: Option<DateTime>,
+
+    pub message: Option<String>,
+}
+
+#[derive(EnumIter, DeriveActiveEnum, Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[sea_orm(rs_type = "String", db_type = "String(Some(1))")]
+pub enum RunStatus {
+    #[sea_orm(string_value = "RUNNING")]
+    Running,
+
+    #[sea_orm(string_value = "SUCCEEDED")]
+    Succeeded,
+
+    #[sea_orm(string_value = "FAILED")]
+    Failed,
+}
+
+impl From<JobRunStatus> for RunStatus {
+    fn from(status: JobRunStatus) -> Self {
+        match status {
+            JobRunStatus::Running => RunStatus::Running,
+            JobRunStatus::Succeeded => RunStatus::Succeeded,
+            JobRunStatus::Failed => RunStatus::Failed,
+        }
+    }
+}
+
+impl From<RunStatus> for JobRunStatus {
+    fn from(status: RunStatus) -> Self {
+        match status {
+            RunStatus::Running => JobRunStatus::Running,
+            RunStatus::Succeeded => JobRunStatus::Succeeded,
+            RunStatus::Failed => JobRunStatus::Failed,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -1,3 +1,9 @@
+pub mod bank_account;
+pub mod bank_transaction;
+pub mod job_run;
 pub mod mandate;
+pub mod mandate_history;
 pub mod user_profile;
+pub mod webhook_delivery;
+pub mod webhook_subscription;
 pub use sea_orm;
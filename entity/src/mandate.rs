@@ -1,5 +1,6 @@
 
 use sea_orm::{entity::prelude::*, strum::{EnumString, IntoStaticStr}};
+use api_models::models::{CollectionSchedule, Frequency, PaymentType, Scheme, Status};
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, Eq, PartialEq, DeriveEntityModel, Deserialize, Serialize)]
@@ -26,26 +27,213 @@ pub struct Model {
 
     pub creditor: Json,
 
-    pub bank_account: Json,
+    pub bank_account_id: i32,
+
+    pub scheme: MandateScheme,
+
+    pub payment_type: MandatePaymentType,
+
+    pub signature_date: Option<Date>,
+
+    pub signature_place: Option<String>,
+
+    pub account_holder: Option<String>,
+
+    pub expected_amount: Option<Decimal>,
+
+    pub frequency: Option<MandateFrequency>,
+
+    pub interval_months: Option<i32>,
+
+    pub anchor_date: Option<Date>,
+
+    pub last_collection_date: Option<Date>,
+}
+
+impl Model {
+    /// Signature date of the mandate, the creation date when it was not entered.
+    pub fn signed_on(&self) -> Date {
+        self.signature_date.unwrap_or_else(|| self.date_created.date())
+    }
+
+    /// Day of the latest collection, the signature date when there was none.
+    pub fn last_used_on(&self) -> Date {
+        self.last_collection_date.unwrap_or_else(|| self.signed_on())
+    }
+
+    /// The expected collections, when amount, frequency and anchor date are all stored.
+    pub fn schedule(&self) -> Option<CollectionSchedule> {
+        match (self.expected_amount, &self.frequency, self.anchor_date) {
+            (Some(amount), Some(frequency), Some(anchor_date)) => Some(CollectionSchedule {
+                amount,
+                frequency: frequency.clone().into(),
+                interval_months: self.interval_months.map(|months| months as u32),
+                anchor_date,
+            }),
+            _ => None,
+        }
+    }
 }
 
+/// Stored form of `api_models::models::Status`, which defines the allowed transitions.
 #[derive(EnumIter, IntoStaticStr, EnumString, DeriveActiveEnum, Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 #[sea_orm(rs_type = "String", db_type = "String(Some(1))")]
 pub enum MandateStatus {
+    #[sea_orm(string_value = "NEW")]
+    NEW,
+
     #[sea_orm(string_value = "ACTIVE")]
     ACTIVE,
 
-    #[sea_orm(string_value = "DELETED")]
-    DELETED,
+    #[sea_orm(string_value = "SUSPENDED")]
+    SUSPENDED,
 
     #[sea_orm(string_value = "CANCELED")]
     CANCELED,
+
+    #[sea_orm(string_value = "EXPIRED")]
+    EXPIRED,
+
+    #[sea_orm(string_value = "DELETED")]
+    DELETED,
+}
+
+impl From<Status> for MandateStatus {
+    fn from(status: Status) -> Self {
+        match status {
+            Status::NEW => MandateStatus::NEW,
+            Status::ACTIVE => MandateStatus::ACTIVE,
+            Status::SUSPENDED => MandateStatus::SUSPENDED,
+            Status::CANCELED => MandateStatus::CANCELED,
+            Status::EXPIRED => MandateStatus::EXPIRED,
+            Status::DELETED => MandateStatus::DELETED,
+        }
+    }
+}
+
+impl From<MandateStatus> for Status {
+    fn from(status: MandateStatus) -> Self {
+        match status {
+            MandateStatus::NEW => Status::NEW,
+            MandateStatus::ACTIVE => Status::ACTIVE,
+            MandateStatus::SUSPENDED => Status::SUSPENDED,
+            MandateStatus::CANCELED => Status::CANCELED,
+            MandateStatus::EXPIRED => Status::EXPIRED,
+            MandateStatus::DELETED => Status::DELETED,
+        }
+    }
+}
+
+impl MandateStatus {
+    pub fn can_transition_to(&self, next: &MandateStatus) -> bool {
+        Status::from(self.clone()).can_transition_to(next.clone().into())
+    }
+}
+
+/// Stored form of `api_models::models::Scheme`.
+#[derive(EnumIter, DeriveActiveEnum, Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[sea_orm(rs_type = "String", db_type = "String(Some(1))")]
+pub enum MandateScheme {
+    #[sea_orm(string_value = "CORE")]
+    CORE,
+
+    #[sea_orm(string_value = "B2B")]
+    B2B,
+}
+
+impl From<Scheme> for MandateScheme {
+    fn from(scheme: Scheme) -> Self {
+        match scheme {
+            Scheme::CORE => MandateScheme::CORE,
+            Scheme::B2B => MandateScheme::B2B,
+        }
+    }
+}
+
+impl From<MandateScheme> for Scheme {
+    fn from(scheme: MandateScheme) -> Self {
+        match scheme {
+            MandateScheme::CORE => Scheme::CORE,
+            MandateScheme::B2B => Scheme::B2B,
+        }
+    }
+}
+
+/// Stored form of `api_models::models::PaymentType`.
+#[derive(EnumIter, DeriveActiveEnum, Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[sea_orm(rs_type = "String", db_type = "String(Some(1))")]
+pub enum MandatePaymentType {
+    #[sea_orm(string_value = "RECURRENT")]
+    Recurrent,
+
+    #[sea_orm(string_value = "ONE_OFF")]
+    OneOff,
+}
+
+impl From<PaymentType> for MandatePaymentType {
+    fn from(payment_type: PaymentType) -> Self {
+        match payment_type {
+            PaymentType::Recurrent => MandatePaymentType::Recurrent,
+            PaymentType::OneOff => MandatePaymentType::OneOff,
+        }
+    }
+}
+
+impl From<MandatePaymentType> for PaymentType {
+    fn from(payment_type: MandatePaymentType) -> Self {
+        match payment_type {
+            MandatePaymentType::Recurrent => PaymentType::Recurrent,
+            MandatePaymentType::OneOff => PaymentType::OneOff,
+        }
+    }
+}
+
+/// Stored form of `api_models::models::Frequency`.
+#[derive(EnumIter, DeriveActiveEnum, Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[sea_orm(rs_type = "String", db_type = "String(Some(1))")]
+pub enum MandateFrequency {
+    #[sea_orm(string_value = "MONTHLY")]
+    Monthly,
+
+    #[sea_orm(string_value = "QUARTERLY")]
+    Quarterly,
+
+    #[sea_orm(string_value = "YEARLY")]
+    Yearly,
+
+    #[sea_orm(string_value = "CUSTOM")]
+    Custom,
+}
+
+impl From<Frequency> for MandateFrequency {
+    fn from(frequency: Frequency) -> Self {
+        match frequency {
+            Frequency::Monthly => MandateFrequency::Monthly,
+            Frequency::Quarterly => MandateFrequency::Quarterly,
+            Frequency::Yearly => MandateFrequency::Yearly,
+            Frequency::Custom => MandateFrequency::Custom,
+        }
+    }
+}
+
+impl From<MandateFrequency> for Frequency {
+    fn from(frequency: MandateFrequency) -> Self {
+        match frequency {
+            MandateFrequency::Monthly => Frequency::Monthly,
+            MandateFrequency::Quarterly => Frequency::Quarterly,
+            MandateFrequency::Yearly => Frequency::Yearly,
+            MandateFrequency::Custom => Frequency::Custom,
+        }
+    }
 }
 
 
 #[derive(Copy, Clone, Debug, EnumIter)]
 pub enum Relation {
     UserProfile,
+    BankAccount,
+    BankTransactions,
+    History,
 }
 
 impl RelationTrait for Relation {
@@ -55,6 +243,12 @@ impl RelationTrait for Relation {
                 .from(Column::UserProfileId)
                 .to(super::user_profile::Column::Id)
                 .into(),
+            Self::BankAccount => Entity::belongs_to(super::bank_account::Entity)
+                .from(Column::BankAccountId)
+                .to(super::bank_account::Column::Id)
+                .into(),
+            Self::BankTransactions => Entity::has_many(super::bank_transaction::Entity).into(),
+            Self::History => Entity::has_many(super::mandate_history::Entity).into(),
         }
     }
 }
@@ -65,4 +259,22 @@ impl Related<super::user_profile::Entity> for Entity {
     }
 }
 
+impl Related<super::bank_account::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::BankAccount.def()
+    }
+}
+
+impl Related<super::bank_transaction::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::BankTransactions.def()
+    }
+}
+
+impl Related<super::mandate_history::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::History.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,47 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Audit trail of a mandate, one row per changed field.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Deserialize, Serialize)]
+#[sea_orm(table_name = "mandate_history")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    #[serde(skip_deserializing)]
+    pub id: i32,
+
+    pub mandate_id: i32,
+
+    pub auth_id: String,
+
+    pub date_changed: DateTime,
+
+    pub field: String,
+
+    pub old_value: Option<Json>,
+
+    pub new_value: Option<Json>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter)]
+pub enum Relation {
+    Mandate,
+}
+
+impl RelationTrait for Relation {
+    fn def(&self) -> RelationDef {
+        match self {
+            Self::Mandate => Entity::belongs_to(super::mandate::Entity)
+                .from(Column::MandateId)
+                .to(super::mandate::Column::Id)
+                .into(),
+        }
+    }
+}
+
+impl Related<super::mandate::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Mandate.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -15,6 +15,12 @@ pub struct Model {
     pub preferred_language: Option<String>,
     pub date_of_birth: Option<Date>,
     pub status: ProfileStatus,
+    /// Address from the token of the last save, notifications are sent to it.
+    pub email: Option<String>,
+    pub notify_upcoming_debits: bool,
+    pub notify_debit_alerts: bool,
+    pub notify_profile_reminders: bool,
+    pub notify_expired_mandates: bool,
 }
 
 #[derive(EnumIter, DeriveActiveEnum, Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
@@ -29,12 +35,18 @@ pub enum ProfileStatus {
 #[derive(Copy, Clone, Debug, EnumIter)]
 pub enum Relation {
     Mandates,
+    BankAccounts,
+    WebhookSubscriptions,
 }
 
 impl RelationTrait for Relation {
     fn def(&self) -> RelationDef {
         match self {
             Self::Mandates => Entity::has_many(super::mandate::Entity).into(),
+            Self::BankAccounts => Entity::has_many(super::bank_account::Entity).into(),
+            Self::WebhookSubscriptions => {
+                Entity::has_many(super::webhook_subscription::Entity).into()
+            }
         }
     }
 }
@@ -45,4 +57,16 @@ impl Related<super::mandate::Entity> for Entity {
     }
 }
 
+impl Related<super::bank_account::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::BankAccounts.def()
+    }
+}
+
+impl Related<super::webhook_subscription::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::WebhookSubscriptions.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,99 @@
+use sea_orm::entity::prelude::*;
+use api_models::models::DeliveryStatus as DeliveryStatusDto;
+use serde::{Deserialize, Serialize};
+
+/// An event to post to a subscription. Written in the transaction of the change as the outbox
+/// and kept with the outcome of the last attempt as the delivery log.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize)]
+#[sea_orm(table_name = "webhook_delivery")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    #[serde(skip_deserializing)]
+    pub id: i32,
+
+    #[sea_orm(unique)]
+    pub api_id: Uuid,
+
+    pub subscription_id: i32,
+
+    pub event_id: Uuid,
+
+    /// Name of the `api_models::models::WebhookEvent`.
+    pub event: String,
+
+    /// The serialized `api_models::models::WebhookPayload`.
+    pub payload: Json,
+
+    pub status: DeliveryStatus,
+
+    pub attempts: i32,
+
+    /// While pending, also pushed out while an attempt is in flight.
+    pub next_attempt: Option<DateTime>,
+
+    pub last_attempt: Option<DateTime>,
+
+    pub response_status: Option<i32>,
+
+    pub error: Option<String>,
+
+    pub date_created: DateTime,
+}
+
+/// Stored form of `api_models::models::DeliveryStatus`.
+#[derive(EnumIter, DeriveActiveEnum, Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[sea_orm(rs_type = "String", db_type = "String(Some(1))")]
+pub enum DeliveryStatus {
+    #[sea_orm(string_value = "PENDING")]
+    Pending,
+
+    #[sea_orm(string_value = "DELIVERED")]
+    Delivered,
+
+    #[sea_orm(string_value = "FAILED")]
+    Failed,
+}
+
+impl From<DeliveryStatusDto> for DeliveryStatus {
+    fn from(status: DeliveryStatusDto) -> Self {
+        match status {
+            DeliveryStatusDto::Pending => DeliveryStatus::Pending,
+            DeliveryStatusDto::Delivered => DeliveryStatus::Delivered,
+            DeliveryStatusDto::Failed => DeliveryStatus::Failed,
+        }
+    }
+}
+
+impl From<DeliveryStatus> for DeliveryStatusDto {
+    fn from(status: DeliveryStatus) -> Self {
+        match status {
+            DeliveryStatus::Pending => DeliveryStatusDto::Pending,
+            DeliveryStatus::Delivered => DeliveryStatusDto::Delivered,
+            DeliveryStatus::Failed => DeliveryStatusDto::Failed,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter)]
+pub enum Relation {
+    Subscription,
+}
+
+impl RelationTrait for Relation {
+    fn def(&self) -> RelationDef {
+        match self {
+            Self::Subscription => Entity::belongs_to(super::webhook_subscription::Entity)
+                .from(Column::SubscriptionId)
+                .to(super::webhook_subscription::Column::Id)
+                .into(),
+        }
+    }
+}
+
+impl Related<super::webhook_subscription::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Subscription.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,60 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Webhook of a user, events are posted to its URL signed with its secret.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Deserialize, Serialize)]
+#[sea_orm(table_name = "webhook_subscription")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    #[serde(skip_deserializing)]
+    pub id: i32,
+
+    #[sea_orm(unique)]
+    pub api_id: Uuid,
+
+    pub user_profile_id: i32,
+
+    pub url: String,
+
+    #[serde(skip_serializing)]
+    pub secret: String,
+
+    /// Names of the subscribed `api_models::models::WebhookEvent`s.
+    pub events: Json,
+
+    pub active: bool,
+
+    pub date_created: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter)]
+pub enum Relation {
+    UserProfile,
+    Deliveries,
+}
+
+impl RelationTrait for Relation {
+    fn def(&self) -> RelationDef {
+        match self {
+            Self::UserProfile => Entity::belongs_to(super::user_profile::Entity)
+                .from(Column::UserProfileId)
+                .to(super::user_profile::Column::Id)
+                .into(),
+            Self::Deliveries => Entity::has_many(super::webhook_delivery::Entity).into(),
+        }
+    }
+}
+
+impl Related<super::user_profile::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::UserProfile.def()
+    }
+}
+
+impl Related<super::webhook_delivery::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Deliveries.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -2,6 +2,19 @@ pub use sea_orm_migration::prelude::*;
 
 mod m_1_create_table_user_profile;
 mod m_2_create_table_mandate;
+mod m_3_create_table_bank_transaction;
+mod m_4_create_table_mandate_history;
+mod m_5_alter_mandate_status_check;
+mod m_6_create_mandate_search_indexes;
+mod m_7_alter_mandate_tags_not_null;
+mod m_8_create_table_bank_account;
+mod m_9_alter_mandate_add_scheme_and_signature;
+mod m_10_alter_mandate_add_schedule;
+mod m_11_alter_user_profile_add_notifications;
+mod m_12_create_table_job_run;
+mod m_13_alter_mandate_add_last_collection_date;
+mod m_14_create_tables_webhook;
+mod sql;
 
 pub struct Migrator;
 
@@ -11,6 +24,18 @@ impl MigratorTrait for Migrator {
         vec![
             Box::new(m_1_create_table_user_profile::Migration),
             Box::new(m_2_create_table_mandate::Migration),
+            Box::new(m_3_create_table_bank_transaction::Migration),
+            Box::new(m_4_create_table_mandate_history::Migration),
+            Box::new(m_5_alter_mandate_status_check::Migration),
+            Box::new(m_6_create_mandate_search_indexes::Migration),
+            Box::new(m_7_alter_mandate_tags_not_null::Migration),
+            Box::new(m_8_create_table_bank_account::Migration),
+            Box::new(m_9_alter_mandate_add_scheme_and_signature::Migration),
+            Box::new(m_10_alter_mandate_add_schedule::Migration),
+            Box::new(m_11_alter_user_profile_add_notifications::Migration),
+            Box::new(m_12_create_table_job_run::Migration),
+            Box::new(m_13_alter_mandate_add_last_collection_date::Migration),
+            Box::new(m_14_create_tables_webhook::Migration),
         ]
     }
 }
@@ -0,0 +1,50 @@
+use sea_orm_migration::prelude::*;
+use sea_orm::DbErr;
+
+use crate::sql::execute_all;
+
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m_10_alter_mandate_add_schedule"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        execute_all(
+            manager,
+            &[
+                "alter table mandate
+                    add column expected_amount numeric(14, 2),
+                    add column frequency       text,
+                    add column interval_months integer,
+                    add column anchor_date     date",
+                "alter table mandate add constraint frequency_check
+                    check (frequency IN ('MONTHLY', 'QUARTERLY', 'YEARLY', 'CUSTOM'))",
+                // a schedule is stored completely or not at all
+                "alter table mandate add constraint schedule_check
+                    check ((expected_amount is null) = (frequency is null)
+                       and (frequency is null) = (anchor_date is null))",
+            ],
+        )
+        .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        execute_all(
+            manager,
+            &[
+                "alter table mandate
+                    drop column expected_amount,
+                    drop column frequency,
+                    drop column interval_months,
+                    drop column anchor_date",
+            ],
+        )
+        .await
+    }
+}
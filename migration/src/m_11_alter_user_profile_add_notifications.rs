@@ -0,0 +1,45 @@
+use sea_orm_migration::prelude::*;
+use sea_orm::DbErr;
+
+use crate::sql::execute_all;
+
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m_11_alter_user_profile_add_notifications"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        execute_all(
+            manager,
+            &[
+                // notifications are opt-in, the address is taken from the token on every save
+                "alter table user_profile
+                    add column email                    text,
+                    add column notify_upcoming_debits   boolean not null default false,
+                    add column notify_debit_alerts      boolean not null default false,
+                    add column notify_profile_reminders boolean not null default false",
+            ],
+        )
+        .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        execute_all(
+            manager,
+            &[
+                "alter table user_profile
+                    drop column email,
+                    drop column notify_upcoming_debits,
+                    drop column notify_debit_alerts,
+                    drop column notify_profile_reminders",
+            ],
+        )
+        .await
+    }
+}
@@ -0,0 +1,43 @@
+use entity::job_run::*;
+use sea_orm_migration::prelude::*;
+use sea_orm::{ConnectionTrait, DbErr, Statement};
+
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m_12_create_table_job_run"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = "create table job_run
+        (
+            id            integer GENERATED BY DEFAULT AS IDENTITY not null primary key,
+            job           text                                     not null,
+            scheduled_for timestamp                                not null,
+            triggered_by  text,
+            status        text                                     not null,
+            started       timestamp                                not null default current_timestamp,
+            finished      timestamp,
+            message       text,
+            constraint job_run_status_check check (status IN ('RUNNING', 'SUCCEEDED', 'FAILED')),
+            constraint job_run_slot_unique unique (job, scheduled_for)
+        )";
+        let index_sql = "create index job_run_job_started_idx on job_run (job, started desc)";
+        for sql in [sql, index_sql] {
+            let stmt = Statement::from_string(manager.get_database_backend(), sql.to_owned());
+            manager.get_connection().execute(stmt).await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(sea_query::Table::drop().table(Entity).to_owned())
+            .await
+    }
+}
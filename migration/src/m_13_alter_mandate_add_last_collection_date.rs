@@ -0,0 +1,45 @@
+use sea_orm_migration::prelude::*;
+use sea_orm::DbErr;
+
+use crate::sql::execute_all;
+
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m_13_alter_mandate_add_last_collection_date"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        execute_all(
+            manager,
+            &[
+                "alter table mandate add column last_collection_date date",
+                // mandates expire 36 months after their last collection, start from the imported debits;
+                // the expiry job leaves the ones without a debit or signature date alone
+                "update mandate m set last_collection_date = (
+                    select max(coalesce(t.value_date, t.booking_date))
+                    from bank_transaction t
+                    where t.mandate_id = m.id and not t.is_reversal)",
+                "alter table user_profile
+                    add column notify_expired_mandates boolean not null default false",
+            ],
+        )
+        .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        execute_all(
+            manager,
+            &[
+                "alter table mandate drop column last_collection_date",
+                "alter table user_profile drop column notify_expired_mandates",
+            ],
+        )
+        .await
+    }
+}
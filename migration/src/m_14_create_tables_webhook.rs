@@ -0,0 +1,64 @@
+use sea_orm_migration::prelude::*;
+use sea_orm::DbErr;
+
+use crate::sql::execute_all;
+
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m_14_create_tables_webhook"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        execute_all(
+            manager,
+            &[
+                "create table webhook_subscription
+                (
+                    id              integer GENERATED BY DEFAULT AS IDENTITY not null primary key,
+                    api_id          uuid                                     not null unique,
+                    user_profile_id integer references user_profile (id)     not null,
+                    url             text                                     not null,
+                    secret          text                                     not null,
+                    events          jsonb                                    not null default '[]',
+                    active          boolean                                  not null default true,
+                    date_created    timestamp                                not null default current_timestamp
+                )",
+                "create table webhook_delivery
+                (
+                    id              integer GENERATED BY DEFAULT AS IDENTITY            not null primary key,
+                    api_id          uuid                                                not null unique,
+                    subscription_id integer references webhook_subscription (id) on delete cascade not null,
+                    event_id        uuid                                                not null,
+                    event           text                                                not null,
+                    payload         jsonb                                               not null,
+                    status          text                                                not null,
+                    attempts        integer                                             not null default 0,
+                    next_attempt    timestamp,
+                    last_attempt    timestamp,
+                    response_status integer,
+                    error           text,
+                    date_created    timestamp                                           not null default current_timestamp,
+                    constraint webhook_delivery_status_check check (status IN ('PENDING', 'DELIVERED', 'FAILED'))
+                )",
+                // the dispatcher only looks at pending deliveries
+                "create index webhook_delivery_pending_idx on webhook_delivery (next_attempt) where status = 'PENDING'",
+                "create index webhook_delivery_subscription_idx on webhook_delivery (subscription_id, date_created desc)",
+            ],
+        )
+        .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        execute_all(
+            manager,
+            &["drop table webhook_delivery", "drop table webhook_subscription"],
+        )
+        .await
+    }
+}
@@ -0,0 +1,52 @@
+use entity::bank_transaction::*;
+use sea_orm_migration::prelude::*;
+use sea_orm::{ConnectionTrait, DbErr, Statement};
+
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m_3_create_table_bank_transaction"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = "create table bank_transaction
+        (
+            id                     integer GENERATED BY DEFAULT AS IDENTITY not null primary key,
+            api_id                 uuid                                     not null unique,
+            user_profile_id        integer references user_profile (id)     not null,
+            mandate_id             integer references mandate (id),
+            source                 text                                     not null,
+            booking_date           date                                     not null,
+            value_date             date,
+            amount                 numeric(14, 2)                           not null,
+            currency               text                                     not null,
+            is_reversal            boolean                                  not null default false,
+            mandate_reference      text,
+            creditor_identifier    text,
+            creditor_name          text,
+            debtor_iban            text,
+            end_to_end_id          text,
+            remittance_information text,
+            fingerprint            text                                     not null,
+            date_imported          timestamp                                not null default current_timestamp,
+            constraint bank_transaction_fingerprint_unique unique (user_profile_id, fingerprint)
+        )";
+        let index_sql = "create index bank_transaction_mandate_idx on bank_transaction (mandate_id)";
+        for sql in [sql, index_sql] {
+            let stmt = Statement::from_string(manager.get_database_backend(), sql.to_owned());
+            manager.get_connection().execute(stmt).await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(sea_query::Table::drop().table(Entity).to_owned())
+            .await
+    }
+}
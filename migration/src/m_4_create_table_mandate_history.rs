@@ -0,0 +1,40 @@
+use entity::mandate_history::*;
+use sea_orm_migration::prelude::*;
+use sea_orm::{ConnectionTrait, DbErr, Statement};
+
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m_4_create_table_mandate_history"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = "create table mandate_history
+        (
+            id                     integer GENERATED BY DEFAULT AS IDENTITY not null primary key,
+            mandate_id             integer references mandate (id)          not null,
+            auth_id                text                                     not null,
+            date_changed           timestamp                                not null default current_timestamp,
+            field                  text                                     not null,
+            old_value              jsonb,
+            new_value              jsonb
+        )";
+        let index_sql = "create index mandate_history_mandate_idx on mandate_history (mandate_id, date_changed)";
+        for sql in [sql, index_sql] {
+            let stmt = Statement::from_string(manager.get_database_backend(), sql.to_owned());
+            manager.get_connection().execute(stmt).await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(sea_query::Table::drop().table(Entity).to_owned())
+            .await
+    }
+}
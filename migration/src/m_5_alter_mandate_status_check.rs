@@ -0,0 +1,42 @@
+use sea_orm_migration::prelude::*;
+use sea_orm::DbErr;
+
+use crate::sql::execute_all;
+
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m_5_alter_mandate_status_check"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        execute_all(
+            manager,
+            &[
+                "alter table mandate drop constraint status_check",
+                "alter table mandate add constraint status_check
+                    check (status IN ('NEW', 'ACTIVE', 'SUSPENDED', 'CANCELED', 'EXPIRED', 'DELETED'))",
+            ],
+        )
+        .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        execute_all(
+            manager,
+            &[
+                "alter table mandate drop constraint status_check",
+                "update mandate set status = 'ACTIVE' where status in ('NEW', 'SUSPENDED')",
+                "update mandate set status = 'CANCELED' where status = 'EXPIRED'",
+                "alter table mandate add constraint status_check
+                    check (status IN ('ACTIVE', 'DELETED', 'CANCELED'))",
+            ],
+        )
+        .await
+    }
+}
@@ -0,0 +1,54 @@
+use sea_orm_migration::prelude::*;
+use sea_orm::DbErr;
+
+use crate::sql::execute_all;
+
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m_6_create_mandate_search_indexes"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        execute_all(
+            manager,
+            &[
+                // ILIKE '%...%' can only use trigram indexes. Creating the extension needs a
+                // superuser or, from PostgreSQL 13 on, the CREATE privilege on the database as
+                // pg_trgm is trusted. Otherwise run this statement once as a superuser first.
+                "create extension if not exists pg_trgm",
+                "create index mandate_user_profile_date_created_idx
+                    on mandate (user_profile_id, date_created, id)",
+                "create index mandate_tags_idx on mandate using gin (tags jsonb_path_ops)",
+                "create index mandate_iban_idx on mandate ((bank_account ->> 'iban'))",
+                "create index mandate_creditor_name_trgm_idx
+                    on mandate using gin ((creditor ->> 'name') gin_trgm_ops)",
+                "create index mandate_display_name_trgm_idx
+                    on mandate using gin (display_name gin_trgm_ops)",
+                "create index mandate_unique_reference_trgm_idx
+                    on mandate using gin (unique_reference gin_trgm_ops)",
+            ],
+        )
+        .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        execute_all(
+            manager,
+            &[
+                "drop index mandate_unique_reference_trgm_idx",
+                "drop index mandate_display_name_trgm_idx",
+                "drop index mandate_creditor_name_trgm_idx",
+                "drop index mandate_iban_idx",
+                "drop index mandate_tags_idx",
+                "drop index mandate_user_profile_date_created_idx",
+            ],
+        )
+        .await
+    }
+}
@@ -0,0 +1,51 @@
+use sea_orm_migration::prelude::*;
+use sea_orm::DbErr;
+
+use crate::sql::execute_all;
+
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m_7_alter_mandate_tags_not_null"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        execute_all(
+            manager,
+            &[
+                "update mandate set tags = '[]'::jsonb where tags is null or jsonb_typeof(tags) <> 'array'",
+                // tags are stored normalized like `normalize_tags` does: trimmed, lower case, without
+                // empty ones and duplicates, in their original order
+                "update mandate m set tags = coalesce((
+                    select jsonb_agg(n.tag order by n.position)
+                    from (
+                        select distinct on (lower(trim(t.tag))) lower(trim(t.tag)) as tag, t.position
+                        from jsonb_array_elements_text(m.tags) with ordinality as t(tag, position)
+                        where trim(t.tag) <> ''
+                        order by lower(trim(t.tag)), t.position
+                    ) n), '[]'::jsonb)",
+                "alter table mandate alter column tags set default '[]'::jsonb",
+                "alter table mandate alter column tags set not null",
+                "alter table mandate add constraint tags_check check (jsonb_typeof(tags) = 'array')",
+            ],
+        )
+        .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        execute_all(
+            manager,
+            &[
+                "alter table mandate drop constraint tags_check",
+                "alter table mandate alter column tags drop not null",
+                "alter table mandate alter column tags drop default",
+            ],
+        )
+        .await
+    }
+}
@@ -0,0 +1,84 @@
+use sea_orm_migration::prelude::*;
+use sea_orm::DbErr;
+
+use crate::sql::execute_all;
+
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m_8_create_table_bank_account"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        execute_all(
+            manager,
+            &[
+                "create table bank_account
+                (
+                    id                     integer GENERATED BY DEFAULT AS IDENTITY not null primary key,
+                    api_id                 uuid                                     not null unique,
+                    user_profile_id        integer references user_profile (id)     not null,
+                    holder_name            text                                     not null,
+                    nickname               text,
+                    institution            text                                     not null,
+                    iban                   text                                     not null,
+                    bic                    text,
+                    is_primary             boolean                                  not null default false,
+                    date_created           timestamp                                not null default current_timestamp,
+                    constraint bank_account_iban_unique unique (user_profile_id, iban)
+                )",
+                "create unique index bank_account_primary_idx on bank_account (user_profile_id) where is_primary",
+                // one account per IBAN of the user, the most recently created mandate has the latest copy;
+                // mandates without an IBAN share an account with an empty one, so every mandate gets one
+                "insert into bank_account (api_id, user_profile_id, holder_name, institution, iban, bic)
+                select gen_random_uuid(), a.user_profile_id, a.holder_name, a.institution, a.iban, a.bic
+                from (
+                    select distinct on (m.user_profile_id, upper(replace(coalesce(m.bank_account ->> 'iban', ''), ' ', '')))
+                        m.user_profile_id,
+                        trim(coalesce(p.firstname, '') || ' ' || coalesce(p.lastname, '')) as holder_name,
+                        coalesce(m.bank_account ->> 'institution', '')                      as institution,
+                        upper(replace(coalesce(m.bank_account ->> 'iban', ''), ' ', ''))    as iban,
+                        nullif(upper(trim(m.bank_account ->> 'bic')), '')                   as bic
+                    from mandate m
+                    join user_profile p on p.id = m.user_profile_id
+                    order by m.user_profile_id, upper(replace(coalesce(m.bank_account ->> 'iban', ''), ' ', '')), m.date_created desc
+                ) a",
+                "update bank_account b set is_primary = true
+                where b.id = (select o.id from bank_account o where o.user_profile_id = b.user_profile_id
+                              order by o.iban = '', o.id limit 1)",
+                "alter table mandate add column bank_account_id integer references bank_account (id)",
+                "update mandate m set bank_account_id = b.id
+                from bank_account b
+                where b.user_profile_id = m.user_profile_id
+                  and b.iban = upper(replace(coalesce(m.bank_account ->> 'iban', ''), ' ', ''))",
+                "alter table mandate alter column bank_account_id set not null",
+                "alter table mandate drop column bank_account",
+                "create index mandate_bank_account_idx on mandate (bank_account_id)",
+            ],
+        )
+        .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        execute_all(
+            manager,
+            &[
+                "alter table mandate add column bank_account jsonb",
+                "update mandate m set bank_account = jsonb_strip_nulls(jsonb_build_object(
+                    'institution', b.institution, 'iban', b.iban, 'bic', b.bic))
+                from bank_account b
+                where b.id = m.bank_account_id",
+                "alter table mandate alter column bank_account set not null",
+                "alter table mandate drop column bank_account_id",
+                "create index mandate_iban_idx on mandate ((bank_account ->> 'iban'))",
+                "drop table bank_account",
+            ],
+        )
+        .await
+    }
+}
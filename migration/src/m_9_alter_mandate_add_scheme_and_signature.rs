@@ -0,0 +1,51 @@
+use sea_orm_migration::prelude::*;
+use sea_orm::DbErr;
+
+use crate::sql::execute_all;
+
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m_9_alter_mandate_add_scheme_and_signature"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        execute_all(
+            manager,
+            &[
+                // existing mandates are recurrent CORE mandates, their signature date is unknown and
+                // stays empty, the expiry job leaves such mandates alone until they are collected under
+                "alter table mandate
+                    add column scheme          text not null default 'CORE',
+                    add column payment_type    text not null default 'RECURRENT',
+                    add column signature_date  date,
+                    add column signature_place text,
+                    add column account_holder  text",
+                "alter table mandate add constraint scheme_check check (scheme IN ('CORE', 'B2B'))",
+                "alter table mandate add constraint payment_type_check
+                    check (payment_type IN ('RECURRENT', 'ONE_OFF'))",
+            ],
+        )
+        .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        execute_all(
+            manager,
+            &[
+                "alter table mandate
+                    drop column scheme,
+                    drop column payment_type,
+                    drop column signature_date,
+                    drop column signature_place,
+                    drop column account_holder",
+            ],
+        )
+        .await
+    }
+}
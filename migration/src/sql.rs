@@ -0,0 +1,11 @@
+use sea_orm_migration::prelude::*;
+use sea_orm::{ConnectionTrait, DbErr, Statement};
+
+/// Runs the raw SQL statements in order, stopping at the first failing one.
+pub(crate) async fn execute_all(manager: &SchemaManager<'_>, sqls: &[&str]) -> Result<(), DbErr> {
+    for sql in sqls {
+        let stmt = Statement::from_string(manager.get_database_backend(), sql.to_string());
+        manager.get_connection().execute(stmt).await?;
+    }
+    Ok(())
+}
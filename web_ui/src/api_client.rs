@@ -1,10 +1,54 @@
-use api_models::models::{UserProfile, Mandate};
+use api_models::models::{
+    AccountSwitch, AccountSwitchResult, BankAccount, ClientConfig, DebitDeadline, Forecast,
+    ForecastQuery, LetterKind, LetterQuery, Mandate, MandateChange, MandatePage, MandateQuery,
+    Problem, TagCount, UserProfile,
+};
+use uuid::Uuid;
 use seed::{prelude::*, *};
 
 use crate::{User, AuthError};
 
 const API_URL_MANDATES: &str = "/api/mandates";
 const API_URL_PROFILE: &str = "/api/profile";
+const API_URL_CONFIG: &str = "/api/config";
+const API_URL_TAGS: &str = "/api/tags";
+const API_URL_ACCOUNTS: &str = "/api/accounts";
+const API_URL_FORECAST: &str = "/api/forecast";
+const API_URL_DEADLINES: &str = "/api/deadlines";
+
+/// Failed API call, with the problem details (RFC 7807) if the backend sent them.
+#[derive(Debug)]
+pub enum ApiError {
+    Fetch(fetch::FetchError),
+    Problem(Problem),
+}
+
+impl ApiError {
+    pub fn problem(&self) -> Option<&Problem> {
+        match self {
+            ApiError::Problem(problem) => Some(problem),
+            ApiError::Fetch(_) => None,
+        }
+    }
+}
+
+impl From<fetch::FetchError> for ApiError {
+    fn from(error: fetch::FetchError) -> Self {
+        ApiError::Fetch(error)
+    }
+}
+
+/// Like `check_status`, but keeps the problem details of a failed response.
+async fn check_problem(response: Response) -> Result<Response, ApiError> {
+    let status = response.status();
+    if status.is_ok() {
+        return Ok(response);
+    }
+    match response.json::<Problem>().await {
+        Ok(problem) => Err(ApiError::Problem(problem)),
+        Err(_) => Err(ApiError::Fetch(fetch::FetchError::StatusError(status))),
+    }
+}
 
 // ------ ------
 //     API calls
@@ -13,11 +57,7 @@ const API_URL_PROFILE: &str = "/api/profile";
 #[wasm_bindgen]
 extern "C" {
     #[wasm_bindgen(catch)]
-    pub async fn init_auth(
-        domain: String,
-        client_id: String,
-        audience: String,
-    ) -> Result<JsValue, JsValue>;
+    pub async fn init_auth(config: JsValue) -> Result<JsValue, JsValue>;
 
     #[wasm_bindgen(catch)]
     pub async fn redirect_to_sign_up() -> Result<(), JsValue>;
@@ -31,6 +71,9 @@ extern "C" {
     #[wasm_bindgen(catch)]
     pub async fn getTokenSilently() -> Result<JsValue, JsValue>;
 
+    #[wasm_bindgen(catch)]
+    pub async fn download_file(url: &str, token: &str) -> Result<JsValue, JsValue>;
+
 }
 
 pub async fn get_token() -> fetch::Result<String> {
@@ -40,11 +83,29 @@ pub async fn get_token() -> fetch::Result<String> {
     }
 }
 
+/// OpenID Connect settings of the backend, needed before anybody can sign in.
+pub async fn get_client_config() -> fetch::Result<ClientConfig> {
+    Request::new(API_URL_CONFIG)
+        .method(Method::Get)
+        .header(Header::custom("Accept", "application/json"))
+        .fetch()
+        .await?
+        .check_status()?
+        .json::<ClientConfig>()
+        .await
+}
+
 pub async fn get_auth_user() -> Result<User, AuthError> {
-    let domain = String::from("dev-jecc6018.us.auth0.com");
-    let client_id = String::from("1FE1XMCrbIeY80inOYaq3QLJDbGE26BW");
-    let audience = String::from("http://mysepa-backend");   
-    let js_user = init_auth(domain, client_id, audience).await;
+    let config = match get_client_config().await {
+        Ok(config) => config,
+        Err(error) => {
+            log!("Auth configuration not available", error);
+            return Err(AuthError::ConfigurationError);
+        }
+    };
+    let config =
+        serde_wasm_bindgen::to_value(&config).map_err(|_| AuthError::ConfigurationError)?;
+    let js_user = init_auth(config).await;
     log!("Auth JSValue {}", js_user);
     if js_user.is_err() {
         return Err(AuthError::ConfigurationError);
@@ -74,17 +135,16 @@ pub async fn is_profile_created() -> fetch::Result<Status> {
 }
 
 
-pub async fn save_profile(user_profile: UserProfile) -> fetch::Result<Status> {
-    Ok(Request::new(API_URL_PROFILE)
+pub async fn save_profile(user_profile: UserProfile) -> Result<Status, ApiError> {
+    let response = Request::new(API_URL_PROFILE)
         .method(Method::Post)
         .header(Header::custom("Accept", "application/json"))
         .header(Header::content_type("application/json"))
         .header(Header::bearer(get_token().await?))
         .json(&user_profile)?
         .fetch()
-        .await?
-        .check_status()?
-        .status())
+        .await?;
+    Ok(check_problem(response).await?.status())
 }
 pub async fn get_user_profile() -> fetch::Result<UserProfile> {
     Request::new(API_URL_PROFILE)
@@ -100,35 +160,143 @@ pub async fn get_user_profile() -> fetch::Result<UserProfile> {
 }
 
 
-pub async fn request_mandates() -> fetch::Result<Vec<Mandate>> {
-    match getTokenSilently().await {
-        Ok(token) => {
-            Request::new(API_URL_MANDATES)
-                .method(Method::Get)
-                .header(Header::custom("Accept", "application/json"))
-                .header(Header::content_type("application/json"))
-                .header(Header::bearer(token.as_string().unwrap()))
-                .fetch()
-                .await?
-                .check_status()?
-                .json::<Vec<Mandate>>()
-                .await
-        }
-        Err(err) => Err(fetch::FetchError::NetworkError(err)),
-    }
+pub async fn request_mandates(query: MandateQuery) -> fetch::Result<MandatePage> {
+    let query_string = serde_urlencoded::to_string(&query).unwrap_or_default();
+    Request::new(format!("{}?{}", API_URL_MANDATES, query_string))
+        .method(Method::Get)
+        .header(Header::custom("Accept", "application/json"))
+        .header(Header::bearer(get_token().await?))
+        .fetch()
+        .await?
+        .check_status()?
+        .json::<MandatePage>()
+        .await
 }
 
-pub async fn save_selected_mandate(mandate: Mandate) -> fetch::Result<Response> {
-    match getTokenSilently().await {
-        Ok(token) => Request::new(API_URL_MANDATES)
-            .method(Method::Post)
-            .header(Header::custom("Accept", "application/json"))
-            .header(Header::content_type("application/json"))
-            .header(Header::bearer(token.as_string().unwrap()))
-            .json(&mandate)?
-            .fetch()
-            .await?
-            .check_status(),
-        Err(err) => Err(fetch::FetchError::NetworkError(err)),
-    }
+pub async fn save_selected_mandate(mandate: Mandate) -> Result<Response, ApiError> {
+    let response = Request::new(format!("{}/{}", API_URL_MANDATES, mandate.api_id))
+        .method(Method::Put)
+        .header(Header::custom("Accept", "application/json"))
+        .header(Header::content_type("application/json"))
+        .header(Header::bearer(get_token().await?))
+        .json(&mandate)?
+        .fetch()
+        .await?;
+    check_problem(response).await
+}
+
+pub async fn delete_mandate(api_id: Uuid) -> Result<Response, ApiError> {
+    let response = Request::new(format!("{}/{}", API_URL_MANDATES, api_id))
+        .method(Method::Delete)
+        .header(Header::custom("Accept", "application/json"))
+        .header(Header::bearer(get_token().await?))
+        .fetch()
+        .await?;
+    check_problem(response).await
+}
+
+pub async fn request_mandate_history(api_id: Uuid) -> fetch::Result<Vec<MandateChange>> {
+    Request::new(format!("{}/{}/history", API_URL_MANDATES, api_id))
+        .method(Method::Get)
+        .header(Header::bearer(get_token().await?))
+        .header(Header::custom("Accept", "application/json"))
+        .fetch()
+        .await?
+        .check_status()?
+        .json::<Vec<MandateChange>>()
+        .await
+}
+
+pub async fn request_tags() -> fetch::Result<Vec<TagCount>> {
+    Request::new(API_URL_TAGS)
+        .method(Method::Get)
+        .header(Header::bearer(get_token().await?))
+        .header(Header::custom("Accept", "application/json"))
+        .fetch()
+        .await?
+        .check_status()?
+        .json::<Vec<TagCount>>()
+        .await
+}
+
+pub async fn request_bank_accounts() -> fetch::Result<Vec<BankAccount>> {
+    Request::new(API_URL_ACCOUNTS)
+        .method(Method::Get)
+        .header(Header::bearer(get_token().await?))
+        .header(Header::custom("Accept", "application/json"))
+        .fetch()
+        .await?
+        .check_status()?
+        .json::<Vec<BankAccount>>()
+        .await
+}
+
+pub async fn request_forecast(query: ForecastQuery) -> fetch::Result<Forecast> {
+    let query_string = serde_urlencoded::to_string(&query).unwrap_or_default();
+    Request::new(format!("{}?{}", API_URL_FORECAST, query_string))
+        .method(Method::Get)
+        .header(Header::bearer(get_token().await?))
+        .header(Header::custom("Accept", "application/json"))
+        .fetch()
+        .await?
+        .check_status()?
+        .json::<Forecast>()
+        .await
+}
+
+pub async fn request_deadlines() -> fetch::Result<Vec<DebitDeadline>> {
+    Request::new(API_URL_DEADLINES)
+        .method(Method::Get)
+        .header(Header::bearer(get_token().await?))
+        .header(Header::custom("Accept", "application/json"))
+        .fetch()
+        .await?
+        .check_status()?
+        .json::<Vec<DebitDeadline>>()
+        .await
+}
+
+/// Moves the active mandates to another account, answers which creditors to notify.
+pub async fn switch_bank_account(switch: AccountSwitch) -> Result<AccountSwitchResult, ApiError> {
+    let response = Request::new(format!("{}/switch", API_URL_ACCOUNTS))
+        .method(Method::Post)
+        .header(Header::custom("Accept", "application/json"))
+        .header(Header::content_type("application/json"))
+        .header(Header::bearer(get_token().await?))
+        .json(&switch)?
+        .fetch()
+        .await?;
+    Ok(check_problem(response)
+        .await?
+        .json::<AccountSwitchResult>()
+        .await?)
+}
+
+/// Lets the browser save one of the letters to the creditor of a mandate.
+pub async fn download_letter(
+    api_id: Uuid,
+    kind: LetterKind,
+    query: LetterQuery,
+) -> fetch::Result<()> {
+    let kind: &'static str = kind.into();
+    let url = format!(
+        "{}/{}/letters/{}?{}",
+        API_URL_MANDATES,
+        api_id,
+        kind,
+        serde_urlencoded::to_string(&query).unwrap_or_default()
+    );
+    download_file(&url, &get_token().await?)
+        .await
+        .map(|_| ())
+        .map_err(fetch::FetchError::NetworkError)
+}
+
+/// Pre-filled mandate form PDF, saved like a letter.
+pub async fn download_mandate_form(api_id: Uuid) -> fetch::Result<()> {
+    let url = format!("{}/{}/form.pdf", API_URL_MANDATES, api_id);
+    download_file(&url, &get_token().await?)
+        .await
+        .map(|_| ())
+        .map_err(fetch::FetchError::NetworkError)
 }
@@ -1,5 +1,6 @@
 #![allow(clippy::wildcard_imports)]
 
+use api_models::models::{DebitDeadline, Forecast, ForecastQuery};
 use page::{sepa_management, user_profile};
 use seed::{prelude::*, *};
 use serde::{Deserialize, Serialize};
@@ -15,7 +16,9 @@ const USER_PROFILE: &str = "user-profile";
 //     Model
 // ------ ------
 
-#[derive(Deserialize, Serialize, Clone, Debug)]
+/// Claims of the ID token, only `sub` is guaranteed by every OpenID Connect provider.
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+#[serde(default)]
 pub struct User {
     nickname: String,
     name: String,
@@ -33,6 +36,10 @@ pub struct Model {
     page: Page,
     menu_visible: bool,
     remote_call_in_progress: bool,
+    /// Upcoming debits shown on the home page.
+    forecast: Option<Forecast>,
+    /// Refundable debits and debits collected without a valid mandate.
+    deadlines: Vec<DebitDeadline>,
 }
 
 // ------ Page ------
@@ -81,6 +88,8 @@ enum Msg {
     IsProfileExistsFetched(fetch::Result<Status>),
     RedirectingToSignUp(Result<(), JsValue>),
     RedirectingToLogIn(Result<(), JsValue>),
+    ForecastFetched(fetch::Result<Forecast>),
+    DeadlinesFetched(fetch::Result<Vec<DebitDeadline>>),
     // ------ pages ------
     SepaManagement(sepa_management::Msg),
     UserProfile(user_profile::Msg),
@@ -114,6 +123,8 @@ fn init(url: Url, orders: &mut impl Orders<Msg>) -> Model {
         menu_visible: false,
         remote_call_in_progress: true,
         is_profile_created: false,
+        forecast: None,
+        deadlines: Vec::new(),
     }
 }
 // ------ ------
@@ -122,7 +133,12 @@ fn init(url: Url, orders: &mut impl Orders<Msg>) -> Model {
 fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
     log!("lib.rs - update {}", msg.to_string());
     match msg {
-        Msg::UrlChanged(subs::UrlChanged(url)) => model.page = Page::init(url, orders),
+        Msg::UrlChanged(subs::UrlChanged(url)) => {
+            model.page = Page::init(url, orders);
+            if matches!(model.page, Page::Home) && model.is_profile_created {
+                fetch_home_data(orders);
+            }
+        }
         Msg::ToggleMenu => model.menu_visible = not(model.menu_visible),
         Msg::HideMenu => {
             if model.menu_visible {
@@ -180,6 +196,17 @@ fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
                 Ok(st) => model.is_profile_created = st.is_ok(),
                 Err(_) => model.is_profile_created = false,
             }
+            if model.is_profile_created {
+                fetch_home_data(orders);
+            }
+        }
+        Msg::ForecastFetched(Ok(forecast)) => model.forecast = Some(forecast),
+        Msg::ForecastFetched(Err(error)) => {
+            log!("Forecast fetch failed", error);
+        }
+        Msg::DeadlinesFetched(Ok(deadlines)) => model.deadlines = deadlines,
+        Msg::DeadlinesFetched(Err(error)) => {
+            log!("Deadlines fetch failed", error);
         }
 
         Msg::SepaManagement(msg) => {
@@ -195,6 +222,14 @@ fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
     }
 }
 
+/// Upcoming debits and the debits that need action, shown on the home page.
+fn fetch_home_data(orders: &mut impl Orders<Msg>) {
+    orders.perform_cmd(async {
+        Msg::ForecastFetched(api_client::request_forecast(ForecastQuery::default()).await)
+    });
+    orders.perform_cmd(async { Msg::DeadlinesFetched(api_client::request_deadlines().await) });
+}
+
 // ------ ------
 //     View
 // ------ ------
@@ -328,7 +363,7 @@ fn view_navbar_menu_end(base_url: &Url, user: Option<&User>) -> Node<Msg> {
         C!["navbar-end"],
         div![
             C!["navbar-item"],
-            div![if let Some(user) = user {
+            div![if let Some(user) = user.filter(|u| !u.picture.is_empty()) {
                 img![attrs! {
                     At::Src => user.picture
                 }]
@@ -1,4 +1,4 @@
-use api_models::validator::Validate;
+use api_models::{models::Problem, validator::Validate};
 use seed::{prelude::*, *};
 
 pub mod anonimous;
@@ -20,3 +20,24 @@ fn view_validation_icon<Ms>(val: &impl Validate, key: &str) -> Node<Ms> {
 
     span![C!["icon", "is-small", "is-right"], i![C!["fas", class]]]
 }
+
+/// Messages the backend reported for one field of the submitted form.
+fn view_field_errors<Ms>(problem: Option<&Problem>, field: &str) -> Vec<Node<Ms>> {
+    problem
+        .map(|p| p.field_errors(field))
+        .unwrap_or_default()
+        .iter()
+        .map(|message| p![C!["help", "is-danger"], message])
+        .collect()
+}
+
+fn view_problem<Ms>(problem: Option<&Problem>) -> Node<Ms> {
+    match problem {
+        Some(problem) => div![
+            C!["notification", "is-danger", "is-light"],
+            strong![&problem.title],
+            problem.detail.as_ref().map(|detail| p![detail]),
+        ],
+        None => empty![],
+    }
+}
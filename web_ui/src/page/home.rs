@@ -1,9 +1,16 @@
-use crate::Model;
+use api_models::models::{DebitAlert, DebitDeadline, Forecast};
+use rust_decimal::Decimal;
 use seed::{prelude::*, *};
 
+use crate::Model;
+
 pub fn view<Ms>(model: &Model) -> Node<Ms> {
     match &model.user {
-        Some(user) => view_user(user),
+        Some(user) => div![
+            view_user(user),
+            view_action_required(&model.deadlines),
+            view_forecast(model.forecast.as_ref())
+        ],
         None => view_anonymous(),
     }
 }
@@ -38,3 +45,96 @@ fn view_user<Ms>(user: &crate::User) -> Node<Ms> {
         ]
     ]
 }
+
+/// Debits collected after the end of their mandate, then debits that can still be refunded.
+fn view_action_required<Ms>(deadlines: &[DebitDeadline]) -> Node<Ms> {
+    let open: Vec<&DebitDeadline> = deadlines
+        .iter()
+        .filter(|d| d.alert.is_some() || d.refundable)
+        .collect();
+    if open.is_empty() {
+        return empty![];
+    }
+    div![
+        C!["box", "ml-6"],
+        h2![C!["subtitle"], "Action required"],
+        table![
+            C!["table", "is-fullwidth", "is-striped"],
+            thead![tr![
+                th!["Mandate"],
+                th!["Debited"],
+                th![C!["has-text-right"], "Amount"],
+                th![""],
+                th!["Deadline"],
+            ]],
+            tbody![open.iter().map(|d| tr![
+                td![&d.display_name],
+                td![d.debit_date.format("%d.%m.%Y").to_string()],
+                td![
+                    C!["has-text-right"],
+                    format!("{:.2} {}", d.transaction.amount.abs(), d.transaction.currency)
+                ],
+                td![match d.alert {
+                    Some(DebitAlert::CollectedAfterCancellation) => {
+                        span![C!["tag", "is-danger"], "Collected after cancellation"]
+                    }
+                    Some(DebitAlert::CollectedAfterDeletion) => {
+                        span![C!["tag", "is-danger"], "Collected after deletion"]
+                    }
+                    None => span![C!["tag", "is-info"], "Refundable"],
+                }],
+                // a refund is quicker than disputing the debit as unauthorised
+                td![match d.refund_until.filter(|_| d.refundable) {
+                    Some(until) => format!("Refund until {}", until.format("%d.%m.%Y")),
+                    None => format!(
+                        "Dispute until {}",
+                        d.unauthorised_until.format("%d.%m.%Y")
+                    ),
+                }],
+            ])],
+        ]
+    ]
+}
+
+/// Expected debits per month and account, to plan the cash on the debited accounts.
+fn view_forecast<Ms>(forecast: Option<&Forecast>) -> Node<Ms> {
+    let forecast = match forecast {
+        Some(forecast) => forecast,
+        None => return empty![],
+    };
+    let total: Decimal = forecast.totals.iter().map(|t| t.total).sum();
+    div![
+        C!["box", "ml-6"],
+        h2![
+            C!["subtitle"],
+            format!(
+                "Upcoming debits until {}",
+                forecast.until.format("%d.%m.%Y")
+            )
+        ],
+        if forecast.totals.is_empty() {
+            p!["No debits expected. Enter the expected amount of your mandates to plan ahead."]
+        } else {
+            table![
+                C!["table", "is-fullwidth", "is-striped"],
+                thead![tr![
+                    th!["Month"],
+                    th!["Account"],
+                    th![C!["has-text-right"], "Debits"],
+                    th![C!["has-text-right"], "Total"],
+                ]],
+                tbody![forecast.totals.iter().map(|t| tr![
+                    td![t.month.format("%B %Y").to_string()],
+                    td![&t.iban],
+                    td![C!["has-text-right"], t.debits.to_string()],
+                    td![C!["has-text-right"], format!("{:.2} EUR", t.total)],
+                ])],
+                tfoot![tr![
+                    th![attrs! {At::ColSpan => "2"}, "Total"],
+                    th![C!["has-text-right"], forecast.debits.len().to_string()],
+                    th![C!["has-text-right"], format!("{:.2} EUR", total)],
+                ]],
+            ]
+        }
+    ]
+}
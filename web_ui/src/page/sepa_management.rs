@@ -1,10 +1,21 @@
-use crate::{page::view_validation_icon, api_client};
+use crate::{
+    api_client::{self, ApiError},
+    page::{view_field_errors, view_problem, view_validation_icon},
+};
 
 use api_models::{
-    models::{BankAccount, Mandate, Status},
+    models::{
+        mandate::{usable_until, validate_scheme},
+        tag::{normalize_tag, validate_tag},
+        AccountSwitch, AccountSwitchResult, BankAccount, CollectionSchedule, CreditorIdentifier,
+        Frequency, Iban, LetterFormat, LetterKind, LetterQuery, Mandate, MandateChange, MandatePage,
+        MandateQuery, PaymentType, Problem, Scheme, Status, TagCount,
+    },
     validator::Validate,
 };
+use rust_decimal::Decimal;
 use seed::{prelude::*, *};
+use std::str::FromStr;
 use uuid::Uuid;
 
 // ------ ------~
@@ -17,23 +28,43 @@ pub enum Confirmation {
     ConfirmedNo,
 }
 
+/// Choices of the status filter, in lifecycle order.
+const STATUS_FILTER: [Status; 6] = [
+    Status::NEW,
+    Status::ACTIVE,
+    Status::SUSPENDED,
+    Status::CANCELED,
+    Status::EXPIRED,
+    Status::DELETED,
+];
+
 pub struct Model {
     mandates: Vec<Mandate>,
+    query: MandateQuery,
+    next_cursor: Option<Uuid>,
+    bank_accounts: Vec<BankAccount>,
+    /// All tags of the user, suggested by the tag editor.
+    tags: Vec<TagCount>,
+    tag_input: String,
     selected_mandate: Option<Mandate>,
+    history: Vec<MandateChange>,
+    problem: Option<Problem>,
+    /// Letter offered after a status change, e.g. the revocation of a canceled mandate.
+    letter_offer: Option<(Uuid, LetterKind)>,
+    /// The open account switch assistant.
+    account_switch: Option<AccountSwitch>,
+    account_switch_result: Option<AccountSwitchResult>,
     unsaved_changes_confirmation: Option<Confirmation>,
     remote_call_in_progress: bool,
 }
 impl Model {
-    fn get_bank_accounts(&self) -> Vec<BankAccount> {
-        let mut all_bank_accounts: Vec<BankAccount> = self
-            .mandates
+    /// The stored account with the `api_id` of a select option.
+    fn find_bank_account(&self, value: &str) -> Option<&BankAccount> {
+        self.bank_accounts
             .iter()
-            .map(|m| m.bank_account.clone())
-            .collect();
-        all_bank_accounts.sort();
-        all_bank_accounts.dedup();
-        all_bank_accounts
+            .find(|a| a.api_id.map(|id| id.to_string()).as_deref() == Some(value))
     }
+
     fn find_mandate_by_id(&self, id: Uuid) -> Option<&Mandate> {
         self.mandates.iter().find(|m| m.api_id == id)
     }
@@ -46,12 +77,29 @@ impl Model {
             false
         }
     }
+
+    /// The schedule of the edited mandate, if it has one.
+    fn selected_schedule_mut(&mut self) -> Option<&mut CollectionSchedule> {
+        self.selected_mandate
+            .as_mut()
+            .and_then(|sm| sm.schedule.as_mut())
+    }
 }
 
 #[derive(Debug)]
 pub enum Msg {
-    MandatesFetched(fetch::Result<Vec<Mandate>>),
+    MandatesFetched(MandateQuery, fetch::Result<MandatePage>),
+    SearchChanged(String),
+    StatusFilterChanged(String),
+    LoadMoreMandates,
+    TagsFetched(fetch::Result<Vec<TagCount>>),
+    BankAccountsFetched(fetch::Result<Vec<BankAccount>>),
+    TagFilterChanged(String),
+    TagInputChanged(String),
+    AddTag,
+    RemoveTag(String),
     MandateItemSelected(Uuid),
+    HistoryFetched(Uuid, fetch::Result<Vec<MandateChange>>),
     DisplayNameChanged(String),
     MandateReferenceChanged(String),
     BankAccountChanged(String),
@@ -61,13 +109,36 @@ pub enum Msg {
     CreditorHouseNoChanged(String),
     CreditorZipChanged(String),
     CreditorPlaceChanged(String),
+    SchemeChanged(String),
+    PaymentTypeChanged(String),
+    SignatureDateChanged(String),
+    LastCollectionDateChanged(String),
+    SignaturePlaceChanged(String),
+    AccountHolderChanged(String),
+    FrequencyChanged(String),
+    ExpectedAmountChanged(String),
+    IntervalMonthsChanged(String),
+    AnchorDateChanged(String),
     SaveSelectedMandate(Status),
-    SelectedMandateSaved(Mandate, fetch::Result<Response>),
+    SelectedMandateSaved(Mandate, Result<Response, ApiError>),
     DebtorBankAccountInstitutionChanged(String),
     DebtorBankAccountIbanChanged(String),
     DebtorBankAccountBicChanged(String),
     NewMandateClicked,
     ConfirmUnsavedChanges(Confirmation),
+    AccountSwitchClicked,
+    SwitchFromChanged(String),
+    SwitchToChanged(String),
+    SwitchToInstitutionChanged(String),
+    SwitchToIbanChanged(String),
+    SwitchToBicChanged(String),
+    SwitchAccount,
+    AccountSwitched(Result<AccountSwitchResult, ApiError>),
+    CloseAccountSwitch,
+    DownloadLetter(Uuid, LetterKind, LetterFormat),
+    LetterDownloaded(fetch::Result<()>),
+    DownloadMandateForm(Uuid),
+    DismissLetterOffer,
 }
 
 //--------
@@ -76,24 +147,110 @@ pub enum Msg {
 
 pub fn init(_url: Url, orders: &mut impl Orders<Msg>) -> Model {
     log!("Init manage");
-    orders.perform_cmd(async { Msg::MandatesFetched(api_client::request_mandates().await) });
+    fetch_mandates(MandateQuery::default(), orders);
+    orders.perform_cmd(async { Msg::TagsFetched(api_client::request_tags().await) });
+    orders.perform_cmd(async {
+        Msg::BankAccountsFetched(api_client::request_bank_accounts().await)
+    });
     Model {
         mandates: Vec::new(),
+        query: MandateQuery::default(),
+        next_cursor: None,
+        bank_accounts: Vec::new(),
+        tags: Vec::new(),
+        tag_input: String::new(),
         selected_mandate: None,
+        history: Vec::new(),
+        problem: None,
+        letter_offer: None,
+        account_switch: None,
+        account_switch_result: None,
         remote_call_in_progress: true,
         unsaved_changes_confirmation: None,
     }
 }
 
+fn fetch_mandates(query: MandateQuery, orders: &mut impl Orders<Msg>) {
+    orders.perform_cmd(async move {
+        Msg::MandatesFetched(query.clone(), api_client::request_mandates(query).await)
+    });
+}
+
 // ------ ------
 //     Update
 // ------ ------
 pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
     log!("Update manage", msg);
     match msg {
-        Msg::MandatesFetched(result) => {
+        Msg::MandatesFetched(query, result) => {
+            // answer to a search that has been changed since
+            let first_page = MandateQuery {
+                cursor: None,
+                ..query.clone()
+            };
+            if first_page != model.query {
+                return;
+            }
             model.remote_call_in_progress = false;
-            model.mandates = result.map_or(Vec::new(), |r| r)
+            let page = result.unwrap_or_default();
+            if query.cursor.is_none() {
+                model.mandates = page.items;
+            } else {
+                model.mandates.extend(page.items);
+            }
+            model.next_cursor = page.next_cursor;
+        }
+
+        Msg::SearchChanged(value) => {
+            model.query.q = Some(value).filter(|q| !q.trim().is_empty());
+            model.remote_call_in_progress = true;
+            fetch_mandates(model.query.clone(), orders);
+        }
+
+        Msg::StatusFilterChanged(value) => {
+            model.query.status = value.parse::<Status>().ok();
+            model.remote_call_in_progress = true;
+            fetch_mandates(model.query.clone(), orders);
+        }
+
+        Msg::LoadMoreMandates => {
+            if let Some(cursor) = model.next_cursor {
+                fetch_mandates(model.query.next_page(cursor), orders);
+            }
+        }
+
+        Msg::TagsFetched(result) => {
+            model.tags = result.unwrap_or_default();
+        }
+
+        Msg::BankAccountsFetched(result) => {
+            model.bank_accounts = result.unwrap_or_default();
+        }
+
+        Msg::TagFilterChanged(value) => {
+            model.query.tag = Some(value).filter(|tag| !tag.is_empty());
+            model.remote_call_in_progress = true;
+            fetch_mandates(model.query.clone(), orders);
+        }
+
+        Msg::TagInputChanged(value) => model.tag_input = value,
+
+        Msg::AddTag => {
+            if validate_tag(&model.tag_input).is_ok() {
+                let tag = normalize_tag(&model.tag_input);
+                if let Some(sm) = model.selected_mandate.as_mut() {
+                    if !sm.tags.contains(&tag) {
+                        sm.tags.push(tag);
+                    }
+                }
+                model.tag_input.clear();
+            }
+        }
+
+        Msg::RemoveTag(tag) => {
+            if let Some(sm) = model.selected_mandate.as_mut() {
+                sm.tags.retain(|t| *t != tag);
+            }
         }
 
         Msg::MandateItemSelected(mid) => {
@@ -105,22 +262,58 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
                 model.unsaved_changes_confirmation = Some(Confirmation::Uncomfirmed)
             } else if let Some(mandate) = model.find_mandate_by_id(mid) {
                 model.selected_mandate = Some(mandate.clone());
+                model.history.clear();
+                model.problem = None;
+                model.letter_offer = None;
+                orders.perform_cmd(async move {
+                    Msg::HistoryFetched(mid, api_client::request_mandate_history(mid).await)
+                });
                 // model.unsaved_changes_confirmation = Some(Confirmation::Uncomfirmed);
             }
         }
 
+        Msg::HistoryFetched(mid, result) => {
+            if model.selected_mandate.as_ref().map_or(false, |sm| sm.api_id == mid) {
+                // a new mandate has no history yet
+                model.history = result.unwrap_or_default();
+            }
+        }
+
         Msg::SaveSelectedMandate(status) => {
             model.selected_mandate.as_mut().map(|sm| sm.status = status);
             
             model.selected_mandate.clone().map(|sm| {
                 orders.perform_cmd(async move {
-                    Msg::SelectedMandateSaved(sm.clone(), api_client::save_selected_mandate(sm).await)
+                    let result = if sm.status == Status::DELETED {
+                        api_client::delete_mandate(sm.api_id).await
+                    } else {
+                        api_client::save_selected_mandate(sm.clone()).await
+                    };
+                    Msg::SelectedMandateSaved(sm, result)
                 });
             });
         }
 
         Msg::SelectedMandateSaved(mandate, m) => match m {
             Ok(_) => {
+                model.problem = None;
+                let mid = mandate.api_id;
+                // offer the matching letter when the status has just changed
+                let status_changed =
+                    model.find_mandate_by_id(mid).map(|m| m.status) != Some(mandate.status);
+                model.letter_offer = LetterKind::for_status(mandate.status)
+                    .filter(|_| status_changed)
+                    .map(|kind| (mid, kind));
+                orders.perform_cmd(async move {
+                    Msg::HistoryFetched(mid, api_client::request_mandate_history(mid).await)
+                });
+                orders.perform_cmd(async { Msg::TagsFetched(api_client::request_tags().await) });
+                // saving with a new IBAN creates the account
+                if mandate.bank_account.api_id.is_none() {
+                    orders.perform_cmd(async {
+                        Msg::BankAccountsFetched(api_client::request_bank_accounts().await)
+                    });
+                }
                 model
                     .mandates
                     .iter_mut()
@@ -130,7 +323,15 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
                         *m = mandate;
                     });
             }
-            Err(e) => log!(e),
+            Err(e) => {
+                log!(e);
+                model.problem = e.problem().cloned();
+                // e.g. an illegal status change, keep showing the saved status
+                let saved_status = model.find_mandate_by_id(mandate.api_id).map(|m| m.status);
+                if let (Some(sm), Some(status)) = (model.selected_mandate.as_mut(), saved_status) {
+                    sm.status = status;
+                }
+            }
         },
 
         Msg::NewMandateClicked => {
@@ -138,6 +339,9 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
             new_mandate.api_id = Uuid::new_v4();
             let id = new_mandate.api_id;
             new_mandate.display_name = "new...".to_string();
+            if let Some(primary) = model.bank_accounts.iter().find(|a| a.is_primary) {
+                new_mandate.bank_account = primary.clone();
+            }
             model.mandates.push(new_mandate);
             orders.perform_cmd(async move { Msg::MandateItemSelected(id.clone()) });
         }
@@ -160,14 +364,8 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
             model.selected_mandate.as_mut().map(|sm| {
                 if selected == "ADDNEW" {
                     sm.bank_account = BankAccount::default();
-                } else {
-                    model
-                        .mandates
-                        .iter()
-                        .filter(|a| a.bank_account.iban == selected)
-                        .map(|f| f.bank_account.clone())
-                        .next()
-                        .map(|bac| sm.bank_account = bac);
+                } else if let Some(bac) = model.find_bank_account(&selected) {
+                    sm.bank_account = bac.clone();
                 }
             });
         }
@@ -183,7 +381,89 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
             model
                 .selected_mandate
                 .as_mut()
-                .map(|sm| sm.creditor.sepa_identifier = Some(value));
+                .map(|sm| sm.creditor.sepa_identifier = Some(value).filter(|v| !v.is_empty()));
+        }
+
+        Msg::SchemeChanged(value) => {
+            if let (Some(sm), Ok(scheme)) = (model.selected_mandate.as_mut(), value.parse()) {
+                sm.scheme = scheme;
+            }
+        }
+
+        Msg::PaymentTypeChanged(value) => {
+            if let (Some(sm), Ok(payment_type)) = (model.selected_mandate.as_mut(), value.parse()) {
+                sm.payment_type = payment_type;
+            }
+        }
+
+        Msg::SignatureDateChanged(value) => {
+            model.selected_mandate.as_mut().map(|sm| {
+                sm.signature_date = chrono::NaiveDate::parse_from_str(&value, "%Y-%m-%d").ok()
+            });
+        }
+
+        Msg::LastCollectionDateChanged(value) => {
+            model.selected_mandate.as_mut().map(|sm| {
+                sm.last_collection_date = chrono::NaiveDate::parse_from_str(&value, "%Y-%m-%d").ok()
+            });
+        }
+
+        Msg::SignaturePlaceChanged(value) => {
+            model
+                .selected_mandate
+                .as_mut()
+                .map(|sm| sm.signature_place = Some(value).filter(|v| !v.trim().is_empty()));
+        }
+
+        Msg::AccountHolderChanged(value) => {
+            model
+                .selected_mandate
+                .as_mut()
+                .map(|sm| sm.account_holder = Some(value).filter(|v| !v.trim().is_empty()));
+        }
+
+        Msg::FrequencyChanged(value) => {
+            if let Some(sm) = model.selected_mandate.as_mut() {
+                sm.schedule = match (value.parse::<Frequency>(), sm.schedule.take()) {
+                    (Err(_), _) => None,
+                    (Ok(frequency), Some(schedule)) => Some(CollectionSchedule {
+                        frequency,
+                        interval_months: schedule
+                            .interval_months
+                            .filter(|_| frequency == Frequency::Custom),
+                        ..schedule
+                    }),
+                    // collected from the signature date on unless another date is entered
+                    (Ok(frequency), None) => Some(CollectionSchedule {
+                        amount: Decimal::ZERO,
+                        frequency,
+                        interval_months: None,
+                        anchor_date: sm
+                            .signature_date
+                            .unwrap_or_else(|| chrono::Local::now().naive_local().date()),
+                    }),
+                };
+            }
+        }
+
+        Msg::ExpectedAmountChanged(value) => {
+            let amount = Decimal::from_str(&value.trim().replace(',', "."));
+            if let (Some(schedule), Ok(amount)) = (model.selected_schedule_mut(), amount) {
+                schedule.amount = amount;
+            }
+        }
+
+        Msg::IntervalMonthsChanged(value) => {
+            if let Some(schedule) = model.selected_schedule_mut() {
+                schedule.interval_months = value.trim().parse().ok();
+            }
+        }
+
+        Msg::AnchorDateChanged(value) => {
+            let date = chrono::NaiveDate::parse_from_str(&value, "%Y-%m-%d");
+            if let (Some(schedule), Ok(date)) = (model.selected_schedule_mut(), date) {
+                schedule.anchor_date = date;
+            }
         }
 
         Msg::CreditorStreetChanged(value) => {
@@ -235,6 +515,117 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
                 .map(|sm| sm.bank_account.bic = Some(value));
         }
         Msg::ConfirmUnsavedChanges(conf) => model.unsaved_changes_confirmation = Some(conf),
+
+        Msg::AccountSwitchClicked => {
+            model.problem = None;
+            model.account_switch_result = None;
+            model.account_switch = Some(AccountSwitch {
+                from: model
+                    .bank_accounts
+                    .iter()
+                    .find(|a| a.is_primary)
+                    .and_then(|a| a.api_id)
+                    .unwrap_or_default(),
+                to: BankAccount::default(),
+            });
+        }
+
+        Msg::SwitchFromChanged(selected) => {
+            let from = model.find_bank_account(&selected).and_then(|a| a.api_id);
+            if let (Some(switch), Some(from)) = (model.account_switch.as_mut(), from) {
+                switch.from = from;
+            }
+        }
+
+        Msg::SwitchToChanged(selected) => {
+            let to = model
+                .find_bank_account(&selected)
+                .cloned()
+                .unwrap_or_default();
+            model.account_switch.as_mut().map(|switch| switch.to = to);
+        }
+
+        Msg::SwitchToInstitutionChanged(value) => {
+            model
+                .account_switch
+                .as_mut()
+                .map(|switch| switch.to.institution = value);
+        }
+
+        Msg::SwitchToIbanChanged(value) => {
+            model
+                .account_switch
+                .as_mut()
+                .map(|switch| switch.to.iban = value);
+        }
+
+        Msg::SwitchToBicChanged(value) => {
+            model
+                .account_switch
+                .as_mut()
+                .map(|switch| switch.to.bic = Some(value).filter(|v| !v.is_empty()));
+        }
+
+        Msg::SwitchAccount => {
+            model.account_switch.clone().map(|switch| {
+                orders.perform_cmd(async move {
+                    Msg::AccountSwitched(api_client::switch_bank_account(switch).await)
+                });
+            });
+        }
+
+        Msg::AccountSwitched(result) => match result {
+            Ok(result) => {
+                model.problem = None;
+                model.account_switch = None;
+                // the listed and the edited copies must not write back the old account
+                let moved = |api_id: Uuid| result.mandates.iter().any(|m| m.api_id == api_id);
+                for mandate in model.mandates.iter_mut().filter(|m| moved(m.api_id)) {
+                    mandate.bank_account = result.to.clone();
+                }
+                if let Some(sm) = model.selected_mandate.as_mut().filter(|sm| moved(sm.api_id)) {
+                    sm.bank_account = result.to.clone();
+                }
+                model.account_switch_result = Some(result);
+                orders.perform_cmd(async {
+                    Msg::BankAccountsFetched(api_client::request_bank_accounts().await)
+                });
+            }
+            Err(e) => {
+                log!(e);
+                model.problem = e.problem().cloned();
+            }
+        },
+
+        Msg::CloseAccountSwitch => {
+            model.account_switch = None;
+            model.account_switch_result = None;
+            model.problem = None;
+        }
+
+        Msg::DownloadLetter(api_id, kind, format) => {
+            let query = LetterQuery {
+                format,
+                ..Default::default()
+            };
+            orders.perform_cmd(async move {
+                Msg::LetterDownloaded(api_client::download_letter(api_id, kind, query).await)
+            });
+        }
+
+        Msg::LetterDownloaded(result) => {
+            if let Err(e) = result {
+                log!("Letter download failed", e);
+            }
+        }
+
+        Msg::DownloadMandateForm(api_id) => {
+            orders.perform_cmd(async move {
+                Msg::LetterDownloaded(api_client::download_mandate_form(api_id).await)
+            });
+        }
+
+        Msg::DismissLetterOffer => model.letter_offer = None,
     };
 }
 
@@ -262,11 +653,53 @@ fn view_mandate_list(model: &Model) -> Node<Msg> {
                     C!["control has-icons-left"],
                     input![
                         C!["input"],
-                        attrs! {At::Value => "", At::Type => "text", At::Placeholder => "Search mandates"},
+                        attrs! {
+                            At::Value => model.query.q.clone().unwrap_or_default(),
+                            At::Type => "text",
+                            At::Placeholder => "Search mandates"
+                        },
+                        input_ev(Ev::Input, Msg::SearchChanged),
                     ],
                     span![C!["icon", "is-left"], i![C!["fas", "fa-search"]]],
                 ],
             ],
+            // status filter
+            div![
+                C!["panel-block"],
+                div![
+                    C!["select", "is-fullwidth"],
+                    select![
+                        option![attrs! {At::Value => ""}, "All statuses"],
+                        STATUS_FILTER.iter().map(|status| {
+                            let value: &'static str = status.into();
+                            option![
+                                IF!(model.query.status == Some(*status) => attrs! {At::Selected => ""}),
+                                attrs! {At::Value => value},
+                                value
+                            ]
+                        }),
+                        input_ev(Ev::Change, Msg::StatusFilterChanged),
+                    ]
+                ]
+            ],
+            // tag filter
+            div![
+                C!["panel-block"],
+                div![
+                    C!["select", "is-fullwidth"],
+                    select![
+                        option![attrs! {At::Value => ""}, "All tags"],
+                        model.tags.iter().map(|tag| {
+                            option![
+                                IF!(model.query.tag.as_ref() == Some(&tag.tag) => attrs! {At::Selected => ""}),
+                                attrs! {At::Value => tag.tag},
+                                format!("{} ({})", tag.tag, tag.count)
+                            ]
+                        }),
+                        input_ev(Ev::Change, Msg::TagFilterChanged),
+                    ]
+                ]
+            ],
             div![
                 C!["scrollable-list"],
                 if model.remote_call_in_progress {
@@ -279,6 +712,14 @@ fn view_mandate_list(model: &Model) -> Node<Msg> {
                         .collect()
                 }
             ],
+            IF!(model.next_cursor.is_some() => div![
+                C!["panel-block"],
+                button![
+                    C!["button", "is-light", "is-fullwidth"],
+                    ev(Ev::Click, |_| Msg::LoadMoreMandates),
+                    "Load more"
+                ]
+            ]),
             div![
                 C!["panel-block"],
                 button![
@@ -286,8 +727,206 @@ fn view_mandate_list(model: &Model) -> Node<Msg> {
                     ev(Ev::Click, |_| Msg::NewMandateClicked),
                     "Create New Mandate"
                 ]
+            ],
+            IF!(!model.bank_accounts.is_empty() => div![
+                C!["panel-block"],
+                button![
+                    C!["button", "is-light", "is-fullwidth"],
+                    ev(Ev::Click, |_| Msg::AccountSwitchClicked),
+                    "Switch Bank Account"
+                ]
+            ])
+        ],
+        view_account_switch(model),
+    ]
+}
+
+fn view_account_switch(model: &Model) -> Node<Msg> {
+    let content = match (&model.account_switch, &model.account_switch_result) {
+        (Some(switch), _) => view_account_switch_form(model, switch),
+        (None, Some(result)) => view_account_switch_result(result),
+        (None, None) => return empty![],
+    };
+    div![
+        C!["modal", "is-active"],
+        div![C!["modal-background"], ev(Ev::Click, |_| Msg::CloseAccountSwitch)],
+        div![C!["modal-content"], div![C!["box"], content]],
+        button![
+            C!["modal-close", "is-large"],
+            attrs! {At::from("aria-label") => "close"},
+            ev(Ev::Click, |_| Msg::CloseAccountSwitch)
+        ],
+    ]
+}
+
+fn view_account_switch_form(model: &Model, switch: &AccountSwitch) -> Vec<Node<Msg>> {
+    let invalid = switch.to.validate().is_err() || switch.to.api_id == Some(switch.from);
+    vec![
+        h2![C!["subtitle"], "Switch bank account"],
+        p![
+            C!["block"],
+            "All active mandates of the old account are moved to the new one."
+        ],
+        div![
+            C!["field"],
+            label![C!["label"], "Old account"],
+            div![
+                C!["select", "is-fullwidth"],
+                select![
+                    model.bank_accounts.iter().map(|a| {
+                        option![
+                            IF!(a.api_id == Some(switch.from) => attrs! {At::Selected => ""}),
+                            attrs! {At::Value => a.api_id.map(|id| id.to_string()).unwrap_or_default()},
+                            a.label()
+                        ]
+                    }),
+                    input_ev(Ev::Change, Msg::SwitchFromChanged),
+                ]
             ]
-        ]
+        ],
+        div![
+            C!["field"],
+            label![C!["label"], "New account"],
+            div![
+                C!["select", "is-fullwidth"],
+                select![
+                    model
+                        .bank_accounts
+                        .iter()
+                        .filter(|a| a.api_id != Some(switch.from))
+                        .map(|a| {
+                            option![
+                                IF!(a.api_id.is_some() && a.api_id == switch.to.api_id => attrs! {At::Selected => ""}),
+                                attrs! {At::Value => a.api_id.map(|id| id.to_string()).unwrap_or_default()},
+                                a.label()
+                            ]
+                        }),
+                    option![
+                        IF!(switch.to.api_id.is_none() => attrs! {At::Selected => ""}),
+                        attrs! {At::Value => "ADDNEW"},
+                        "--- Add New Bank Account ---"
+                    ],
+                    input_ev(Ev::Change, Msg::SwitchToChanged),
+                ]
+            ]
+        ],
+        IF!(switch.to.api_id.is_none() => div![
+            div![
+                C!["field"],
+                label![C!["label"], "Bank/Institution"],
+                div![
+                    C!["control"],
+                    input![
+                        C!["input"],
+                        attrs! {At::Value => switch.to.institution},
+                        input_ev(Ev::Input, Msg::SwitchToInstitutionChanged),
+                    ],
+                ],
+                view_field_errors(model.problem.as_ref(), "to.institution"),
+            ],
+            div![
+                C!["field"],
+                label![C!["label"], "IBAN"],
+                div![
+                    C!["control"],
+                    input![
+                        C!["input"],
+                        attrs! {
+                            At::Value => switch.to.iban,
+                            At::Placeholder => "DE____________________"
+                        },
+                        input_ev(Ev::Input, Msg::SwitchToIbanChanged),
+                    ],
+                ],
+                view_iban_help(&switch.to.iban),
+                view_field_errors(model.problem.as_ref(), "to.iban"),
+            ],
+            div![
+                C!["field"],
+                label![C!["label"], "BIC (Optional)"],
+                div![
+                    C!["control"],
+                    input![
+                        C!["input"],
+                        attrs! {At::Value => switch.to.bic.clone().unwrap_or_default()},
+                        input_ev(Ev::Input, Msg::SwitchToBicChanged),
+                    ],
+                ],
+                view_field_errors(model.problem.as_ref(), "to.bic"),
+            ],
+        ]),
+        view_problem(model.problem.as_ref()),
+        div![
+            C!["field", "is-grouped"],
+            div![
+                C!["control"],
+                button![
+                    IF!(invalid => attrs! {At::Disabled => ""}),
+                    C!["button", "is-success"],
+                    "Move mandates",
+                    ev(Ev::Click, |_| Msg::SwitchAccount),
+                ]
+            ],
+            div![
+                C!["control"],
+                button![
+                    C!["button", "is-light"],
+                    "Cancel",
+                    ev(Ev::Click, |_| Msg::CloseAccountSwitch),
+                ]
+            ],
+        ],
+    ]
+}
+
+fn view_account_switch_result(result: &AccountSwitchResult) -> Vec<Node<Msg>> {
+    if result.mandates.is_empty() {
+        return vec![p![
+            C!["notification", "is-info", "is-light"],
+            format!("{} has no active mandates, nothing was moved.", result.from.label())
+        ]];
+    }
+    vec![
+        p![
+            C!["notification", "is-success", "is-light"],
+            format!(
+                "{} mandate(s) moved to {}. Please let these creditors know your new IBAN {}:",
+                result.mandates.len(),
+                result.to.label(),
+                result.to.iban
+            )
+        ],
+        table![
+            C!["table", "is-fullwidth", "is-striped"],
+            thead![tr![
+                th!["Creditor"],
+                th!["Creditor ID"],
+                th!["Mandate reference"],
+                th!["Letter"]
+            ]],
+            tbody![result.mandates.iter().map(|m| {
+                let api_id = m.api_id;
+                tr![
+                    td![&m.creditor.name],
+                    td![m.creditor.sepa_identifier.clone().unwrap_or_default()],
+                    td![m.unique_reference.clone().unwrap_or_else(|| m.display_name.clone())],
+                    td![button![
+                        C!["button", "is-small", "is-link", "is-light"],
+                        span![C!["icon"], i![C!["fas", "fa-file-pdf"]]],
+                        ev(Ev::Click, move |_| Msg::DownloadLetter(
+                            api_id,
+                            LetterKind::AccountChange,
+                            LetterFormat::Pdf
+                        ))
+                    ]],
+                ]
+            })],
+        ],
+        button![
+            C!["button", "is-link"],
+            "Done",
+            ev(Ev::Click, |_| Msg::CloseAccountSwitch)
+        ],
     ]
 }
 
@@ -357,7 +996,8 @@ fn view_selected_mandate_details(model: &Model) -> Node<Msg> {
                         input_ev(Ev::Input, move |dname| { Msg::DisplayNameChanged(dname) }),
                     ],
                     view_validation_icon(mandate, "display_name"),
-                ]
+                ],
+                view_field_errors(model.problem.as_ref(), "display_name"),
             ],
             div![
                 C!["field"],
@@ -374,8 +1014,10 @@ fn view_selected_mandate_details(model: &Model) -> Node<Msg> {
                         }),
                     ],
                     view_validation_icon(mandate, "unique_reference"),
-                ]
+                ],
+                view_field_errors(model.problem.as_ref(), "unique_reference"),
             ],
+            view_tag_editor(model, mandate),
             // Creditor
             div![
                 C!["box"],
@@ -406,7 +1048,8 @@ fn view_selected_mandate_details(model: &Model) -> Node<Msg> {
                                         i![C!["fas", "fa-university"]]
                                     ],
                                     view_validation_icon(&mandate.creditor, "name"),
-                                ]
+                                ],
+                                view_field_errors(model.problem.as_ref(), "creditor.name"),
                             ],
                         ],
                         // creditor identifier
@@ -426,7 +1069,9 @@ fn view_selected_mandate_details(model: &Model) -> Node<Msg> {
                                     input_ev(Ev::Input, move |value| {
                                         Msg::CreditorSepaIdentChanged(value)
                                     }),
-                                ]
+                                ],
+                                view_creditor_identifier_help(mandate.creditor.sepa_identifier.as_deref()),
+                                view_field_errors(model.problem.as_ref(), "creditor.sepa_identifier"),
                             ]
                         ]
                     ]
@@ -458,7 +1103,8 @@ fn view_selected_mandate_details(model: &Model) -> Node<Msg> {
                                         i![C!["fas", "fa-map-marker-alt"]]
                                     ],
                                     view_validation_icon(&mandate.creditor.address, "street"),
-                                ]
+                                ],
+                                view_field_errors(model.problem.as_ref(), "creditor.address.street"),
                             ],
                         ],
                         // Hause number
@@ -483,7 +1129,8 @@ fn view_selected_mandate_details(model: &Model) -> Node<Msg> {
                                         i![C!["fas", "fa-map-marker-alt"]]
                                     ],
                                     view_validation_icon(&mandate.creditor.address, "house_number"),
-                                ]
+                                ],
+                                view_field_errors(model.problem.as_ref(), "creditor.address.house_number"),
                             ]
                         ]
                     ]
@@ -514,7 +1161,8 @@ fn view_selected_mandate_details(model: &Model) -> Node<Msg> {
                                         i![C!["fas", "fa-map-marker-alt"]]
                                     ],
                                     view_validation_icon(&mandate.creditor.address, "zip"),
-                                ]
+                                ],
+                                view_field_errors(model.problem.as_ref(), "creditor.address.zip"),
                             ],
                         ],
                         // Place
@@ -539,7 +1187,8 @@ fn view_selected_mandate_details(model: &Model) -> Node<Msg> {
                                         i![C!["fas", "fa-map-marker-alt"]]
                                     ],
                                     view_validation_icon(&mandate.creditor.address, "place"),
-                                ]
+                                ],
+                                view_field_errors(model.problem.as_ref(), "creditor.address.place"),
                             ]
                         ]
                     ]
@@ -556,19 +1205,20 @@ fn view_selected_mandate_details(model: &Model) -> Node<Msg> {
                         div![
                             C!["select"],
                             select![
-                                model.get_bank_accounts().iter().map(|m| {
+                                model.bank_accounts.iter().map(|m| {
                                     option![
-                                        if m.iban == mandate.bank_account.iban {
+                                        if m.api_id.is_some() && m.api_id == mandate.bank_account.api_id {
                                             attrs! {At::Selected => ""}
                                         } else {
                                             attrs! {At::Alt => ""}
                                         },
-                                        attrs! {At::Value => m.iban.clone()},
-                                        format!("{} - {}", m.institution, m.iban)
+                                        attrs! {At::Value => m.api_id.map(|id| id.to_string()).unwrap_or_default()},
+                                        m.label()
                                     ]
                                 }),
                                 // option![attrs! {At::Value=>""}, ""],
                                 option![
+                                    IF!(mandate.bank_account.api_id.is_none() => attrs! {At::Selected => ""}),
                                     attrs! {At::Value=>"ADDNEW"},
                                     "--- Add New Bank Account ---"
                                 ],
@@ -579,7 +1229,14 @@ fn view_selected_mandate_details(model: &Model) -> Node<Msg> {
                         ],
                     ],
                 ],
-                div![
+                IF!(mandate.bank_account.api_id.is_some() => p![
+                    C!["help"],
+                    format!(
+                        "Account holder: {}. Changes to a stored account apply to all of its mandates.",
+                        mandate.bank_account.holder_name.clone().unwrap_or_default()
+                    )
+                ]),
+                IF!(mandate.bank_account.api_id.is_none() => div![
                     div![
                         C!["field"],
                         label![C!["label"], "Bank/Institution"],
@@ -595,7 +1252,8 @@ fn view_selected_mandate_details(model: &Model) -> Node<Msg> {
                                 }),
                             ],
                             view_validation_icon(&mandate.bank_account, "insitution"),
-                        ]
+                        ],
+                        view_field_errors(model.problem.as_ref(), "bank_account.institution"),
                     ],
                     div![
                         C!["field"],
@@ -613,7 +1271,9 @@ fn view_selected_mandate_details(model: &Model) -> Node<Msg> {
                                 }),
                             ],
                             view_validation_icon(&mandate.bank_account, "iban"),
-                        ]
+                        ],
+                        view_iban_help(&mandate.bank_account.iban),
+                        view_field_errors(model.problem.as_ref(), "bank_account.iban"),
                     ],
                     div![
                         C!["field"],
@@ -631,10 +1291,13 @@ fn view_selected_mandate_details(model: &Model) -> Node<Msg> {
                                 }),
                             ],
                             view_validation_icon(&mandate.bank_account, "bic"),
-                        ]
+                        ],
+                        view_field_errors(model.problem.as_ref(), "bank_account.bic"),
                     ],
-                ],
+                ]),
             ],
+            view_signature(model, mandate),
+            view_schedule(model, mandate),
             div![
                 C!["field"],
                 label![
@@ -649,47 +1312,467 @@ fn view_selected_mandate_details(model: &Model) -> Node<Msg> {
                 C!["field"],
                 label![C!["label"], format!("Status: {:?}", mandate.status)],
             ],
+            view_history(&model.history),
+            view_letter_offer(model.letter_offer),
+            view_problem(model.problem.as_ref()),
             // buttons
+            view_buttons(model, mandate),
+        ]
+    } else {
+        section![
+            C!["hero", "is-medium", "ml-6"],
+            div![
+                C!["hero-body", "has-text-centered"],
+                h2![
+                    C!["subtitle", "is-size-3"],
+                    "Please select or create mandate."
+                ]
+            ]
+        ]
+    }
+}
+
+/// Scheme, payment type and who signed when and where.
+fn view_signature(model: &Model, mandate: &Mandate) -> Node<Msg> {
+    div![
+        C!["box"],
+        label![C!["label"], "Mandate details"],
+        nav![
+            C!["level"],
             div![
-                C!["field", "is-grouped", "section"],
+                C!["level-left"],
                 div![
-                    C!["control"],
-                    button![
-                        IF!(mandate.validate().is_err() => attrs!{ At::Disabled => ""}),
-                        C!["button", "is-success"],
-                        "Save",
-                        ev(Ev::Click, |_| Msg::SaveSelectedMandate(Status::ACTIVE)),
+                    C!["level-item"],
+                    div![
+                        C!["field"],
+                        label![C!["label"], "Scheme"],
+                        div![
+                            C!["select"],
+                            select![
+                                [Scheme::CORE, Scheme::B2B].iter().map(|scheme| {
+                                    let value: &'static str = scheme.into();
+                                    option![
+                                        IF!(mandate.scheme == *scheme => attrs! {At::Selected => ""}),
+                                        attrs! {At::Value => value},
+                                        value
+                                    ]
+                                }),
+                                input_ev(Ev::Change, Msg::SchemeChanged),
+                            ]
+                        ],
+                        validate_scheme(mandate)
+                            .err()
+                            .and_then(|error| error.message)
+                            .map(|message| p![C!["help", "is-danger"], message.to_string()]),
                     ]
                 ],
                 div![
-                    C!["control"],
-                    button![
-                        C!["button", "is-danger"],
-                        "Delete",
-                        ev(Ev::Click, |_| Msg::SaveSelectedMandate(Status::DELETED)),
+                    C!["level-item"],
+                    div![
+                        C!["field"],
+                        label![C!["label"], "Payment type"],
+                        div![
+                            C!["select"],
+                            select![
+                                [
+                                    (PaymentType::Recurrent, "Recurrent"),
+                                    (PaymentType::OneOff, "One-off")
+                                ]
+                                .iter()
+                                .map(|(payment_type, label)| {
+                                    let value: &'static str = payment_type.into();
+                                    option![
+                                        IF!(mandate.payment_type == *payment_type => attrs! {At::Selected => ""}),
+                                        attrs! {At::Value => value},
+                                        *label
+                                    ]
+                                }),
+                                input_ev(Ev::Change, Msg::PaymentTypeChanged),
+                            ]
+                        ],
                     ]
                 ],
+            ]
+        ],
+        nav![
+            C!["level"],
+            div![
+                C!["level-left"],
                 div![
-                    C!["control"],
-                    button![
-                        C!["button", "is-warning"],
-                        "Print cancelation form",
-                        ev(Ev::Click, |_| Msg::SaveSelectedMandate(Status::CANCELED)),
+                    C!["level-item"],
+                    div![
+                        C!["field"],
+                        label![C!["label"], "Signed on (optional)"],
+                        div![
+                            C!["control"],
+                            input![
+                                C!["input"],
+                                attrs! {
+                                    At::Type => "date",
+                                    At::Value => mandate.signature_date.map(|d| d.to_string()).unwrap_or_default()
+                                },
+                                input_ev(Ev::Change, Msg::SignatureDateChanged),
+                            ],
+                        ],
+                        view_field_errors(model.problem.as_ref(), "signature_date"),
+                    ]
+                ],
+                div![
+                    C!["level-item"],
+                    div![
+                        C!["field"],
+                        label![C!["label"], "Signed at (optional)"],
+                        div![
+                            C!["control", "has-icons-right"],
+                            input![
+                                C!["input"],
+                                attrs! {
+                                    At::Value => mandate.signature_place.clone().unwrap_or_default()
+                                },
+                                input_ev(Ev::Input, Msg::SignaturePlaceChanged),
+                            ],
+                            view_validation_icon(mandate, "signature_place"),
+                        ],
+                        view_field_errors(model.problem.as_ref(), "signature_place"),
                     ]
                 ],
             ]
-        ]
-    } else {
-        section![
-            C!["hero", "is-medium", "ml-6"],
+        ],
+        div![
+            C!["field"],
+            label![C!["label"], "Signed by (optional)"],
             div![
-                C!["hero-body", "has-text-centered"],
-                h2![
-                    C!["subtitle", "is-size-3"],
-                    "Please select or create mandate."
+                C!["control", "has-icons-right"],
+                input![
+                    C!["input"],
+                    attrs! {
+                        At::Value => mandate.account_holder.clone().unwrap_or_default(),
+                        At::Placeholder => mandate.bank_account.holder_name.clone().unwrap_or_default()
+                    },
+                    input_ev(Ev::Input, Msg::AccountHolderChanged),
+                ],
+                view_validation_icon(mandate, "account_holder"),
+            ],
+            p![C!["help"], "Only needed when someone other than the account holder signed."],
+            view_field_errors(model.problem.as_ref(), "account_holder"),
+        ],
+        div![
+            C!["field"],
+            label![C!["label"], "Last collected on (optional)"],
+            div![
+                C!["control"],
+                input![
+                    C!["input"],
+                    attrs! {
+                        At::Type => "date",
+                        At::Value => mandate.last_collection_date.map(|d| d.to_string()).unwrap_or_default()
+                    },
+                    input_ev(Ev::Change, Msg::LastCollectionDateChanged),
+                ],
+            ],
+            p![
+                C!["help"],
+                match mandate.last_collection_date {
+                    Some(date) => format!(
+                        "Updated by imported statements, the mandate expires after {}.",
+                        usable_until(date)
+                    ),
+                    None => "Updated by imported statements. A mandate expires 36 months after its last debit.".to_string(),
+                }
+            ],
+            view_field_errors(model.problem.as_ref(), "last_collection_date"),
+        ],
+    ]
+}
+
+/// Expected amount and due dates, for the forecast on the home page.
+fn view_schedule(model: &Model, mandate: &Mandate) -> Node<Msg> {
+    let schedule = mandate.schedule.as_ref();
+    let frequencies = [
+        (Frequency::Monthly, "Monthly"),
+        (Frequency::Quarterly, "Quarterly"),
+        (Frequency::Yearly, "Yearly"),
+        (Frequency::Custom, "Every ... months"),
+    ];
+    div![
+        C!["box"],
+        label![C!["label"], "Expected collections"],
+        nav![
+            C!["level"],
+            div![
+                C!["level-left"],
+                div![
+                    C!["level-item"],
+                    div![
+                        C!["field"],
+                        label![C!["label"], "Frequency"],
+                        div![
+                            C!["select"],
+                            select![
+                                option![attrs! {At::Value => ""}, "Not planned"],
+                                frequencies.iter().map(|(frequency, label)| {
+                                    let value: &'static str = frequency.into();
+                                    option![
+                                        IF!(schedule.map(|s| s.frequency) == Some(*frequency) => attrs! {At::Selected => ""}),
+                                        attrs! {At::Value => value},
+                                        *label
+                                    ]
+                                }),
+                                input_ev(Ev::Change, Msg::FrequencyChanged),
+                            ]
+                        ],
+                    ]
+                ],
+                schedule.filter(|s| s.frequency == Frequency::Custom).map(|s| div![
+                    C!["level-item"],
+                    div![
+                        C!["field"],
+                        label![C!["label"], "Months"],
+                        div![
+                            C!["control", "has-icons-right"],
+                            input![
+                                C!["input"],
+                                attrs! {
+                                    At::Type => "number",
+                                    At::Min => "1",
+                                    At::Max => "120",
+                                    At::Value => s.interval_months.map(|m| m.to_string()).unwrap_or_default()
+                                },
+                                input_ev(Ev::Input, Msg::IntervalMonthsChanged),
+                            ],
+                            view_validation_icon(s, "interval_months"),
+                        ],
+                        view_field_errors(model.problem.as_ref(), "schedule.interval_months"),
+                    ]
+                ]),
+                schedule.map(|s| div![
+                    C!["level-item"],
+                    div![
+                        C!["field"],
+                        label![C!["label"], "Amount (EUR)"],
+                        div![
+                            C!["control", "has-icons-right"],
+                            input![
+                                C!["input"],
+                                attrs! {
+                                    At::Value => s.amount.to_string(),
+                                    At::Placeholder => "0.00"
+                                },
+                                input_ev(Ev::Change, Msg::ExpectedAmountChanged),
+                            ],
+                            view_validation_icon(s, "amount"),
+                        ],
+                        view_field_errors(model.problem.as_ref(), "schedule.amount"),
+                    ]
+                ]),
+                schedule.map(|s| div![
+                    C!["level-item"],
+                    div![
+                        C!["field"],
+                        label![C!["label"], "A due date"],
+                        div![
+                            C!["control"],
+                            input![
+                                C!["input"],
+                                attrs! {
+                                    At::Type => "date",
+                                    At::Value => s.anchor_date.to_string()
+                                },
+                                input_ev(Ev::Change, Msg::AnchorDateChanged),
+                            ],
+                        ],
+                    ]
+                ]),
+            ]
+        ],
+    ]
+}
+
+fn view_tag_editor(model: &Model, mandate: &Mandate) -> Node<Msg> {
+    let input_error = validate_tag(&model.tag_input)
+        .err()
+        .filter(|_| !model.tag_input.trim().is_empty())
+        .and_then(|e| e.message);
+    div![
+        C!["field"],
+        label![C!["label"], "Tags"],
+        div![
+            C!["tags"],
+            mandate.tags.iter().map(|tag| {
+                let removed = tag.clone();
+                span![
+                    C!["tag", "is-info", "is-light"],
+                    tag,
+                    button![
+                        C!["delete", "is-small"],
+                        ev(Ev::Click, move |_| Msg::RemoveTag(removed))
+                    ]
+                ]
+            })
+        ],
+        div![
+            C!["control"],
+            input![
+                C!["input"],
+                attrs! {
+                    At::Value => model.tag_input,
+                    At::Placeholder => "Add a tag, e.g. household",
+                    At::from("list") => "tag-suggestions"
+                },
+                input_ev(Ev::Input, Msg::TagInputChanged),
+                keyboard_ev(Ev::KeyDown, |event| IF!(event.key() == "Enter" => Msg::AddTag)),
+            ],
+            datalist![
+                id!["tag-suggestions"],
+                model
+                    .tags
+                    .iter()
+                    .filter(|t| !mandate.tags.contains(&t.tag))
+                    .map(|t| option![attrs! {At::Value => t.tag}])
+            ]
+        ],
+        input_error.map(|message| p![C!["help", "is-danger"], message.to_string()]),
+        view_field_errors(model.problem.as_ref(), "tags"),
+    ]
+}
+
+fn view_buttons(model: &Model, mandate: &Mandate) -> Node<Msg> {
+    // transitions start from the saved status, not from the edited copy
+    let status = model
+        .find_mandate_by_id(mandate.api_id)
+        .map_or(mandate.status, |m| m.status);
+    let invalid = mandate.validate().is_err();
+    let api_id = mandate.api_id;
+    div![
+        C!["field", "is-grouped", "section"],
+        div![
+            C!["control"],
+            button![
+                IF!(invalid => attrs!{ At::Disabled => ""}),
+                C!["button", "is-success"],
+                "Save",
+                ev(Ev::Click, move |_| Msg::SaveSelectedMandate(status)),
+            ]
+        ],
+        status.transitions().iter().map(|next| {
+            let next = *next;
+            let (label, class) = match (status, next) {
+                (Status::SUSPENDED, Status::ACTIVE) => ("Resume", "is-success is-light"),
+                (_, Status::ACTIVE) => ("Activate", "is-success is-light"),
+                (_, Status::SUSPENDED) => ("Suspend", "is-warning is-light"),
+                (_, Status::CANCELED) => ("Cancel mandate", "is-warning"),
+                (_, Status::EXPIRED) => ("Mark as expired", "is-warning is-light"),
+                (_, Status::DELETED) => ("Delete", "is-danger"),
+                (_, Status::NEW) => ("Reset", "is-light"),
+            };
+            div![
+                C!["control"],
+                button![
+                    IF!(invalid => attrs!{ At::Disabled => ""}),
+                    C!["button", class],
+                    label,
+                    ev(Ev::Click, move |_| Msg::SaveSelectedMandate(next)),
                 ]
             ]
+        }),
+        div![
+            C!["control"],
+            button![
+                // the form is filled from the saved mandate
+                IF!(mandate.date_created.is_none() => attrs!{ At::Disabled => ""}),
+                C!["button", "is-link", "is-light"],
+                span![C!["icon"], i![C!["fas", "fa-file-pdf"]]],
+                span!["Mandate form"],
+                ev(Ev::Click, move |_| Msg::DownloadMandateForm(api_id)),
+            ]
+        ],
+    ]
+}
+
+fn view_letter_offer(offer: Option<(Uuid, LetterKind)>) -> Node<Msg> {
+    let (api_id, kind) = match offer {
+        Some(offer) => offer,
+        None => return empty![],
+    };
+    let text = match kind {
+        LetterKind::Revocation => "The mandate is canceled. Send the creditor a revocation letter:",
+        LetterKind::AccountChange => "Tell the creditor about your new bank account:",
+        LetterKind::RefundRequest => "Ask the creditor to refund the debit:",
+    };
+    div![
+        C!["notification", "is-info", "is-light"],
+        button![C!["delete"], ev(Ev::Click, |_| Msg::DismissLetterOffer)],
+        p![C!["mb-2"], text],
+        div![
+            C!["buttons"],
+            button![
+                C!["button", "is-small", "is-link"],
+                span![C!["icon"], i![C!["fas", "fa-file-pdf"]]],
+                span!["PDF"],
+                ev(Ev::Click, move |_| Msg::DownloadLetter(api_id, kind, LetterFormat::Pdf))
+            ],
+            button![
+                C!["button", "is-small"],
+                span![C!["icon"], i![C!["fas", "fa-file-alt"]]],
+                span!["Text"],
+                ev(Ev::Click, move |_| Msg::DownloadLetter(api_id, kind, LetterFormat::Txt))
+            ],
         ]
+    ]
+}
+
+fn view_history(history: &[MandateChange]) -> Node<Msg> {
+    if history.is_empty() {
+        return empty![];
+    }
+    div![
+        C!["box"],
+        label![C!["label"], "History"],
+        ul![
+            history.iter().rev().map(|change| {
+                let new_value = change
+                    .new_value
+                    .as_ref()
+                    .map(format_history_value)
+                    .unwrap_or_default();
+                li![
+                    C!["mb-2"],
+                    span![
+                        C!["has-text-grey", "mr-2"],
+                        change.date_changed.format("%Y-%m-%d %H:%M UTC").to_string()
+                    ],
+                    span![C!["has-text-weight-semibold", "mr-2"], change.field.as_str()],
+                    change.old_value.as_ref().map(|old| span![
+                        span![C!["has-text-danger"], format_history_value(old)],
+                        " → "
+                    ]),
+                    span![C!["has-text-success"], new_value],
+                ]
+            })
+        ]
+    ]
+}
+
+fn format_history_value(value: &serde_json::Value) -> String {
+    match value.as_str() {
+        Some(text) => text.to_string(),
+        None => value.to_string(),
     }
 }
 
+fn view_iban_help(iban: &str) -> Node<Msg> {
+    if iban.trim().is_empty() {
+        return empty![];
+    }
+    match Iban::parse(iban) {
+        Ok(_) => empty![],
+        Err(error) => p![C!["help", "is-danger"], error.to_string()],
+    }
+}
+
+fn view_creditor_identifier_help(ci: Option<&str>) -> Node<Msg> {
+    match ci.map(CreditorIdentifier::parse) {
+        Some(Err(error)) => p![C!["help", "is-danger"], error.to_string()],
+        _ => empty![],
+    }
+}
@@ -1,8 +1,11 @@
 use seed::{prelude::*, *};
 
-use crate::{page::view_validation_icon, api_client};
+use crate::{
+    api_client::{self, ApiError},
+    page::{view_field_errors, view_problem, view_validation_icon},
+};
 use api_models::{
-    models::{Address, UserProfile},
+    models::{letter::LETTER_LANGUAGES, Address, Problem, UserProfile},
     validator::Validate,
 };
 
@@ -12,6 +15,7 @@ pub struct Model {
     fetching_remote_data: bool,
     saving_remote_data: bool,
     saved_success: bool,
+    problem: Option<Problem>,
 }
 #[derive(Debug)]
 pub enum Msg {
@@ -21,8 +25,13 @@ pub enum Msg {
     ZipChanged(String),
     PlaceChanged(String),
     HouseNoChanged(String),
+    LanguageChanged(String),
+    UpcomingDebitsToggled,
+    DebitAlertsToggled,
+    ProfileRemindersToggled,
+    ExpiredMandatesToggled,
     SaveProfile,
-    ProfileSaved(fetch::Result<Status>),
+    ProfileSaved(Result<Status, ApiError>),
     ProfileFetched(fetch::Result<UserProfile>),
     RemoveNotification,
 }
@@ -34,6 +43,7 @@ pub fn init(_url: Url, orders: &mut impl Orders<Msg>) -> Model {
         fetching_remote_data: true,
         saving_remote_data: false,
         saved_success: false,
+        problem: None,
     }
 }
 
@@ -69,6 +79,25 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
                 .get_or_insert(Address::default())
                 .house_number = value
         }
+        Msg::LanguageChanged(value) => {
+            model.user_profile.preferred_language = Some(value).filter(|v| !v.is_empty())
+        }
+        Msg::UpcomingDebitsToggled => {
+            let notifications = &mut model.user_profile.notifications;
+            notifications.upcoming_debits = !notifications.upcoming_debits;
+        }
+        Msg::DebitAlertsToggled => {
+            let notifications = &mut model.user_profile.notifications;
+            notifications.debit_alerts = !notifications.debit_alerts;
+        }
+        Msg::ProfileRemindersToggled => {
+            let notifications = &mut model.user_profile.notifications;
+            notifications.profile_reminders = !notifications.profile_reminders;
+        }
+        Msg::ExpiredMandatesToggled => {
+            let notifications = &mut model.user_profile.notifications;
+            notifications.expired_mandates = !notifications.expired_mandates;
+        }
         Msg::SaveProfile => {
             model.saving_remote_data = true;
             let up = model.user_profile.clone();
@@ -84,9 +113,15 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
         }
         Msg::ProfileSaved(result) => {
             model.saving_remote_data = false;
-            model.saved_success = true;
-            orders.stream(streams::interval(3000, || Msg::RemoveNotification));
-            log!("profile saved {}", result)
+            log!("profile saved {}", result);
+            match result {
+                Ok(_) => {
+                    model.problem = None;
+                    model.saved_success = true;
+                    orders.stream(streams::interval(3000, || Msg::RemoveNotification));
+                }
+                Err(e) => model.problem = e.problem().cloned(),
+            }
         }
         Msg::RemoveNotification => {
             model.saved_success = false;
@@ -131,7 +166,8 @@ pub fn view(model: &Model) -> Node<Msg> {
                                 ],
                                 span![C!["icon", "is-small", "is-left"], i![C!["fas", "fa-user"]]],
                                 view_validation_icon(profile, "first_name")
-                            ]
+                            ],
+                            view_field_errors(model.problem.as_ref(), "first_name"),
                         ],
                     ],
                     // Last name
@@ -153,7 +189,8 @@ pub fn view(model: &Model) -> Node<Msg> {
                                 ],
                                 span![C!["icon", "is-small", "is-left"], i![C!["fas", "fa-user"]]],
                                 view_validation_icon(profile, "last_name")
-                            ]
+                            ],
+                            view_field_errors(model.problem.as_ref(), "last_name"),
                         ]
                     ]
                 ]
@@ -183,7 +220,8 @@ pub fn view(model: &Model) -> Node<Msg> {
                                     i![C!["fas", "fa-map-marker-alt"]]
                                 ],
                                 view_validation_icon(&address, "street")
-                            ]
+                            ],
+                            view_field_errors(model.problem.as_ref(), "address.street"),
                         ],
                     ],
                     // Hause number
@@ -208,7 +246,8 @@ pub fn view(model: &Model) -> Node<Msg> {
                                     i![C!["fas", "fa-map-marker-alt"]]
                                 ],
                                 view_validation_icon(&address, "house_number")
-                            ]
+                            ],
+                            view_field_errors(model.problem.as_ref(), "address.house_number"),
                         ]
                     ]
                 ]
@@ -238,7 +277,8 @@ pub fn view(model: &Model) -> Node<Msg> {
                                     i![C!["fas", "fa-map-marker-alt"]]
                                 ],
                                 view_validation_icon(&address, "zip")
-                            ]
+                            ],
+                            view_field_errors(model.problem.as_ref(), "address.zip"),
                         ],
                     ],
                     // Place
@@ -262,12 +302,14 @@ pub fn view(model: &Model) -> Node<Msg> {
                                     i![C!["fas", "fa-map-marker-alt"]]
                                 ],
                                 view_validation_icon(&address, "place")
-                            ]
+                            ],
+                            view_field_errors(model.problem.as_ref(), "address.place"),
                         ]
                     ]
                 ]
             ],
         ],
+        view_notifications(profile),
         // buttons
         div![
             C!["field", "is-grouped", "section"],
@@ -286,7 +328,75 @@ pub fn view(model: &Model) -> Node<Msg> {
             ],
             div![C!["control"], button![C!["button", "is-danger"], "Delete"]],
         ],
+        view_problem(model.problem.as_ref()),
         IF!(model.saved_success => div![C!["notification", "is-success", "is-light"],"Profile saved successfully."]),
     ]
 }
 
+
+/// Language of letters and emails, and the emails the user opted in to.
+fn view_notifications(profile: &UserProfile) -> Node<Msg> {
+    let notifications = &profile.notifications;
+    let checkbox = |checked: bool, label: &str, msg: fn() -> Msg| {
+        div![
+            C!["field"],
+            label![
+                C!["checkbox"],
+                input![
+                    attrs! {At::Type => "checkbox", At::Checked => checked.as_at_value()},
+                    ev(Ev::Change, move |_| msg()),
+                ],
+                format!(" {}", label)
+            ]
+        ]
+    };
+    div![
+        C!["box"],
+        div![
+            C!["field"],
+            label![C!["label"], "Language of letters and emails"],
+            div![
+                C!["select"],
+                select![
+                    option![attrs! {At::Value => ""}, "Default"],
+                    LETTER_LANGUAGES.iter().map(|lang| {
+                        option![
+                            IF!(profile.preferred_language.as_deref() == Some(*lang) => attrs! {At::Selected => ""}),
+                            attrs! {At::Value => lang},
+                            lang.to_uppercase()
+                        ]
+                    }),
+                    input_ev(Ev::Change, Msg::LanguageChanged),
+                ]
+            ],
+        ],
+        label![C!["label"], "Email notifications"],
+        match &profile.email {
+            Some(email) => p![C!["help", "mb-3"], format!("Sent to {}", email)],
+            None => p![
+                C!["help", "mb-3"],
+                "Sent to the verified email address of your login, as of the last save."
+            ],
+        },
+        checkbox(
+            notifications.upcoming_debits,
+            "Debits due in the next days",
+            || Msg::UpcomingDebitsToggled
+        ),
+        checkbox(
+            notifications.debit_alerts,
+            "Debits collected after their mandate was canceled or deleted",
+            || Msg::DebitAlertsToggled
+        ),
+        checkbox(
+            notifications.profile_reminders,
+            "Reminder to complete the profile",
+            || Msg::ProfileRemindersToggled
+        ),
+        checkbox(
+            notifications.expired_mandates,
+            "Mandates that expired after 36 months without a debit",
+            || Msg::ExpiredMandatesToggled
+        ),
+    ]
+}
@@ -1,48 +1,182 @@
 import init from '/pkg/package.js';
 
-let auth0 = null;
+// Minimal OpenID Connect client: authorization code flow with PKCE against any provider.
+// The settings come from the backend (`/api/config`).
 
-window.init_auth = async (domain, client_id, audience) => {
-    auth0 = await createAuth0Client({
-        domain,
-        client_id,
-        audience
+const TOKENS_KEY = "oidc.tokens";
+const LOGIN_KEY = "oidc.login";
+
+let config = null;
+
+const base64UrlEncode = (bytes) =>
+    btoa(String.fromCharCode(...new Uint8Array(bytes)))
+        .replace(/\+/g, "-")
+        .replace(/\//g, "_")
+        .replace(/=+$/, "");
+
+const randomString = () => base64UrlEncode(crypto.getRandomValues(new Uint8Array(32)));
+
+const decodeJwtPayload = (jwt) => {
+    const payload = jwt.split(".")[1].replace(/-/g, "+").replace(/_/g, "/");
+    const json = decodeURIComponent(
+        atob(payload)
+            .split("")
+            .map((c) => "%" + ("00" + c.charCodeAt(0).toString(16)).slice(-2))
+            .join("")
+    );
+    return JSON.parse(json);
+};
+
+const loadTokens = () => JSON.parse(sessionStorage.getItem(TOKENS_KEY) || "null");
+
+const storeTokens = (response) => {
+    const previous = loadTokens() || {};
+    const tokens = {
+        access_token: response.access_token,
+        id_token: response.id_token || previous.id_token,
+        refresh_token: response.refresh_token || previous.refresh_token,
+        expires_at: Date.now() + (response.expires_in || 300) * 1000,
+    };
+    sessionStorage.setItem(TOKENS_KEY, JSON.stringify(tokens));
+    return tokens;
+};
+
+const isValid = (tokens) => tokens && tokens.access_token && tokens.expires_at - 30000 > Date.now();
+
+const requestTokens = async (params) => {
+    const response = await fetch(config.token_endpoint, {
+        method: "POST",
+        headers: { "Content-Type": "application/x-www-form-urlencoded" },
+        body: new URLSearchParams({ client_id: config.client_id, ...params }),
     });
-    
+    if (!response.ok) {
+        throw new Error("Token request failed with status " + response.status);
+    }
+    return storeTokens(await response.json());
+};
+
+const refreshTokens = async (tokens) =>
+    requestTokens({ grant_type: "refresh_token", refresh_token: tokens.refresh_token });
 
-    const query = window.location.search;
-    if (query.includes("code=") && query.includes("state=")) {
-      await auth0.handleRedirectCallback();
+// Without an outstanding login the response was handled before, e.g. when the page is reloaded,
+// and is ignored. A provider error, like a canceled login, leaves the user logged out.
+const handleRedirectCallback = async (query) => {
+    const login = JSON.parse(sessionStorage.getItem(LOGIN_KEY) || "null");
+    sessionStorage.removeItem(LOGIN_KEY);
+    if (!login) {
+        return;
+    }
+    if (login.state !== query.get("state")) {
+        throw new Error("Invalid authorization response state");
+    }
+    if (query.has("error")) {
+        console.error("Login failed:", query.get("error_description") || query.get("error"));
+        return;
     }
+    await requestTokens({
+        grant_type: "authorization_code",
+        code: query.get("code"),
+        redirect_uri: window.location.origin,
+        code_verifier: login.verifier,
+    });
+};
+
+const loginWithRedirect = async (extraParams) => {
+    const verifier = randomString();
+    const state = randomString();
+    const challenge = base64UrlEncode(
+        await crypto.subtle.digest("SHA-256", new TextEncoder().encode(verifier))
+    );
+    sessionStorage.setItem(LOGIN_KEY, JSON.stringify({ verifier, state }));
+    const url = new URL(config.authorization_endpoint);
+    const params = {
+        response_type: "code",
+        client_id: config.client_id,
+        redirect_uri: window.location.origin,
+        scope: config.scope,
+        audience: config.audience,
+        state,
+        code_challenge: challenge,
+        code_challenge_method: "S256",
+        ...extraParams,
+    };
+    Object.entries(params).forEach(([key, value]) => url.searchParams.set(key, value));
+    window.location.assign(url.toString());
+};
 
-    if (await auth0.isAuthenticated()) {
-        return await auth0.getUser();
+window.init_auth = async (clientConfig) => {
+    config = clientConfig;
+
+    const query = new URLSearchParams(window.location.search);
+    if (query.has("state") && (query.has("code") || query.has("error"))) {
+        try {
+            await handleRedirectCallback(query);
+        } finally {
+            // a reload or back navigation must not hand the used response in again
+            window.history.replaceState(null, "", window.location.pathname);
+        }
+    }
+
+    let tokens = loadTokens();
+    if (tokens && !isValid(tokens) && tokens.refresh_token) {
+        tokens = await refreshTokens(tokens).catch(() => null);
+    }
+    if (isValid(tokens) && tokens.id_token) {
+        return decodeJwtPayload(tokens.id_token);
     }
 }
 
 window.redirect_to_sign_up = async () => {
-    await auth0.loginWithRedirect({
-        redirect_uri: window.location.origin,
-        screen_hint: "signup"
-    });
+    await loginWithRedirect({ screen_hint: "signup" });
 }
 
 window.redirect_to_log_in = async () => {
-    await auth0.loginWithRedirect({
-        redirect_uri: window.location.origin,
-    });
+    await loginWithRedirect({});
 }
 
 window.logout = () => {
-    auth0.logout({
-        returnTo: window.location.origin
-    });
+    const tokens = loadTokens();
+    sessionStorage.removeItem(TOKENS_KEY);
+    if (config.end_session_endpoint) {
+        const url = new URL(config.end_session_endpoint);
+        url.searchParams.set("client_id", config.client_id);
+        url.searchParams.set("post_logout_redirect_uri", window.location.origin);
+        if (tokens && tokens.id_token) {
+            url.searchParams.set("id_token_hint", tokens.id_token);
+        }
+        window.location.assign(url.toString());
+    } else {
+        window.location.assign(window.location.origin);
+    }
 }
 
 window.getTokenSilently = async () => {
-    return await auth0.getTokenSilently();
-    // return "something";
-    
+    const tokens = loadTokens();
+    if (isValid(tokens)) {
+        return tokens.access_token;
+    }
+    if (tokens && tokens.refresh_token) {
+        return (await refreshTokens(tokens)).access_token;
+    }
+    throw new Error("login_required");
+}
+
+// Downloads need the bearer token, so they are fetched and handed to the browser as a blob.
+window.download_file = async (url, token) => {
+    const response = await fetch(url, { headers: { Authorization: "Bearer " + token } });
+    if (!response.ok) {
+        throw new Error("Download failed with status " + response.status);
+    }
+    const disposition = response.headers.get("Content-Disposition") || "";
+    const match = disposition.match(/filename="([^"]+)"/);
+    const objectUrl = URL.createObjectURL(await response.blob());
+    const link = document.createElement("a");
+    link.href = objectUrl;
+    link.download = match ? match[1] : "download";
+    document.body.appendChild(link);
+    link.click();
+    link.remove();
+    URL.revokeObjectURL(objectUrl);
 }
 
 init('/pkg/package_bg.wasm');